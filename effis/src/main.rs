@@ -21,12 +21,14 @@ use rocket::{
 };
 use rocket_db_pools::Database;
 use todel::{
+    conf::StorageConf,
     http::{Cache, DB},
     ids::IdGenerator,
+    storage::Storage,
     Conf,
 };
 
-pub const BUCKETS: [&str; 8] = [
+pub const BUCKETS: [&str; 9] = [
     "attachments",
     "avatars",
     "banners",
@@ -35,6 +37,7 @@ pub const BUCKETS: [&str; 8] = [
     "member-avatars",
     "member-banners",
     "emojis",
+    "group-icons",
 ];
 
 #[cfg(test)]
@@ -46,13 +49,14 @@ fn rocket() -> Result<Rocket<Build>, anyhow::Error> {
         INIT.call_once(|| {
             env::set_current_dir("..").expect("Could not set the current directory");
             env::set_var("ELUDRIS_CONF", "tests/Eludris.toml");
-            create_file_dirs().expect("Could not create necessary file directories");
             dotenvy::dotenv().ok();
             env_logger::init();
         });
     }
 
     let conf = Conf::new_from_env()?;
+    create_file_dirs(&conf)?;
+    let storage = Storage::new(&conf.effis.storage)?;
 
     let config = Config::figment()
         .merge((
@@ -98,6 +102,7 @@ fn rocket() -> Result<Rocket<Build>, anyhow::Error> {
     Ok(rocket::custom(config)
         .manage(Mutex::new(IdGenerator::new()))
         .manage(conf)
+        .manage(storage)
         .manage(
             Client::builder()
                 .timeout(Duration::from_secs(10))
@@ -122,8 +127,6 @@ async fn main() -> Result<(), anyhow::Error> {
     dotenvy::dotenv().ok();
     env_logger::init();
 
-    create_file_dirs()?;
-
     let _ = rocket()?
         .launch()
         .await
@@ -132,11 +135,15 @@ async fn main() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
-fn create_file_dirs() -> Result<(), anyhow::Error> {
+fn create_file_dirs(conf: &Conf) -> Result<(), anyhow::Error> {
+    // Rocket keeps uploads here while they're being received
     try_create_dir("files")?;
-    try_create_dir("files/static")?;
-    for dir in BUCKETS.iter() {
-        try_create_dir(format!("files/{dir}"))?;
+    if let StorageConf::Local { path } = &conf.effis.storage {
+        try_create_dir(path)?;
+        try_create_dir(format!("{path}/static"))?;
+        for dir in BUCKETS.iter() {
+            try_create_dir(format!("{path}/{dir}"))?;
+        }
     }
     Ok(())
 }
@@ -1,9 +1,12 @@
 use rocket::{form::Form, serde::json::Json, State};
 use rocket_db_pools::Connection;
 use todel::{
-    http::ClientIP,
+    http::{ClientIP, FetchHeaders},
     ids::IdGenerator,
-    models::{ErrorResponse, FetchResponse, File, FileData, FileUpload},
+    models::{
+        ErrorResponse, FetchResponse, File, FileData, FileUpload, FileVariant, ThumbnailFormat,
+    },
+    storage::Storage,
     Conf,
 };
 use tokio::sync::Mutex;
@@ -45,6 +48,7 @@ pub async fn upload_file<'a>(
     mut cache: Connection<Cache>,
     mut db: Connection<DB>,
     conf: &State<Conf>,
+    storage: &State<Storage>,
     gen: &State<Mutex<IdGenerator>>,
 ) -> RateLimitedRouteResponse<Json<FileData>> {
     let mut rate_limiter = RateLimiter::new("attachments", bucket, ip, conf.inner());
@@ -56,6 +60,8 @@ pub async fn upload_file<'a>(
     let file = File::create(
         upload.file,
         bucket.to_string(),
+        storage,
+        conf,
         &mut *gen.inner().lock().await,
         &mut db,
     )
@@ -69,6 +75,12 @@ pub async fn upload_file<'a>(
 /// The `Content-Deposition` header is set to `inline`.
 /// Use the [`download_file`] endpoint to get `Content-Deposition` set to `attachment`.
 ///
+/// Parts of the file can be fetched with the `Range` and `If-Range` headers, and the `ETag` and
+/// `Last-Modified` headers can be used to revalidate cached copies of it.
+///
+/// Images can be resized with the `size` query parameter, which has to be one of the instance's
+/// `thumbnail_sizes`, and converted to `webp`, `avif` or `png` with the `format` query parameter.
+///
 /// -----
 ///
 /// ### Example
@@ -79,22 +91,33 @@ pub async fn upload_file<'a>(
 /// <raw file data>
 /// ```
 #[autodoc(category = "Files")]
-#[get("/<bucket>/<id>?<size>")]
+#[get("/<bucket>/<id>?<size>&<format>")]
 pub async fn get_file<'a>(
     bucket: &'a str,
     id: u64,
     size: Option<u32>,
+    format: Option<ThumbnailFormat>,
     ip: ClientIP,
+    headers: FetchHeaders,
     mut cache: Connection<Cache>,
     mut db: Connection<DB>,
     conf: &State<Conf>,
+    storage: &State<Storage>,
 ) -> RateLimitedRouteResponse<FetchResponse<'a>> {
     let mut rate_limiter = RateLimiter::new("fetch_file", bucket, ip, conf.inner());
     rate_limiter.process_rate_limit(0, &mut cache).await?;
     check_bucket(bucket).map_err(|e| rate_limiter.add_headers(e))?;
-    let file = File::fetch_file(id, bucket, size, &mut db)
-        .await
-        .map_err(|e| rate_limiter.add_headers(e))?;
+    let file = File::fetch_file(
+        id,
+        bucket,
+        FileVariant { size, format },
+        &headers,
+        storage,
+        conf,
+        &mut db,
+    )
+    .await
+    .map_err(|e| rate_limiter.add_headers(e))?;
     rate_limiter.wrap_response(file)
 }
 
@@ -103,6 +126,12 @@ pub async fn get_file<'a>(
 /// The `Content-Deposition` header is set to `attachment`.
 /// Use the [`get_file`] endpoint to get `Content-Deposition` set to `inline`.
 ///
+/// Parts of the file can be fetched with the `Range` and `If-Range` headers, and the `ETag` and
+/// `Last-Modified` headers can be used to revalidate cached copies of it.
+///
+/// Images can be resized with the `size` query parameter, which has to be one of the instance's
+/// `thumbnail_sizes`, and converted to `webp`, `avif` or `png` with the `format` query parameter.
+///
 /// -----
 ///
 /// ### Example
@@ -113,22 +142,33 @@ pub async fn get_file<'a>(
 /// <raw file data>
 /// ```
 #[autodoc(category = "Files")]
-#[get("/<bucket>/<id>/download?<size>")]
+#[get("/<bucket>/<id>/download?<size>&<format>")]
 pub async fn download_file<'a>(
     bucket: &'a str,
     id: u64,
     size: Option<u32>,
+    format: Option<ThumbnailFormat>,
     ip: ClientIP,
+    headers: FetchHeaders,
     mut cache: Connection<Cache>,
     mut db: Connection<DB>,
     conf: &State<Conf>,
+    storage: &State<Storage>,
 ) -> RateLimitedRouteResponse<FetchResponse<'a>> {
     let mut rate_limiter = RateLimiter::new("fetch_file", bucket, ip, conf.inner());
     rate_limiter.process_rate_limit(0, &mut cache).await?;
     check_bucket(bucket).map_err(|e| rate_limiter.add_headers(e))?;
-    let file = File::fetch_file_download(id, bucket, size, &mut db)
-        .await
-        .map_err(|e| rate_limiter.add_headers(e))?;
+    let file = File::fetch_file_download(
+        id,
+        bucket,
+        FileVariant { size, format },
+        &headers,
+        storage,
+        conf,
+        &mut db,
+    )
+    .await
+    .map_err(|e| rate_limiter.add_headers(e))?;
     rate_limiter.wrap_response(file)
 }
 
@@ -155,8 +195,8 @@ pub async fn download_file<'a>(
 /// ```
 #[autodoc(category = "Files")]
 #[get("/<bucket>/<id>/data")]
-pub async fn get_file_data<'a>(
-    bucket: &'a str,
+pub async fn get_file_data(
+    bucket: &str,
     id: u64,
     ip: ClientIP,
     mut cache: Connection<Cache>,
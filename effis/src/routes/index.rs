@@ -1,9 +1,10 @@
 use rocket::{form::Form, serde::json::Json, State};
 use rocket_db_pools::Connection;
 use todel::{
-    http::ClientIP,
+    http::{ClientIP, FetchHeaders},
     ids::IdGenerator,
-    models::{FetchResponse, File, FileData, FileUpload},
+    models::{FetchResponse, File, FileData, FileUpload, FileVariant, ThumbnailFormat},
+    storage::Storage,
     Conf,
 };
 use tokio::sync::Mutex;
@@ -44,6 +45,7 @@ pub async fn upload_attachment<'a>(
     mut cache: Connection<Cache>,
     mut db: Connection<DB>,
     conf: &State<Conf>,
+    storage: &State<Storage>,
     gen: &State<Mutex<IdGenerator>>,
 ) -> RateLimitedRouteResponse<Json<FileData>> {
     let mut rate_limiter = RateLimiter::new("attachments", "attachments", ip, conf.inner());
@@ -54,6 +56,8 @@ pub async fn upload_attachment<'a>(
     let file = File::create(
         upload.file,
         "attachments".to_string(),
+        storage,
+        conf,
         &mut *gen.inner().lock().await,
         &mut db,
     )
@@ -68,6 +72,12 @@ pub async fn upload_attachment<'a>(
 /// The `Content-Deposition` header is set to `inline`.
 /// Use the [`download_attachment`] endpoint to get `Content-Deposition` set to `attachment`.
 ///
+/// Parts of the file can be fetched with the `Range` and `If-Range` headers, and the `ETag` and
+/// `Last-Modified` headers can be used to revalidate cached copies of it.
+///
+/// Images can be resized with the `size` query parameter, which has to be one of the instance's
+/// `thumbnail_sizes`, and converted to `webp`, `avif` or `png` with the `format` query parameter.
+///
 /// -----
 ///
 /// ### Example
@@ -78,20 +88,31 @@ pub async fn upload_attachment<'a>(
 /// <raw file data>
 /// ```
 #[autodoc(category = "Files")]
-#[get("/<id>?<size>")]
+#[get("/<id>?<size>&<format>")]
 pub async fn get_attachment<'a>(
     id: u64,
     ip: ClientIP,
+    headers: FetchHeaders,
     size: Option<u32>,
+    format: Option<ThumbnailFormat>,
     mut cache: Connection<Cache>,
     mut db: Connection<DB>,
     conf: &State<Conf>,
+    storage: &State<Storage>,
 ) -> RateLimitedRouteResponse<FetchResponse<'a>> {
     let mut rate_limiter = RateLimiter::new("fetch_file", "attachments", ip, conf.inner());
     rate_limiter.process_rate_limit(0, &mut cache).await?;
-    let file = File::fetch_file(id, "attachments", size, &mut db)
-        .await
-        .map_err(|e| rate_limiter.add_headers(e))?;
+    let file = File::fetch_file(
+        id,
+        "attachments",
+        FileVariant { size, format },
+        &headers,
+        storage,
+        conf,
+        &mut db,
+    )
+    .await
+    .map_err(|e| rate_limiter.add_headers(e))?;
     rate_limiter.wrap_response(file)
 }
 
@@ -101,6 +122,12 @@ pub async fn get_attachment<'a>(
 /// The `Content-Deposition` header is set to `attachment`.
 /// Use the [`get_attachment`] endpoint to get `Content-Deposition` set to `inline`.
 ///
+/// Parts of the file can be fetched with the `Range` and `If-Range` headers, and the `ETag` and
+/// `Last-Modified` headers can be used to revalidate cached copies of it.
+///
+/// Images can be resized with the `size` query parameter, which has to be one of the instance's
+/// `thumbnail_sizes`, and converted to `webp`, `avif` or `png` with the `format` query parameter.
+///
 /// -----
 ///
 /// ### Example
@@ -111,18 +138,64 @@ pub async fn get_attachment<'a>(
 /// <raw file data>
 /// ```
 #[autodoc(category = "Files")]
-#[get("/<id>/download?<size>")]
+#[get("/<id>/download?<size>&<format>")]
 pub async fn download_attachment<'a>(
     id: u64,
     ip: ClientIP,
+    headers: FetchHeaders,
     size: Option<u32>,
+    format: Option<ThumbnailFormat>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    conf: &State<Conf>,
+    storage: &State<Storage>,
+) -> RateLimitedRouteResponse<FetchResponse<'a>> {
+    let mut rate_limiter = RateLimiter::new("fetch_file", "attachments", ip, conf.inner());
+    rate_limiter.process_rate_limit(0, &mut cache).await?;
+    let file = File::fetch_file_download(
+        id,
+        "attachments",
+        FileVariant { size, format },
+        &headers,
+        storage,
+        conf,
+        &mut db,
+    )
+    .await
+    .map_err(|e| rate_limiter.add_headers(e))?;
+    rate_limiter.wrap_response(file)
+}
+
+/// Get the poster frame of a video attachment by ID.
+///
+/// The poster frame is a JPEG which is extracted from the video when it's uploaded, files which
+/// aren't videos don't have one.
+///
+/// The `ETag` and `Last-Modified` headers can be used to revalidate cached copies of it.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl https://cdn.eludris.gay/2199681302540/thumbnail
+///
+/// <raw file data>
+/// ```
+#[autodoc(category = "Files")]
+#[get("/<id>/thumbnail")]
+pub async fn get_attachment_thumbnail<'a>(
+    id: u64,
+    ip: ClientIP,
+    headers: FetchHeaders,
     mut cache: Connection<Cache>,
     mut db: Connection<DB>,
     conf: &State<Conf>,
+    storage: &State<Storage>,
 ) -> RateLimitedRouteResponse<FetchResponse<'a>> {
     let mut rate_limiter = RateLimiter::new("fetch_file", "attachments", ip, conf.inner());
     rate_limiter.process_rate_limit(0, &mut cache).await?;
-    let file = File::fetch_file_download(id, "attachments", size, &mut db)
+    let file = File::fetch_thumbnail(id, "attachments", &headers, storage, &mut db)
         .await
         .map_err(|e| rate_limiter.add_headers(e))?;
     rate_limiter.wrap_response(file)
@@ -151,7 +224,7 @@ pub async fn download_attachment<'a>(
 /// ```
 #[autodoc(category = "Files")]
 #[get("/<id>/data")]
-pub async fn get_attachment_data<'a>(
+pub async fn get_attachment_data(
     id: u64,
     ip: ClientIP,
     mut cache: Connection<Cache>,
@@ -11,6 +11,7 @@ pub fn routes() -> Vec<Route> {
         index::get_attachment,
         index::download_attachment,
         index::get_attachment_data,
+        index::get_attachment_thumbnail,
         buckets::upload_file,
         buckets::get_file,
         buckets::download_file,
@@ -10,16 +10,16 @@ use rocket::{
 };
 use rocket_db_pools::Connection;
 use todel::{
-    http::ClientIP,
-    models::{ErrorResponse, FetchResponse},
+    http::{ClientIP, FetchHeaders},
+    models::{ErrorResponse, FetchBody, FetchResponse},
+    storage::Storage,
     Conf,
 };
-use tokio::fs::File;
 
-/// Simple struct meant to represent static disk files
-struct StaticFile<'a> {
-    file: File,
-    path: &'a Path,
+/// Simple struct meant to represent static files
+struct StaticFile {
+    body: FetchBody,
+    file_name: String,
     content_type: Option<ContentType>,
 }
 
@@ -40,33 +40,34 @@ struct StaticFile<'a> {
 /// ```
 #[autodoc(category = "Files")]
 #[get("/<name>", rank = 1)]
-pub async fn get_static_file<'a>(
-    name: &'a str,
+pub async fn get_static_file(
+    name: &str,
     ip: ClientIP,
+    headers: FetchHeaders,
     mut cache: Connection<Cache>,
     conf: &State<Conf>,
-) -> RateLimitedRouteResponse<FetchResponse<'a>> {
+    storage: &State<Storage>,
+) -> RateLimitedRouteResponse<FetchResponse<'static>> {
     let mut rate_limiter = RateLimiter::new("fetch_file", "static", ip, conf.inner());
     rate_limiter.process_rate_limit(0, &mut cache).await?;
 
     let StaticFile {
-        file,
-        path,
+        body,
+        file_name,
         content_type,
-    } = get_file(name)
+    } = get_file(name, &headers, storage)
         .await
         .map_err(|e| rate_limiter.add_headers(e))?;
 
     rate_limiter.wrap_response(FetchResponse {
-        file,
+        body,
         disposition: Header::new(
             "Content-Disposition",
-            format!(
-                "inline; filename=\"{}\"",
-                path.file_name().unwrap().to_str().unwrap()
-            ),
+            format!("inline; filename=\"{}\"", file_name),
         ),
         content_type: content_type.unwrap_or(ContentType::Any),
+        etag: None,
+        last_modified: None,
     })
 }
 
@@ -87,37 +88,42 @@ pub async fn get_static_file<'a>(
 /// ```
 #[autodoc(category = "Files")]
 #[get("/<name>/download", rank = 1)]
-pub async fn download_static_file<'a>(
-    name: &'a str,
+pub async fn download_static_file(
+    name: &str,
     ip: ClientIP,
+    headers: FetchHeaders,
     mut cache: Connection<Cache>,
     conf: &State<Conf>,
-) -> RateLimitedRouteResponse<Result<FetchResponse<'a>, ErrorResponse>> {
+    storage: &State<Storage>,
+) -> RateLimitedRouteResponse<Result<FetchResponse<'static>, ErrorResponse>> {
     let mut rate_limiter = RateLimiter::new("fetch_file", "static", ip, conf.inner());
     rate_limiter.process_rate_limit(0, &mut cache).await?;
 
     let StaticFile {
-        file,
-        path,
+        body,
+        file_name,
         content_type,
-    } = get_file(name)
+    } = get_file(name, &headers, storage)
         .await
         .map_err(|e| rate_limiter.add_headers(e))?;
 
     rate_limiter.wrap_response(Ok(FetchResponse {
-        file,
+        body,
         disposition: Header::new(
             "Content-Disposition",
-            format!(
-                "attachment; filename=\"{}\"",
-                path.file_name().unwrap().to_str().unwrap()
-            ),
+            format!("attachment; filename=\"{}\"", file_name),
         ),
         content_type: content_type.unwrap_or(ContentType::Any),
+        etag: None,
+        last_modified: None,
     }))
 }
 
-async fn get_file(name: &str) -> Result<StaticFile, ErrorResponse> {
+async fn get_file(
+    name: &str,
+    headers: &FetchHeaders,
+    storage: &Storage,
+) -> Result<StaticFile, ErrorResponse> {
     let path = Path::new(name)
         .file_name()
         .map(Path::new)
@@ -133,21 +139,28 @@ async fn get_file(name: &str) -> Result<StaticFile, ErrorResponse> {
         None => None,
     };
 
-    let file = File::open(Path::new("files/static").join(path))
-        .await
-        .map_err(|e| {
-            if e.kind() == ErrorKind::NotFound {
-                error!(NOT_FOUND)
-            } else {
-                error!(SERVER, "Failed to get static file from storage")
-            }
-        })?;
+    let body = FetchBody::fetch(
+        &format!("static/{}", path.display()),
+        headers,
+        None,
+        None,
+        storage,
+    )
+    .await
+    .map_err(|e| {
+        if e.kind() == ErrorKind::NotFound {
+            error!(NOT_FOUND)
+        } else {
+            log::error!("Failed to get static file {}: {}", name, e);
+            error!(SERVER, "Failed to get static file from storage")
+        }
+    })?;
 
     log::debug!("Fetched static file {}", name);
 
     Ok(StaticFile {
-        file,
-        path,
+        body,
+        file_name: path.to_string_lossy().to_string(),
         content_type,
     })
 }
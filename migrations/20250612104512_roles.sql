@@ -0,0 +1,24 @@
+-- VIEW_CHANNELS | SEND_MESSAGES | ADD_REACTIONS, the @everyone baseline.
+ALTER TABLE spheres ALTER COLUMN default_permissions SET DEFAULT 1792;
+UPDATE spheres SET default_permissions = 1792 WHERE default_permissions = 0;
+
+CREATE TABLE IF NOT EXISTS roles (
+  id BIGINT PRIMARY KEY,
+  sphere_id BIGINT NOT NULL,
+  name VARCHAR(32) NOT NULL,
+  colour INT,
+  position INT NOT NULL,
+  permissions BIGINT NOT NULL DEFAULT 0,
+  is_deleted BOOLEAN NOT NULL DEFAULT FALSE,
+  FOREIGN KEY (sphere_id) REFERENCES spheres(id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS member_roles (
+  member_id BIGINT NOT NULL,
+  sphere_id BIGINT NOT NULL,
+  role_id BIGINT NOT NULL,
+  PRIMARY KEY (member_id, sphere_id, role_id),
+  FOREIGN KEY (member_id) REFERENCES users(id) ON DELETE CASCADE ON UPDATE CASCADE,
+  FOREIGN KEY (sphere_id) REFERENCES spheres(id) ON DELETE CASCADE ON UPDATE CASCADE,
+  FOREIGN KEY (role_id) REFERENCES roles(id) ON DELETE CASCADE ON UPDATE CASCADE
+);
@@ -0,0 +1,12 @@
+CREATE TYPE overwrite_type AS ENUM ('ROLE', 'MEMBER');
+
+-- Overwrites of type ROLE whose target_id is the sphere's ID apply to @everyone.
+CREATE TABLE IF NOT EXISTS channel_overwrites (
+  channel_id BIGINT NOT NULL,
+  target_id BIGINT NOT NULL,
+  overwrite_type overwrite_type NOT NULL,
+  allow BIGINT NOT NULL DEFAULT 0,
+  deny BIGINT NOT NULL DEFAULT 0,
+  PRIMARY KEY (channel_id, target_id),
+  FOREIGN KEY (channel_id) REFERENCES channels(id) ON DELETE CASCADE ON UPDATE CASCADE
+);
@@ -0,0 +1,14 @@
+-- Expiry timestamps are seconds since the Unix epoch.
+CREATE TABLE IF NOT EXISTS sphere_bans (
+  sphere_id BIGINT NOT NULL,
+  user_id BIGINT NOT NULL,
+  moderator_id BIGINT NOT NULL,
+  reason VARCHAR(512),
+  expires_at BIGINT,
+  PRIMARY KEY (sphere_id, user_id),
+  FOREIGN KEY (sphere_id) REFERENCES spheres(id) ON DELETE CASCADE ON UPDATE CASCADE,
+  FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE ON UPDATE CASCADE,
+  FOREIGN KEY (moderator_id) REFERENCES users(id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+ALTER TABLE members ADD COLUMN timed_out_until BIGINT;
@@ -0,0 +1,27 @@
+CREATE TYPE audit_log_action AS ENUM (
+  'SPHERE_UPDATE',
+  'CATEGORY_UPDATE',
+  'CATEGORY_DELETE',
+  'CHANNEL_UPDATE',
+  'CHANNEL_DELETE',
+  'EMOJI_UPDATE',
+  'EMOJI_DELETE',
+  'MEMBER_KICK',
+  'MEMBER_BAN',
+  'MEMBER_UNBAN',
+  'MEMBER_TIMEOUT'
+);
+
+CREATE TABLE IF NOT EXISTS audit_log (
+  id BIGINT NOT NULL PRIMARY KEY,
+  sphere_id BIGINT NOT NULL,
+  actor_id BIGINT NOT NULL,
+  target_id BIGINT NOT NULL,
+  action audit_log_action NOT NULL,
+  changes JSONB NOT NULL,
+  reason VARCHAR(512),
+  FOREIGN KEY (sphere_id) REFERENCES spheres(id) ON DELETE CASCADE,
+  FOREIGN KEY (actor_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX audit_log_sphere_id_idx ON audit_log(sphere_id, id);
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS read_states (
+  user_id BIGINT NOT NULL,
+  channel_id BIGINT NOT NULL,
+  last_acked_id BIGINT,
+  mention_count INT NOT NULL DEFAULT 0,
+  PRIMARY KEY (user_id, channel_id),
+  FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE ON UPDATE CASCADE,
+  FOREIGN KEY (channel_id) REFERENCES channels(id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+-- Used to find the latest message of each channel.
+CREATE INDEX IF NOT EXISTS messages_channel_id_idx ON messages(channel_id, id);
@@ -0,0 +1,4 @@
+-- there can only be one direct message channel between two users
+CREATE UNIQUE INDEX IF NOT EXISTS direct_message_channels_users_idx
+ON channels(LEAST(owner_id, recipient_id), GREATEST(owner_id, recipient_id))
+WHERE channel_type = 'DIRECT';
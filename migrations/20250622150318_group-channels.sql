@@ -0,0 +1,3 @@
+ALTER TABLE channel_members ALTER COLUMN channel_id SET NOT NULL;
+ALTER TABLE channel_members ADD PRIMARY KEY (id, channel_id);
+CREATE INDEX IF NOT EXISTS channel_members_channel_id_idx ON channel_members(channel_id);
@@ -0,0 +1,33 @@
+ALTER TYPE channel_type ADD VALUE IF NOT EXISTS 'FORUM';
+
+-- comments are posts with a parent
+CREATE TABLE IF NOT EXISTS posts (
+  id BIGINT PRIMARY KEY,
+  channel_id BIGINT NOT NULL,
+  author_id BIGINT,
+  parent_id BIGINT,
+  title VARCHAR(256),
+  flair VARCHAR(32),
+  content TEXT,
+  FOREIGN KEY (channel_id) REFERENCES channels(id) ON DELETE CASCADE ON UPDATE CASCADE,
+  FOREIGN KEY (author_id) REFERENCES users(id) ON DELETE SET NULL ON UPDATE CASCADE,
+  FOREIGN KEY (parent_id) REFERENCES posts(id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS posts_channel_id_idx ON posts(channel_id, id) WHERE parent_id IS NULL;
+CREATE INDEX IF NOT EXISTS posts_parent_id_idx ON posts(parent_id);
+
+CREATE TABLE IF NOT EXISTS post_attachments (
+  post_id BIGINT NOT NULL,
+  file_id BIGINT NOT NULL,
+  description TEXT,
+  spoiler BOOLEAN NOT NULL DEFAULT FALSE,
+  FOREIGN KEY (post_id) REFERENCES posts(id) ON DELETE CASCADE ON UPDATE CASCADE,
+  FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS post_embeds (
+  post_id BIGINT NOT NULL,
+  embed jsonb NOT NULL,
+  FOREIGN KEY (post_id) REFERENCES posts(id) ON DELETE CASCADE ON UPDATE CASCADE
+);
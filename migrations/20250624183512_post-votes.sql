@@ -0,0 +1,12 @@
+ALTER TABLE posts ADD COLUMN IF NOT EXISTS score INT NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS post_votes (
+  post_id BIGINT NOT NULL,
+  user_id BIGINT NOT NULL,
+  vote SMALLINT NOT NULL CHECK (vote IN (-1, 1)),
+  PRIMARY KEY (post_id, user_id),
+  FOREIGN KEY (post_id) REFERENCES posts(id) ON DELETE CASCADE ON UPDATE CASCADE,
+  FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS posts_score_idx ON posts(channel_id, score) WHERE parent_id IS NULL;
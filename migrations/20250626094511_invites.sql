@@ -0,0 +1,23 @@
+-- Members could always join any sphere, so keep letting them invite others.
+ALTER TABLE spheres ALTER COLUMN default_permissions SET DEFAULT 18176;
+UPDATE spheres SET default_permissions = default_permissions | 16384;
+
+ALTER TABLE spheres ADD COLUMN discoverable BOOLEAN NOT NULL DEFAULT TRUE;
+ALTER TABLE spheres ADD COLUMN invite_only BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Expiry timestamps are seconds since the Unix epoch.
+CREATE TABLE IF NOT EXISTS invites (
+  code VARCHAR(16) PRIMARY KEY,
+  sphere_id BIGINT NOT NULL,
+  creator_id BIGINT,
+  uses INT NOT NULL DEFAULT 0,
+  max_uses INT,
+  expires_at BIGINT,
+  FOREIGN KEY (sphere_id) REFERENCES spheres(id) ON DELETE CASCADE ON UPDATE CASCADE,
+  FOREIGN KEY (creator_id) REFERENCES users(id) ON DELETE SET NULL ON UPDATE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS invites_sphere_id_idx ON invites(sphere_id);
+
+-- Not a foreign key so members keep track of the invite they used after it's revoked.
+ALTER TABLE members ADD COLUMN invite_code VARCHAR(16);
@@ -0,0 +1,7 @@
+-- `users.two_factor_auth` holds the base32 encoded TOTP secret of users who enabled 2FA.
+CREATE TABLE IF NOT EXISTS two_factor_recovery_codes (
+  user_id BIGINT NOT NULL,
+  code_hash CHAR(64) NOT NULL, -- The hex encoded SHA-256 hash of the code
+  PRIMARY KEY (user_id, code_hash),
+  FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE ON UPDATE CASCADE
+);
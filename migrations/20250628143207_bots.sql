@@ -0,0 +1,14 @@
+-- Bots don't have an email or password, they authenticate using tokens issued through their application.
+ALTER TABLE users
+  ALTER COLUMN email DROP NOT NULL,
+  ALTER COLUMN password DROP NOT NULL;
+
+-- An application's ID is the ID of its bot user.
+CREATE TABLE IF NOT EXISTS applications (
+  id BIGINT PRIMARY KEY,
+  owner_id BIGINT NOT NULL,
+  FOREIGN KEY (id) REFERENCES users(id) ON DELETE CASCADE ON UPDATE CASCADE,
+  FOREIGN KEY (owner_id) REFERENCES users(id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS applications_owner_id_idx ON applications(owner_id);
@@ -0,0 +1,9 @@
+ALTER TABLE applications ADD COLUMN IF NOT EXISTS redirect_uris TEXT[] NOT NULL DEFAULT '{}';
+
+-- Sessions created through OAuth2 are scoped to the application they were authorized for.
+ALTER TABLE sessions
+  ADD COLUMN IF NOT EXISTS application_id BIGINT,
+  ADD COLUMN IF NOT EXISTS scopes BIGINT,
+  ADD COLUMN IF NOT EXISTS refresh_token_hash CHAR(64) UNIQUE, -- The hex encoded SHA-256 hash of the refresh token
+  ADD COLUMN IF NOT EXISTS expires_at BIGINT,
+  ADD FOREIGN KEY (application_id) REFERENCES applications(id) ON DELETE CASCADE ON UPDATE CASCADE;
@@ -0,0 +1,15 @@
+ALTER TABLE sessions
+  ADD COLUMN IF NOT EXISTS created_at BIGINT,
+  ADD COLUMN IF NOT EXISTS last_used_at BIGINT,
+  ADD COLUMN IF NOT EXISTS last_ip INET;
+
+-- Session IDs start with the number of seconds since the Eludris epoch they were created at.
+UPDATE sessions
+SET created_at = (id >> 16) + 1650000000,
+  last_used_at = (id >> 16) + 1650000000,
+  last_ip = ip;
+
+ALTER TABLE sessions
+  ALTER COLUMN created_at SET NOT NULL,
+  ALTER COLUMN last_used_at SET NOT NULL,
+  ALTER COLUMN last_ip SET NOT NULL;
@@ -0,0 +1,24 @@
+-- Webhooks are authenticated by the SHA-256 hash of their token.
+CREATE TABLE IF NOT EXISTS webhooks (
+  id BIGINT PRIMARY KEY,
+  channel_id BIGINT NOT NULL,
+  creator_id BIGINT,
+  name VARCHAR(32) NOT NULL,
+  avatar BIGINT,
+  token_hash CHAR(64) NOT NULL,
+  FOREIGN KEY (channel_id) REFERENCES channels(id) ON DELETE CASCADE ON UPDATE CASCADE,
+  FOREIGN KEY (creator_id) REFERENCES users(id) ON DELETE SET NULL ON UPDATE CASCADE,
+  FOREIGN KEY (avatar) REFERENCES files(id) ON DELETE SET NULL ON UPDATE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS webhooks_channel_id_idx ON webhooks(channel_id);
+
+-- Messages keep the name and avatar their webhook had when they were sent, the webhook_id isn't a
+-- foreign key so that they stay attributed to it after it's deleted.
+CREATE TABLE IF NOT EXISTS message_webhooks (
+  message_id BIGINT PRIMARY KEY,
+  webhook_id BIGINT NOT NULL,
+  name VARCHAR(32) NOT NULL,
+  avatar BIGINT,
+  FOREIGN KEY (message_id) REFERENCES messages(id) ON DELETE CASCADE ON UPDATE CASCADE
+);
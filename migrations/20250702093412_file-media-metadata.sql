@@ -0,0 +1,3 @@
+ALTER TABLE files
+  ADD COLUMN IF NOT EXISTS duration DOUBLE PRECISION,
+  ADD COLUMN IF NOT EXISTS codec VARCHAR(32);
@@ -0,0 +1,8 @@
+-- Recovery codes are now hashed with argon2 like passwords, the old unsalted hashes can't be
+-- verified anymore.
+DELETE FROM two_factor_recovery_codes;
+ALTER TABLE two_factor_recovery_codes
+  ALTER COLUMN code_hash TYPE TEXT; -- The argon2 hash of the code
+
+ALTER TABLE users
+  ADD COLUMN IF NOT EXISTS two_factor_last_step BIGINT; -- The last TOTP time step used, to stop codes from being replayed
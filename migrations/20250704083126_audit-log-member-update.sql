@@ -0,0 +1 @@
+ALTER TYPE audit_log_action ADD VALUE IF NOT EXISTS 'MEMBER_UPDATE';
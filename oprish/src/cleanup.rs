@@ -4,7 +4,11 @@ use rocket::{
     Build, Rocket,
 };
 use rocket_db_pools::Database;
-use todel::{http::DB, models::User};
+use todel::{
+    http::DB,
+    models::{Invite, Session, Sphere, User},
+    Conf,
+};
 use tokio::time::sleep;
 
 pub struct ScheduledCleanup;
@@ -19,6 +23,10 @@ impl Fairing for ScheduledCleanup {
     }
 
     async fn on_ignite(&self, rocket: Rocket<Build>) -> Result {
+        let conf = rocket
+            .state::<Conf>()
+            .expect("Could not get the managed Conf")
+            .clone();
         let mut db = {
             let pool = DB::fetch(&rocket).expect("Could not get the managed pool");
             pool.acquire()
@@ -41,6 +49,15 @@ impl Fairing for ScheduledCleanup {
                 if let Err(err) = User::clean_up_unverified(&mut db).await {
                     log::error!("Couldn't clean up unverified users: {}", err);
                 }
+                if let Err(err) = Sphere::clean_up_expired_bans(&mut db).await {
+                    log::error!("Couldn't clean up expired sphere bans: {}", err);
+                }
+                if let Err(err) = Invite::clean_up_expired(&mut db).await {
+                    log::error!("Couldn't clean up expired invites: {}", err);
+                }
+                if let Err(err) = Session::clean_up_expired(&conf, &mut db).await {
+                    log::error!("Couldn't clean up expired sessions: {}", err);
+                }
                 sleep(
                     Duration::days(1)
                         .to_std()
@@ -22,6 +22,7 @@ use rocket_db_pools::Database;
 use todel::{
     http::{Cache, DB},
     ids::IdGenerator,
+    storage::Storage,
     Conf,
 };
 use tokio::sync::Mutex;
@@ -76,8 +77,12 @@ fn rocket() -> Result<Rocket<Build>, anyhow::Error> {
             },
         ));
 
+    let conf = Conf::new_from_env()?;
+    let storage = Storage::new(&conf.effis.storage)?;
+
     Ok(rocket::custom(config)
-        .manage(Conf::new_from_env()?)
+        .manage(conf)
+        .manage(storage)
         .manage(Mutex::new(StdRng::from_entropy()))
         .manage(Mutex::new(IdGenerator::new()))
         .manage(Argon2::default())
@@ -92,7 +97,11 @@ fn rocket() -> Result<Rocket<Build>, anyhow::Error> {
         .mount("/sessions", sessions::get_routes())
         .mount("/spheres", spheres::get_routes())
         .mount("/channels", channels::get_routes())
-        .mount("/emojis", emojis::get_routes()))
+        .mount("/emojis", emojis::get_routes())
+        .mount("/invites", invites::get_routes())
+        .mount("/applications", applications::get_routes())
+        .mount("/oauth2", oauth2::get_routes())
+        .mount("/webhooks", webhooks::get_routes()))
 }
 
 #[rocket::main]
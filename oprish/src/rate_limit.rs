@@ -65,9 +65,20 @@ impl RateLimiter {
             delete_user,
             create_password_reset_code,
             reset_password,
+            setup_two_factor,
+            confirm_two_factor,
+            disable_two_factor,
             create_session,
             get_sessions,
             delete_session,
+            create_application,
+            get_applications,
+            rotate_application_token,
+            edit_application,
+            delete_application,
+            get_oauth_authorization,
+            authorize_application,
+            create_oauth_token,
             resend_verification,
             create_sphere,
             get_sphere,
@@ -98,6 +109,40 @@ impl RateLimiter {
             add_reaction,
             remove_reaction,
             clear_reactions,
+            create_role,
+            get_roles,
+            edit_role,
+            delete_role,
+            get_overwrites,
+            set_overwrite,
+            delete_overwrite,
+            ban_member,
+            unban_member,
+            get_bans,
+            timeout_member,
+            get_audit_log,
+            ack_message,
+            open_direct_message,
+            create_group,
+            edit_group,
+            add_group_member,
+            remove_group_member,
+            create_post,
+            get_posts,
+            get_post,
+            edit_post,
+            delete_post,
+            vote_post,
+            remove_post_vote,
+            create_invite,
+            get_invites,
+            delete_invite,
+            use_invite,
+            create_webhook,
+            get_webhooks,
+            edit_webhook,
+            delete_webhook,
+            execute_webhook,
         );
         RateLimiter {
             key: format!("rate_limit:{}:{}", identifier, bucket),
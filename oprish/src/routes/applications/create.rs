@@ -0,0 +1,84 @@
+use rocket::{http::Status, response::status::Custom, serde::json::Json, State};
+use rocket_db_pools::Connection;
+use todel::{
+    http::{Cache, ClientIP, TokenAuth, DB},
+    ids::IdGenerator,
+    models::{Application, ApplicationCreate, ApplicationCreated, Secret},
+    Conf,
+};
+use tokio::sync::Mutex;
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Create a new application along with its bot user.
+///
+/// The returned token is what the bot uses to authenticate with both the API and the gateway,
+/// bots can't log in with a password.
+///
+/// -- STATUS: 201
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -H "Authorization: <token>" \
+///   --json '{"username": "pengin-bot", "display_name": "Pengin Bot"}' \
+///   https://api.eludris.gay/applications
+///
+/// {
+///   "token": "<token>",
+///   "application": {
+///     "id": 48615849987334,
+///     "owner_id": 48615849987333,
+///     "redirect_uris": [],
+///     "bot": {
+///       "id": 48615849987334,
+///       "username": "pengin-bot",
+///       "display_name": "Pengin Bot",
+///       "social_credit": 0,
+///       "status": {
+///         "type": "ONLINE"
+///       },
+///       "badges": 1,
+///       "permissions": 0,
+///       "verified": true
+///     }
+///   }
+/// }
+/// ```
+#[autodoc("/applications", category = "Applications")]
+#[post("/", data = "<application>")]
+pub async fn create_application(
+    application: Json<ApplicationCreate>,
+    id_generator: &State<Mutex<IdGenerator>>,
+    secret: &State<Secret>,
+    conf: &State<Conf>,
+    mut db: Connection<DB>,
+    mut cache: Connection<Cache>,
+    session: TokenAuth,
+    ip: ClientIP,
+) -> RateLimitedRouteResponse<Custom<Json<ApplicationCreated>>> {
+    let mut rate_limiter = RateLimiter::new(
+        "create_application",
+        session.0.rate_limit_identifier(),
+        conf,
+    );
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    rate_limiter.wrap_response(Custom(
+        Status::Created,
+        Json(
+            Application::create(
+                application.into_inner(),
+                session.0.user_id,
+                *ip,
+                secret,
+                &mut *id_generator.lock().await,
+                &mut db,
+            )
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?,
+        ),
+    ))
+}
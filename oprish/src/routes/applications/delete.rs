@@ -0,0 +1,49 @@
+use rocket::{http::Status, response::status::Custom, State};
+use rocket_db_pools::Connection;
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::Application,
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Delete one of your applications along with its bot user.
+///
+/// -- STATUS: 204
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl --request DELETE \
+///   -H "Authorization: <token>" \
+///   https://api.eludris.gay/applications/48615849987334
+/// ```
+#[autodoc("/applications", category = "Applications")]
+#[delete("/<application_id>")]
+pub async fn delete_application(
+    application_id: u64,
+    conf: &State<Conf>,
+    mut db: Connection<DB>,
+    mut cache: Connection<Cache>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Custom<()>> {
+    let mut rate_limiter = RateLimiter::new(
+        "delete_application",
+        session.0.rate_limit_identifier(),
+        conf,
+    );
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    let application = Application::get(application_id, session.0.user_id, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+    rate_limiter.wrap_response(Custom(
+        Status::NoContent,
+        application
+            .delete(&mut db)
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?,
+    ))
+}
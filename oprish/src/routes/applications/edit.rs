@@ -0,0 +1,63 @@
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::Connection;
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{Application, ApplicationEdit},
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Edit one of your applications.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -X PATCH \
+///   -H "Authorization: <token>" \
+///   --json '{"redirect_uris": ["https://pengin.example/callback"]}' \
+///   https://api.eludris.gay/applications/48615849987334
+///
+/// {
+///   "id": 48615849987334,
+///   "owner_id": 48615849987333,
+///   "redirect_uris": ["https://pengin.example/callback"],
+///   "bot": {
+///     "id": 48615849987334,
+///     "username": "pengin-bot",
+///     "social_credit": 0,
+///     "status": {
+///       "type": "ONLINE"
+///     },
+///     "badges": 1,
+///     "permissions": 0,
+///     "verified": true
+///   }
+/// }
+/// ```
+#[autodoc("/applications", category = "Applications")]
+#[patch("/<application_id>", data = "<edit>")]
+pub async fn edit_application(
+    application_id: u64,
+    edit: Json<ApplicationEdit>,
+    conf: &State<Conf>,
+    mut db: Connection<DB>,
+    mut cache: Connection<Cache>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Json<Application>> {
+    let mut rate_limiter =
+        RateLimiter::new("edit_application", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    let mut application = Application::get(application_id, session.0.user_id, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+    application
+        .edit(edit.into_inner(), &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+    rate_limiter.wrap_response(Json(application))
+}
@@ -0,0 +1,57 @@
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::Connection;
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::Application,
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Get one of your applications.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -H "Authorization: <token>" \
+///   https://api.eludris.gay/applications/48615849987334
+///
+/// {
+///   "id": 48615849987334,
+///   "owner_id": 48615849987333,
+///   "redirect_uris": [],
+///   "bot": {
+///     "id": 48615849987334,
+///     "username": "pengin-bot",
+///     "social_credit": 0,
+///     "status": {
+///       "type": "ONLINE"
+///     },
+///     "badges": 1,
+///     "permissions": 0,
+///     "verified": true
+///   }
+/// }
+/// ```
+#[autodoc("/applications", category = "Applications")]
+#[get("/<application_id>")]
+pub async fn get_application(
+    application_id: u64,
+    conf: &State<Conf>,
+    mut db: Connection<DB>,
+    mut cache: Connection<Cache>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Json<Application>> {
+    let mut rate_limiter =
+        RateLimiter::new("get_applications", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    rate_limiter.wrap_response(Json(
+        Application::get(application_id, session.0.user_id, &mut db)
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?,
+    ))
+}
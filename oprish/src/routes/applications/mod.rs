@@ -0,0 +1,19 @@
+use rocket::Route;
+
+mod create;
+mod delete;
+mod edit;
+mod get;
+mod get_all;
+mod rotate_token;
+
+pub fn get_routes() -> Vec<Route> {
+    routes![
+        create::create_application,
+        get_all::get_applications,
+        get::get_application,
+        edit::edit_application,
+        rotate_token::rotate_application_token,
+        delete::delete_application,
+    ]
+}
@@ -0,0 +1,58 @@
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::Connection;
+use todel::{
+    http::{Cache, ClientIP, TokenAuth, DB},
+    ids::IdGenerator,
+    models::{Application, ApplicationToken, Secret},
+    Conf,
+};
+use tokio::sync::Mutex;
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Rotate one of your applications' bot token.
+///
+/// All of the bot's previous tokens are revoked, disconnecting it from the gateway.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl --request POST \
+///   -H "Authorization: <token>" \
+///   https://api.eludris.gay/applications/48615849987334/token
+///
+/// {
+///   "token": "<token>"
+/// }
+/// ```
+#[autodoc("/applications", category = "Applications")]
+#[post("/<application_id>/token")]
+pub async fn rotate_application_token(
+    application_id: u64,
+    id_generator: &State<Mutex<IdGenerator>>,
+    secret: &State<Secret>,
+    conf: &State<Conf>,
+    mut db: Connection<DB>,
+    mut cache: Connection<Cache>,
+    session: TokenAuth,
+    ip: ClientIP,
+) -> RateLimitedRouteResponse<Json<ApplicationToken>> {
+    let mut rate_limiter = RateLimiter::new(
+        "rotate_application_token",
+        session.0.rate_limit_identifier(),
+        conf,
+    );
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    let application = Application::get(application_id, session.0.user_id, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+    rate_limiter.wrap_response(Json(
+        application
+            .rotate_token(*ip, secret, &mut *id_generator.lock().await, &mut db)
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?,
+    ))
+}
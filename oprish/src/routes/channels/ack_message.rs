@@ -0,0 +1,65 @@
+use rocket::{http::Status, response::status::Custom, State};
+use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{Channel, ErrorResponse, ReadState, ServerPayload, SpherePermission},
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Mark every message of a channel up to a message as read, resetting your mention count in the
+/// channel.
+///
+/// The read state is synced to all of your sessions through a `MESSAGE_ACK` event.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -X POST \
+///   -H "Authorization: <token>" \
+///   https://api.eludris.gay/channels/4080402038789/messages/5490083823641/ack
+/// ```
+#[autodoc("/channels", category = "Messaging")]
+#[post("/<channel_id>/messages/<message_id>/ack")]
+pub async fn ack_message(
+    channel_id: u64,
+    message_id: u64,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Result<Custom<()>, ErrorResponse>> {
+    let mut rate_limiter = RateLimiter::new("ack_message", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+    Channel::require_permission(
+        channel_id,
+        session.0.user_id,
+        SpherePermission::ViewChannels,
+        &mut db,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
+
+    let message_id = ReadState::ack(session.0.user_id, channel_id, message_id, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+
+    cache
+        .publish::<&str, String, ()>(
+            "eludris-events",
+            serde_json::to_string(&ServerPayload::MessageAck {
+                user_id: session.0.user_id,
+                channel_id,
+                message_id,
+            })
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    rate_limiter.wrap_response(Ok(Custom(Status::NoContent, ())))
+}
@@ -0,0 +1,53 @@
+use rocket::{http::Status, response::status::Custom, State};
+use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{ErrorResponse, GroupChannel, ServerPayload},
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Add a user to a group channel you're a member of.
+///
+/// -- STATUS: 204
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -H "Authorization: <token>" \
+///   -X PUT \
+///   https://api.eludris.gay/channels/4080402038800/members/4080402038778
+/// ```
+#[autodoc("/channels", category = "Channels")]
+#[put("/<channel_id>/members/<user_id>")]
+pub async fn add_group_member(
+    channel_id: u64,
+    user_id: u64,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Result<Custom<()>, ErrorResponse>> {
+    let mut rate_limiter =
+        RateLimiter::new("add_group_member", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+    let mut cache = cache.into_inner();
+
+    let user =
+        GroupChannel::add_member(channel_id, user_id, session.0.user_id, &mut db, &mut cache)
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?;
+
+    cache
+        .publish::<&str, String, ()>(
+            "eludris-events",
+            serde_json::to_string(&ServerPayload::GroupMemberAdd { user, channel_id }).unwrap(),
+        )
+        .await
+        .unwrap();
+
+    rate_limiter.wrap_response(Ok(Custom(Status::NoContent, ())))
+}
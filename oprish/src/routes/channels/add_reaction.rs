@@ -2,7 +2,9 @@ use rocket::{serde::json::Json, State};
 use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
 use todel::{
     http::{Cache, TokenAuth, DB},
-    models::{ErrorResponse, Message, ReactionEmojiReference, ServerPayload, SphereChannel},
+    models::{
+        Channel, ErrorResponse, Message, ReactionEmojiReference, ServerPayload, SpherePermission,
+    },
     Conf,
 };
 
@@ -21,22 +23,27 @@ pub async fn add_reaction(
 ) -> RateLimitedRouteResponse<Result<Json<Message>, ErrorResponse>> {
     let mut rate_limiter = RateLimiter::new(
         "add_reaction",
-        format!("{}:{}", channel_id, session.0.user_id),
+        format!("{}:{}", channel_id, session.0.rate_limit_identifier()),
         conf,
     );
     rate_limiter.process_rate_limit(&mut cache).await?;
 
-    if !SphereChannel::has_member(channel_id, session.0.user_id, &mut db)
-        .await
-        .map_err(|err| rate_limiter.add_headers(err))?
-    {
-        error!(rate_limiter, UNAUTHORIZED);
-    }
+    Channel::require_permission(
+        channel_id,
+        session.0.user_id,
+        SpherePermission::AddReactions,
+        &mut db,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
 
     let mut cache = cache.into_inner();
     let mut message = Message::get(message_id, &mut db, &mut cache)
         .await
         .map_err(|err| rate_limiter.add_headers(err))?;
+    if message.channel.get_id() != channel_id {
+        error!(rate_limiter, NOT_FOUND);
+    }
     let emoji = message
         .add_reaction(emoji.into_inner(), session.0.user_id, &mut db)
         .await
@@ -2,7 +2,7 @@ use rocket::{serde::json::Json, State};
 use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
 use todel::{
     http::{Cache, TokenAuth, DB},
-    models::{ErrorResponse, Message, ServerPayload, SphereChannel},
+    models::{Channel, ErrorResponse, Message, ServerPayload, SpherePermission},
     Conf,
 };
 
@@ -20,22 +20,27 @@ pub async fn clear_reactions(
 ) -> RateLimitedRouteResponse<Result<Json<Message>, ErrorResponse>> {
     let mut rate_limiter = RateLimiter::new(
         "clear_reactions",
-        format!("{}:{}", channel_id, session.0.user_id),
+        format!("{}:{}", channel_id, session.0.rate_limit_identifier()),
         conf,
     );
     rate_limiter.process_rate_limit(&mut cache).await?;
 
-    if !SphereChannel::has_member(channel_id, session.0.user_id, &mut db)
-        .await
-        .map_err(|err| rate_limiter.add_headers(err))?
-    {
-        error!(rate_limiter, UNAUTHORIZED);
-    }
+    Channel::require_permission(
+        channel_id,
+        session.0.user_id,
+        SpherePermission::ManageMessages,
+        &mut db,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
 
     let mut cache = cache.into_inner();
     let mut message = Message::get(message_id, &mut db, &mut cache)
         .await
         .map_err(|err| rate_limiter.add_headers(err))?;
+    if message.channel.get_id() != channel_id {
+        error!(rate_limiter, NOT_FOUND);
+    }
     message
         .clear_reactions(&mut db)
         .await
@@ -0,0 +1,72 @@
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    ids::IdGenerator,
+    models::{ErrorResponse, GroupChannel, GroupChannelCreate, ServerPayload},
+    Conf,
+};
+use tokio::sync::Mutex;
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Create a group channel with a set of users.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -H "Authorization: <token>" \
+///   --json '{"name":"abandoned project","members":[4080402038777]}' \
+///   https://api.eludris.gay/channels/groups
+///
+/// {
+///   "id": 4080402038800,
+///   "owner": {
+///     "id": 4080402038776,
+///     "username": "sinsaint",
+///     "social_credit": 0,
+///     "badges": 0,
+///     "permissions": 0
+///   },
+///   "name": "abandoned project",
+///   "members": [ ... ]
+/// }
+/// ```
+#[autodoc("/channels", category = "Channels")]
+#[post("/groups", data = "<group>")]
+pub async fn create_group(
+    group: Json<GroupChannelCreate>,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    id_generator: &State<Mutex<IdGenerator>>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Result<Json<GroupChannel>, ErrorResponse>> {
+    let mut rate_limiter =
+        RateLimiter::new("create_group", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+    let mut cache = cache.into_inner();
+
+    let group = GroupChannel::create(
+        group.into_inner(),
+        session.0.user_id,
+        &mut *id_generator.lock().await,
+        &mut db,
+        &mut cache,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
+
+    cache
+        .publish::<&str, String, ()>(
+            "eludris-events",
+            serde_json::to_string(&ServerPayload::GroupCreate(group.clone())).unwrap(),
+        )
+        .await
+        .unwrap();
+
+    rate_limiter.wrap_response(Ok(Json(group)))
+}
@@ -6,7 +6,9 @@ use rocket_db_pools::deadpool_redis::redis::AsyncCommands;
 use rocket_db_pools::Connection;
 use todel::http::{TokenAuth, DB};
 use todel::ids::IdGenerator;
-use todel::models::{ErrorResponse, Message, MessageCreate, ServerPayload, SphereChannel};
+use todel::models::{
+    Channel, ErrorResponse, Message, MessageCreate, ServerPayload, SpherePermission,
+};
 use todel::Conf;
 use tokio::sync::Mutex;
 
@@ -40,17 +42,19 @@ pub async fn create_message(
 ) -> RateLimitedRouteResponse<Result<Json<Message>, ErrorResponse>> {
     let mut rate_limiter = RateLimiter::new(
         "create_message",
-        format!("{}:{}", channel_id, session.0.user_id),
+        format!("{}:{}", channel_id, session.0.rate_limit_identifier()),
         conf.inner(),
     );
     rate_limiter.process_rate_limit(&mut cache).await?;
 
-    if !SphereChannel::has_member(channel_id, session.0.user_id, &mut db)
-        .await
-        .map_err(|err| rate_limiter.add_headers(err))?
-    {
-        error!(rate_limiter, UNAUTHORIZED);
-    }
+    Channel::require_permission(
+        channel_id,
+        session.0.user_id,
+        SpherePermission::SendMessages,
+        &mut db,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
 
     let mut cache = cache.into_inner();
     let message = Message::create(
@@ -67,7 +71,8 @@ pub async fn create_message(
     cache
         .publish::<&str, String, ()>(
             "eludris-events",
-            serde_json::to_string(&ServerPayload::MessageCreate(message.clone())).unwrap(),
+            serde_json::to_string(&ServerPayload::MessageCreate(Box::new(message.clone())))
+                .unwrap(),
         )
         .await
         .unwrap();
@@ -0,0 +1,93 @@
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    ids::IdGenerator,
+    models::{Channel, ErrorResponse, Post, PostCreate, ServerPayload, SpherePermission},
+    Conf,
+};
+use tokio::sync::Mutex;
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Create a post in a forum channel, or a comment on one if a `parent_id` is provided.
+///
+/// -- STATUS: 201
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -H "Authorization: <token>" \
+///   --json '{"title":"I made a thing!","flair":"showcase","content":"It'"'"'s a rock, but it thinks."}' \
+///   https://api.eludris.gay/channels/4080402038801/posts
+///
+/// {
+///   "id": 5490083823641,
+///   "channel_id": 4080402038801,
+///   "author": {
+///     "id": 48615849987333,
+///     "username": "mlynar",
+///     "social_credit": 9999,
+///     "badges": 256,
+///     "permissions": 8
+///   },
+///   "title": "I made a thing!",
+///   "flair": "showcase",
+///   "content": "It's a rock, but it thinks.",
+///   "attachments": [],
+///   "embeds": [],
+///   "score": 0,
+///   "comment_count": 0
+/// }
+/// ```
+#[autodoc("/channels", category = "Posts")]
+#[post("/<channel_id>/posts", data = "<post>")]
+pub async fn create_post(
+    channel_id: u64,
+    post: Json<PostCreate>,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    id_generator: &State<Mutex<IdGenerator>>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Result<Json<Post>, ErrorResponse>> {
+    let mut rate_limiter = RateLimiter::new(
+        "create_post",
+        format!("{}:{}", channel_id, session.0.rate_limit_identifier()),
+        conf.inner(),
+    );
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    Channel::require_permission(
+        channel_id,
+        session.0.user_id,
+        SpherePermission::SendMessages,
+        &mut db,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
+
+    let mut cache = cache.into_inner();
+    let post = Post::create(
+        post.into_inner(),
+        channel_id,
+        session.0.user_id,
+        &mut *id_generator.lock().await,
+        &mut db,
+        &mut cache,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
+
+    cache
+        .publish::<&str, String, ()>(
+            "eludris-events",
+            serde_json::to_string(&ServerPayload::PostCreate(Box::new(post.clone()))).unwrap(),
+        )
+        .await
+        .unwrap();
+
+    rate_limiter.wrap_response(Ok(Json(post)))
+}
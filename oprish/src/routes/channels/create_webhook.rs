@@ -0,0 +1,77 @@
+use rand::rngs::StdRng;
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::Connection;
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    ids::IdGenerator,
+    models::{SphereChannel, SpherePermission, Webhook, WebhookCreate, WebhookCreated},
+    Conf,
+};
+use tokio::sync::Mutex;
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Create a webhook in a sphere channel.
+///
+/// The webhook's token is only returned once, it's needed to send messages through the webhook.
+///
+/// Requires the `MANAGE_WEBHOOKS` permission.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -H "Authorization: <token>" \
+///   --json '{"name":"GitHub"}' \
+///   https://api.eludris.gay/channels/4080402038789/webhooks
+///
+/// {
+///   "token": "dWV4Q2xZb3R6c1pMbkNRQ3RVWkhqZ0x5TW1aWFlqRkE",
+///   "webhook": {
+///     "id": 5490083823650,
+///     "channel_id": 4080402038789,
+///     "creator_id": 48615849987333,
+///     "name": "GitHub"
+///   }
+/// }
+/// ```
+#[autodoc("/channels", category = "Webhooks")]
+#[post("/<channel_id>/webhooks", data = "<webhook>")]
+pub async fn create_webhook(
+    webhook: Json<WebhookCreate>,
+    channel_id: u64,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    rng: &State<Mutex<StdRng>>,
+    id_generator: &State<Mutex<IdGenerator>>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Json<WebhookCreated>> {
+    let mut rate_limiter =
+        RateLimiter::new("create_webhook", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    SphereChannel::require_permission(
+        channel_id,
+        session.0.user_id,
+        SpherePermission::ManageWebhooks,
+        &mut db,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
+
+    rate_limiter.wrap_response(Json(
+        Webhook::create(
+            webhook.into_inner(),
+            channel_id,
+            session.0.user_id,
+            &mut *rng.lock().await,
+            &mut *id_generator.lock().await,
+            &mut db,
+        )
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?,
+    ))
+}
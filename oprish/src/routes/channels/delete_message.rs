@@ -2,7 +2,7 @@ use rocket::{http::Status, response::status::Custom, State};
 use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
 use todel::{
     http::{Cache, TokenAuth, DB},
-    models::{ErrorResponse, Message, ServerPayload, SphereChannel},
+    models::{Channel, ErrorResponse, Message, ServerPayload, SpherePermission},
     Conf,
 };
 
@@ -18,18 +18,24 @@ pub async fn delete_message(
     mut db: Connection<DB>,
     session: TokenAuth,
 ) -> RateLimitedRouteResponse<Result<Custom<()>, ErrorResponse>> {
-    let mut rate_limiter = RateLimiter::new("delete_message", session.0.user_id, conf);
-    if !SphereChannel::has_member(channel_id, session.0.user_id, &mut db)
-        .await
-        .map_err(|err| rate_limiter.add_headers(err))?
-    {
-        error!(rate_limiter, UNAUTHORIZED);
-    }
+    let mut rate_limiter =
+        RateLimiter::new("delete_message", session.0.rate_limit_identifier(), conf);
+    Channel::require_permission(
+        channel_id,
+        session.0.user_id,
+        SpherePermission::ViewChannels,
+        &mut db,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
     rate_limiter.process_rate_limit(&mut cache).await?;
     let mut cache = cache.into_inner();
     let message = Message::get(message_id, &mut db, &mut cache)
         .await
         .map_err(|err| rate_limiter.add_headers(err))?;
+    if message.channel.get_id() != channel_id {
+        error!(rate_limiter, NOT_FOUND);
+    }
 
     cache
         .publish::<&str, String, ()>(
@@ -0,0 +1,79 @@
+use rocket::{http::Status, response::status::Custom, State};
+use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{Channel, ErrorResponse, Post, ServerPayload, SpherePermission},
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Delete a post or a comment along with all of its comments.
+///
+/// This requires the `MANAGE_MESSAGES` permission unless you're the post's author.
+///
+/// -- STATUS: 204
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -X DELETE \
+///   -H "Authorization: <token>" \
+///   https://api.eludris.gay/channels/4080402038801/posts/5490083823641
+/// ```
+#[autodoc("/channels", category = "Posts")]
+#[delete("/<channel_id>/posts/<post_id>")]
+pub async fn delete_post(
+    channel_id: u64,
+    post_id: u64,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Result<Custom<()>, ErrorResponse>> {
+    let mut rate_limiter = RateLimiter::new("delete_post", session.0.rate_limit_identifier(), conf);
+    Channel::require_permission(
+        channel_id,
+        session.0.user_id,
+        SpherePermission::ViewChannels,
+        &mut db,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    let mut cache = cache.into_inner();
+    let post = Post::get_unpopulated(post_id, channel_id, &mut db, &mut cache)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+    if post.author.id != session.0.user_id {
+        Channel::require_permission(
+            channel_id,
+            session.0.user_id,
+            SpherePermission::ManageMessages,
+            &mut db,
+        )
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+    }
+
+    post.delete(&mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+
+    cache
+        .publish::<&str, String, ()>(
+            "eludris-events",
+            serde_json::to_string(&ServerPayload::PostDelete {
+                channel_id,
+                post_id,
+            })
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    rate_limiter.wrap_response(Ok(Custom(Status::NoContent, ())))
+}
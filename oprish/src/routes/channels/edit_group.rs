@@ -0,0 +1,71 @@
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{ErrorResponse, GroupChannel, GroupChannelEdit, ServerPayload},
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Edit a group channel.
+///
+/// Every member of the group can rename it or change its icon and topic, only its owner can
+/// transfer its ownership.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -H "Authorization: <token>" \
+///   -X PATCH \
+///   --json '{"name":"finished project"}' \
+///   https://api.eludris.gay/channels/4080402038800
+///
+/// {
+///   "id": 4080402038800,
+///   "owner": {
+///     "id": 4080402038776,
+///     "username": "sinsaint",
+///     "social_credit": 0,
+///     "badges": 0,
+///     "permissions": 0
+///   },
+///   "name": "finished project",
+///   "members": [ ... ]
+/// }
+/// ```
+#[autodoc("/channels", category = "Channels")]
+#[patch("/<channel_id>", data = "<edit>")]
+pub async fn edit_group(
+    channel_id: u64,
+    edit: Json<GroupChannelEdit>,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Result<Json<GroupChannel>, ErrorResponse>> {
+    let mut rate_limiter = RateLimiter::new("edit_group", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+    let mut cache = cache.into_inner();
+
+    let data = GroupChannel::edit(edit.into_inner(), channel_id, session.0.user_id, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+
+    cache
+        .publish::<&str, String, ()>(
+            "eludris-events",
+            serde_json::to_string(&ServerPayload::GroupUpdate { data, channel_id }).unwrap(),
+        )
+        .await
+        .unwrap();
+
+    rate_limiter.wrap_response(
+        GroupChannel::get(channel_id, &mut db, &mut cache)
+            .await
+            .map(Json),
+    )
+}
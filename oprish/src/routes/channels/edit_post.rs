@@ -0,0 +1,93 @@
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{Channel, ErrorResponse, Post, PostEdit, ServerPayload, SpherePermission},
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Edit one of your posts or comments.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -X PATCH \
+///   -H "Authorization: <token>" \
+///   --json '{"flair":null}' \
+///   https://api.eludris.gay/channels/4080402038801/posts/5490083823641
+///
+/// {
+///   "id": 5490083823641,
+///   "channel_id": 4080402038801,
+///   "author": {
+///     "id": 48615849987333,
+///     "username": "mlynar",
+///     "social_credit": 9999,
+///     "badges": 256,
+///     "permissions": 8
+///   },
+///   "title": "I made a thing!",
+///   "content": "It's a rock, but it thinks.",
+///   "attachments": [],
+///   "embeds": [],
+///   "score": 42,
+///   "comment_count": 1
+/// }
+/// ```
+#[autodoc("/channels", category = "Posts")]
+#[patch("/<channel_id>/posts/<post_id>", data = "<edit>")]
+pub async fn edit_post(
+    edit: Json<PostEdit>,
+    channel_id: u64,
+    post_id: u64,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Result<Json<Post>, ErrorResponse>> {
+    let mut rate_limiter = RateLimiter::new("edit_post", session.0.rate_limit_identifier(), conf);
+    Channel::require_permission(
+        channel_id,
+        session.0.user_id,
+        SpherePermission::ViewChannels,
+        &mut db,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    let mut cache = cache.into_inner();
+    let mut edit = edit.into_inner();
+    let mut post = Post::get_unpopulated(post_id, channel_id, &mut db, &mut cache)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+    if post.author.id != session.0.user_id {
+        error!(rate_limiter, FORBIDDEN);
+    }
+    edit.validate()
+        .map_err(|err| rate_limiter.add_headers(err))?;
+
+    post.edit(edit.clone(), &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+
+    cache
+        .publish::<&str, String, ()>(
+            "eludris-events",
+            serde_json::to_string(&ServerPayload::PostUpdate {
+                channel_id,
+                post_id,
+                data: edit,
+            })
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    rate_limiter.wrap_response(Ok(Json(post)))
+}
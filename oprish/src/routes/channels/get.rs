@@ -39,7 +39,7 @@ pub async fn get_channel(
 ) -> RateLimitedRouteResponse<Json<SphereChannel>> {
     let mut rate_limiter;
     if let Some(session) = &session {
-        rate_limiter = RateLimiter::new("get_channel", session.0.user_id, conf);
+        rate_limiter = RateLimiter::new("get_channel", session.0.rate_limit_identifier(), conf);
     } else {
         rate_limiter = RateLimiter::new("guest_get_channel", ip, conf);
     }
@@ -2,7 +2,7 @@ use rocket::{serde::json::Json, State};
 use rocket_db_pools::Connection;
 use todel::{
     http::{Cache, TokenAuth, DB},
-    models::{ErrorResponse, Message, SphereChannel},
+    models::{Channel, ErrorResponse, Message, SpherePermission},
     Conf,
 };
 
@@ -18,17 +18,21 @@ pub async fn get_message(
     mut db: Connection<DB>,
     session: TokenAuth,
 ) -> RateLimitedRouteResponse<Result<Json<Message>, ErrorResponse>> {
-    let mut rate_limiter = RateLimiter::new("get_message", session.0.user_id, conf);
-    if !SphereChannel::has_member(channel_id, session.0.user_id, &mut db)
+    let mut rate_limiter = RateLimiter::new("get_message", session.0.rate_limit_identifier(), conf);
+    Channel::require_permission(
+        channel_id,
+        session.0.user_id,
+        SpherePermission::ViewChannels,
+        &mut db,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
+    rate_limiter.process_rate_limit(&mut cache).await?;
+    let message = Message::get(message_id, &mut db, &mut cache.into_inner())
         .await
-        .map_err(|err| rate_limiter.add_headers(err))?
-    {
-        error!(rate_limiter, UNAUTHORIZED);
+        .map_err(|err| rate_limiter.add_headers(err))?;
+    if message.channel.get_id() != channel_id {
+        error!(rate_limiter, NOT_FOUND);
     }
-    rate_limiter.process_rate_limit(&mut cache).await?;
-    rate_limiter.wrap_response(Ok(Json(
-        Message::get(message_id, &mut db, &mut cache.into_inner())
-            .await
-            .map_err(|err| rate_limiter.add_headers(err))?,
-    )))
+    rate_limiter.wrap_response(Ok(Json(message)))
 }
@@ -2,7 +2,7 @@ use rocket::{serde::json::Json, State};
 use rocket_db_pools::Connection;
 use todel::{
     http::{Cache, TokenAuth, DB},
-    models::{ErrorResponse, Message, SphereChannel},
+    models::{Channel, ErrorResponse, Message, SpherePermission},
     Conf,
 };
 
@@ -42,13 +42,16 @@ pub async fn get_messages(
     after: Option<u64>,
     limit: Option<u32>,
 ) -> RateLimitedRouteResponse<Result<Json<Vec<Message>>, ErrorResponse>> {
-    let mut rate_limiter = RateLimiter::new("get_messages", session.0.user_id, conf);
-    if !SphereChannel::has_member(channel_id, session.0.user_id, &mut db)
-        .await
-        .map_err(|err| rate_limiter.add_headers(err))?
-    {
-        error!(rate_limiter, UNAUTHORIZED);
-    }
+    let mut rate_limiter =
+        RateLimiter::new("get_messages", session.0.rate_limit_identifier(), conf);
+    Channel::require_permission(
+        channel_id,
+        session.0.user_id,
+        SpherePermission::ViewChannels,
+        &mut db,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
     rate_limiter.process_rate_limit(&mut cache).await?;
     rate_limiter.wrap_response(
         Message::get_history(
@@ -0,0 +1,84 @@
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::Connection;
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{Channel, ErrorResponse, Post, SpherePermission},
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Get a post or a comment along with all of its nested comments.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -H "Authorization: <token>" \
+///   https://api.eludris.gay/channels/4080402038801/posts/5490083823641
+///
+/// {
+///   "id": 5490083823641,
+///   "channel_id": 4080402038801,
+///   "author": {
+///     "id": 48615849987333,
+///     "username": "mlynar",
+///     "social_credit": 9999,
+///     "badges": 256,
+///     "permissions": 8
+///   },
+///   "title": "I made a thing!",
+///   "content": "It's a rock, but it thinks.",
+///   "attachments": [],
+///   "embeds": [],
+///   "score": 42,
+///   "comment_count": 1,
+///   "comments": [
+///     {
+///       "id": 5490083823644,
+///       "channel_id": 4080402038801,
+///       "author": {
+///         "id": 48615849987334,
+///         "username": "barbaz",
+///         "social_credit": 3,
+///         "badges": 0,
+///         "permissions": 0
+///       },
+///       "parent_id": 5490083823641,
+///       "content": "What does it think about?",
+///       "attachments": [],
+///       "embeds": [],
+///       "score": 1,
+///       "comment_count": 0
+///     }
+///   ]
+/// }
+/// ```
+#[autodoc("/channels", category = "Posts")]
+#[get("/<channel_id>/posts/<post_id>")]
+pub async fn get_post(
+    channel_id: u64,
+    post_id: u64,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Result<Json<Post>, ErrorResponse>> {
+    let mut rate_limiter = RateLimiter::new("get_post", session.0.rate_limit_identifier(), conf);
+    Channel::require_permission(
+        channel_id,
+        session.0.user_id,
+        SpherePermission::ViewChannels,
+        &mut db,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
+    rate_limiter.process_rate_limit(&mut cache).await?;
+    rate_limiter.wrap_response(
+        Post::get(post_id, channel_id, &mut db, &mut cache.into_inner())
+            .await
+            .map(Json),
+    )
+}
@@ -0,0 +1,85 @@
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::Connection;
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{Channel, ErrorResponse, Post, PostSort, SpherePermission},
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Get a forum channel's posts.
+///
+/// The posts' comments are not included, use [`get_post`] to get them.
+///
+/// The `sort` query parameter can be either `HOT`, `TOP` or `NEW` and defaults to `HOT`.
+///
+/// This endpoint supports pagination via the `before`/`offset`/`limit` query parameters.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -H "Authorization: <token>" \
+///   https://api.eludris.gay/channels/4080402038801/posts?sort=TOP&limit=1
+///
+/// [
+///   {
+///     "id": 5490083823641,
+///     "channel_id": 4080402038801,
+///     "author": {
+///       "id": 48615849987333,
+///       "username": "mlynar",
+///       "social_credit": 9999,
+///       "badges": 256,
+///       "permissions": 8
+///     },
+///     "title": "I made a thing!",
+///     "flair": "showcase",
+///     "content": "It's a rock, but it thinks.",
+///     "attachments": [],
+///     "embeds": [],
+///     "score": 42,
+///     "comment_count": 3
+///   }
+/// ]
+/// ```
+#[autodoc("/channels", category = "Posts")]
+#[get("/<channel_id>/posts?<sort>&<before>&<offset>&<limit>")]
+pub async fn get_posts(
+    channel_id: u64,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    session: TokenAuth,
+    sort: Option<PostSort>,
+    before: Option<u64>,
+    offset: Option<u32>,
+    limit: Option<u32>,
+) -> RateLimitedRouteResponse<Result<Json<Vec<Post>>, ErrorResponse>> {
+    let mut rate_limiter = RateLimiter::new("get_posts", session.0.rate_limit_identifier(), conf);
+    Channel::require_permission(
+        channel_id,
+        session.0.user_id,
+        SpherePermission::ViewChannels,
+        &mut db,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
+    rate_limiter.process_rate_limit(&mut cache).await?;
+    rate_limiter.wrap_response(
+        Post::get_all(
+            channel_id,
+            sort.unwrap_or_default(),
+            limit.unwrap_or(25),
+            before,
+            offset,
+            &mut db,
+            &mut cache.into_inner(),
+        )
+        .await
+        .map(Json),
+    )
+}
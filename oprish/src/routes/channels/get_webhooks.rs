@@ -0,0 +1,61 @@
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::Connection;
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{SphereChannel, SpherePermission, Webhook},
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Get all of a channel's webhooks.
+///
+/// Requires the `MANAGE_WEBHOOKS` permission.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -H "Authorization: <token>" \
+///   https://api.eludris.gay/channels/4080402038789/webhooks
+///
+/// [
+///   {
+///     "id": 5490083823650,
+///     "channel_id": 4080402038789,
+///     "creator_id": 48615849987333,
+///     "name": "GitHub",
+///     "avatar": 2255112175647
+///   }
+/// ]
+/// ```
+#[autodoc("/channels", category = "Webhooks")]
+#[get("/<channel_id>/webhooks")]
+pub async fn get_webhooks(
+    channel_id: u64,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Json<Vec<Webhook>>> {
+    let mut rate_limiter =
+        RateLimiter::new("get_webhooks", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    SphereChannel::require_permission(
+        channel_id,
+        session.0.user_id,
+        SpherePermission::ManageWebhooks,
+        &mut db,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
+
+    rate_limiter.wrap_response(Json(
+        Webhook::get_all(channel_id, &mut db)
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?,
+    ))
+}
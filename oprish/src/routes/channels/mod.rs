@@ -1,12 +1,26 @@
+pub mod ack_message;
+pub mod add_group_member;
 pub mod add_reaction;
 pub mod clear_reactions;
+pub mod create_group;
 pub mod create_message;
+pub mod create_post;
+pub mod create_webhook;
 pub mod delete_message;
+pub mod delete_post;
+pub mod edit_group;
 pub mod edit_message;
+pub mod edit_post;
 pub mod get;
 pub mod get_message;
 pub mod get_messages;
+pub mod get_post;
+pub mod get_posts;
+pub mod get_webhooks;
+pub mod remove_group_member;
+pub mod remove_post_vote;
 pub mod remove_reaction;
+pub mod vote_post;
 
 use rocket::Route;
 
@@ -21,5 +35,19 @@ pub fn get_routes() -> Vec<Route> {
         add_reaction::add_reaction,
         remove_reaction::remove_reaction,
         clear_reactions::clear_reactions,
+        ack_message::ack_message,
+        create_group::create_group,
+        edit_group::edit_group,
+        add_group_member::add_group_member,
+        remove_group_member::remove_group_member,
+        get_posts::get_posts,
+        get_post::get_post,
+        create_post::create_post,
+        edit_post::edit_post,
+        delete_post::delete_post,
+        vote_post::vote_post,
+        remove_post_vote::remove_post_vote,
+        get_webhooks::get_webhooks,
+        create_webhook::create_webhook,
     ]
 }
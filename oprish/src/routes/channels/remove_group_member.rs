@@ -0,0 +1,90 @@
+use rocket::{http::Status, response::status::Custom, State};
+use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
+use todel::{
+    http::{Cache, TokenAuth, UserIdentifier, DB},
+    models::{ErrorResponse, GroupChannel, GroupChannelEdit, ServerPayload, User},
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Leave a group channel or remove a member from it using a [`UserIdentifier`].
+///
+/// Only the owner of a group can remove other members. If the owner leaves, the group's
+/// ownership is transferred to the member with the oldest account.
+///
+/// -- STATUS: 204
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -H "Authorization: <token>" \
+///   -X DELETE \
+///   https://api.eludris.gay/channels/4080402038800/members/@me
+/// ```
+#[autodoc("/channels", category = "Channels")]
+#[delete("/<channel_id>/members/<user_identifier>")]
+pub async fn remove_group_member(
+    channel_id: u64,
+    user_identifier: UserIdentifier,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Result<Custom<()>, ErrorResponse>> {
+    let mut rate_limiter = RateLimiter::new(
+        "remove_group_member",
+        session.0.rate_limit_identifier(),
+        conf,
+    );
+    rate_limiter.process_rate_limit(&mut cache).await?;
+    let mut cache = cache.into_inner();
+    let user_id = match user_identifier {
+        UserIdentifier::Me => session.0.user_id,
+        UserIdentifier::ID(id) => id,
+        UserIdentifier::Username(username) => {
+            User::get_username(&username, None, &mut db, &mut cache)
+                .await
+                .map_err(|err| rate_limiter.add_headers(err))?
+                .id
+        }
+    };
+
+    let new_owner_id = GroupChannel::remove_member(channel_id, user_id, session.0.user_id, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+
+    cache
+        .publish::<&str, String, ()>(
+            "eludris-events",
+            serde_json::to_string(&ServerPayload::GroupMemberRemove {
+                user_id,
+                channel_id,
+            })
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+    if let Some(owner_id) = new_owner_id {
+        cache
+            .publish::<&str, String, ()>(
+                "eludris-events",
+                serde_json::to_string(&ServerPayload::GroupUpdate {
+                    data: GroupChannelEdit {
+                        name: None,
+                        icon: None,
+                        topic: None,
+                        owner_id: Some(owner_id),
+                    },
+                    channel_id,
+                })
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+    }
+
+    rate_limiter.wrap_response(Ok(Custom(Status::NoContent, ())))
+}
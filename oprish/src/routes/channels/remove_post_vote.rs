@@ -0,0 +1,88 @@
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{Channel, ErrorResponse, Post, ServerPayload, SpherePermission, User},
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Remove your vote from a post or a comment.
+///
+/// The post's author gets a `USER_UPDATE` event dispatched with their new social credit.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -X DELETE \
+///   -H "Authorization: <token>" \
+///   https://api.eludris.gay/channels/4080402038801/posts/5490083823641/vote
+///
+/// {
+///   "id": 5490083823641,
+///   "channel_id": 4080402038801,
+///   "author": {
+///     "id": 48615849987333,
+///     "username": "mlynar",
+///     "social_credit": 9999,
+///     "badges": 256,
+///     "permissions": 8
+///   },
+///   "title": "I made a thing!",
+///   "content": "It's a rock, but it thinks.",
+///   "attachments": [],
+///   "embeds": [],
+///   "score": 42,
+///   "comment_count": 1
+/// }
+/// ```
+#[autodoc("/channels", category = "Posts")]
+#[delete("/<channel_id>/posts/<post_id>/vote")]
+pub async fn remove_post_vote(
+    channel_id: u64,
+    post_id: u64,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Result<Json<Post>, ErrorResponse>> {
+    let mut rate_limiter =
+        RateLimiter::new("remove_post_vote", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+    Channel::require_permission(
+        channel_id,
+        session.0.user_id,
+        SpherePermission::ViewChannels,
+        &mut db,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
+
+    let mut cache = cache.into_inner();
+    let mut post = Post::get_unpopulated(post_id, channel_id, &mut db, &mut cache)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+    if post
+        .vote(session.0.user_id, None, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?
+    {
+        let author = User::get_unfiltered(post.author.id, &mut db)
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?;
+        post.author.social_credit = author.social_credit;
+        cache
+            .publish::<&str, String, ()>(
+                "eludris-events",
+                serde_json::to_string(&ServerPayload::UserUpdate(author)).unwrap(),
+            )
+            .await
+            .unwrap();
+    }
+
+    rate_limiter.wrap_response(Ok(Json(post)))
+}
@@ -1,10 +1,14 @@
 use rocket::{serde::json::Json, State};
 use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
 use todel::{
-    http::{Cache, TokenAuth, DB},
-    models::{Emoji, EmojiEdit, ErrorResponse, ServerPayload, Sphere},
+    http::{AuditLogReason, Cache, TokenAuth, DB},
+    ids::IdGenerator,
+    models::{
+        AuditLogActor, Emoji, EmojiEdit, ErrorResponse, ServerPayload, Sphere, SpherePermission,
+    },
     Conf,
 };
+use tokio::sync::Mutex;
 
 use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
 
@@ -16,9 +20,11 @@ pub async fn edit_emoji(
     conf: &State<Conf>,
     mut cache: Connection<Cache>,
     mut db: Connection<DB>,
+    reason: AuditLogReason,
+    id_generator: &State<Mutex<IdGenerator>>,
     session: TokenAuth,
 ) -> RateLimitedRouteResponse<Result<Json<Emoji>, ErrorResponse>> {
-    let mut rate_limiter = RateLimiter::new("edit_emoji", session.0.user_id, conf);
+    let mut rate_limiter = RateLimiter::new("edit_emoji", session.0.rate_limit_identifier(), conf);
     rate_limiter.process_rate_limit(&mut cache).await?;
 
     let edit = edit.into_inner();
@@ -36,12 +42,21 @@ pub async fn edit_emoji(
             })
         })?;
 
-    if sphere.owner_id != session.0.user_id {
-        return Err(rate_limiter.add_headers(error!(FORBIDDEN)));
-    }
+    sphere
+        .require_permission(session.0.user_id, SpherePermission::ManageEmojis, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
 
     emoji
-        .edit(&edit, &mut db)
+        .edit(
+            &edit,
+            AuditLogActor {
+                user_id: session.0.user_id,
+                reason: reason.0,
+                id_generator: &mut *id_generator.lock().await,
+            },
+            &mut db,
+        )
         .await
         .map_err(|err| rate_limiter.add_headers(err))?;
 
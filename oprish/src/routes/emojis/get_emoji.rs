@@ -20,7 +20,7 @@ pub async fn get_emoji(
 ) -> RateLimitedRouteResponse<Json<Emoji>> {
     let mut rate_limiter;
     if let Some(session) = &session {
-        rate_limiter = RateLimiter::new("get_emoji", session.0.user_id, conf);
+        rate_limiter = RateLimiter::new("get_emoji", session.0.rate_limit_identifier(), conf);
     } else {
         rate_limiter = RateLimiter::new("guest_get_emoji", ip, conf);
     }
@@ -0,0 +1,7 @@
+use rocket::Route;
+
+mod use_invite;
+
+pub fn get_routes() -> Vec<Route> {
+    routes![use_invite::use_invite]
+}
@@ -0,0 +1,76 @@
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{Invite, ServerPayload, Sphere},
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Join a sphere using an invite code.
+///
+/// Invites work even if the sphere is invite-only or not discoverable.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl --request POST \
+///   -H "Authorization: <token>" \
+///   https://api.eludris.gay/invites/hK3vQz8p
+///
+/// {
+///   "id": 4204171493377,
+///   "owner_id": 4203748065281,
+///   "slug": "horse",
+///   "type": "HYBRID",
+///   "badges": 0,
+///   "default_permissions": 18176,
+///   "discoverable": false,
+///   "invite_only": true,
+///   "channels": [{
+///       "type": "TEXT",
+///       "id": 4204171493378,
+///       "sphere_id": 4204171493377,
+///       "name": "general",
+///       "position": 0
+///     }]
+/// }
+/// ```
+#[autodoc("/invites", category = "Invites")]
+#[post("/<code>")]
+pub async fn use_invite(
+    code: &str,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Json<Sphere>> {
+    let mut rate_limiter = RateLimiter::new("use_invite", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+    let mut cache = cache.into_inner();
+    let invite = Invite::get(code, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+    let sphere = Sphere::get(invite.sphere_id, &mut db, &mut cache)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+    let member = sphere
+        .add_member(session.0.user_id, Some(&invite.code), &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+    cache
+        .publish::<&str, String, ()>(
+            "eludris-events",
+            serde_json::to_string(&ServerPayload::SphereMemberJoin {
+                user: member.user,
+                sphere_id: sphere.id,
+            })
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+    rate_limiter.wrap_response(Json(sphere))
+}
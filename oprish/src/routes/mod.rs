@@ -1,8 +1,12 @@
+pub mod applications;
 pub mod channels;
 pub mod emojis;
+pub mod invites;
+pub mod oauth2;
 pub mod sessions;
 pub mod spheres;
 pub mod users;
+pub mod webhooks;
 
 use rocket::{serde::json::Json, Route, State};
 use rocket_db_pools::Connection;
@@ -35,6 +39,7 @@ use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter}; // poggers
 ///   "effis_url": "https://cdn.eludris.gay",
 ///   "file_size": 20000000,
 ///   "attachment_file_size": 25000000,
+///   "thumbnail_sizes": [32, 64, 128, 256, 512],
 ///   "rate_limits": {
 ///     "oprish": {
 ///       "info": {
@@ -0,0 +1,121 @@
+use rand::rngs::StdRng;
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::Connection;
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{ErrorResponse, OAuthAuthorization, OAuthAuthorize, OAuthAuthorized},
+    Conf,
+};
+use tokio::sync::Mutex;
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Get the details of an OAuth2 authorization request so that the user can be asked for their
+/// consent.
+///
+/// This takes the query parameters of the authorization request as described in RFC 6749 and
+/// RFC 7636, clients are expected to forward them as is.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -H "Authorization: <token>" \
+///   "https://api.eludris.gay/oauth2/authorize?response_type=code&client_id=48615849987334&redirect_uri=https%3A%2F%2Fpengin.example%2Fcallback&scope=identify%20spheres.read&state=xyz&code_challenge=E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM&code_challenge_method=S256"
+///
+/// {
+///   "application": {
+///     "id": 48615849987334,
+///     "owner_id": 48615849987333,
+///     "redirect_uris": ["https://pengin.example/callback"],
+///     "bot": {
+///       "id": 48615849987334,
+///       "username": "pengin-bot",
+///       "social_credit": 0,
+///       "status": {
+///         "type": "ONLINE"
+///       },
+///       "badges": 1,
+///       "permissions": 0
+///     }
+///   },
+///   "scopes": ["identify", "spheres.read"]
+/// }
+/// ```
+#[autodoc("/oauth2", category = "OAuth2")]
+#[get("/authorize?<authorize..>")]
+pub async fn get_authorization(
+    authorize: OAuthAuthorize,
+    conf: &State<Conf>,
+    mut db: Connection<DB>,
+    mut cache: Connection<Cache>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Json<OAuthAuthorization>> {
+    let mut rate_limiter = RateLimiter::new(
+        "get_oauth_authorization",
+        session.0.rate_limit_identifier(),
+        conf,
+    );
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    rate_limiter.wrap_response(Json(
+        authorize
+            .get_authorization(&mut db)
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?,
+    ))
+}
+
+/// Approve an OAuth2 authorization request, the user should then be redirected to the returned
+/// URI which contains the authorization code.
+///
+/// This takes the same query parameters as getting the authorization. Bots can't authorize
+/// applications.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl --request POST \
+///   -H "Authorization: <token>" \
+///   "https://api.eludris.gay/oauth2/authorize?response_type=code&client_id=48615849987334&redirect_uri=https%3A%2F%2Fpengin.example%2Fcallback&scope=identify%20spheres.read&state=xyz&code_challenge=E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM&code_challenge_method=S256"
+///
+/// {
+///   "redirect_uri": "https://pengin.example/callback?code=Q2x8pZkWm3rV9tYb&state=xyz"
+/// }
+/// ```
+#[autodoc("/oauth2", category = "OAuth2")]
+#[post("/authorize?<authorize..>")]
+pub async fn authorize(
+    authorize: OAuthAuthorize,
+    conf: &State<Conf>,
+    rng: &State<Mutex<StdRng>>,
+    mut db: Connection<DB>,
+    mut cache: Connection<Cache>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Json<OAuthAuthorized>> {
+    let mut rate_limiter = RateLimiter::new(
+        "authorize_application",
+        session.0.rate_limit_identifier(),
+        conf,
+    );
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    if session.0.bot {
+        return Err(rate_limiter.add_headers(error!(FORBIDDEN)));
+    }
+    rate_limiter.wrap_response(Json(
+        authorize
+            .approve(
+                session.0.user_id,
+                &mut *rng.lock().await,
+                &mut db,
+                &mut cache.into_inner(),
+            )
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?,
+    ))
+}
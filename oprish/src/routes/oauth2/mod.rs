@@ -0,0 +1,12 @@
+use rocket::Route;
+
+mod authorize;
+mod token;
+
+pub fn get_routes() -> Vec<Route> {
+    routes![
+        authorize::get_authorization,
+        authorize::authorize,
+        token::create_token,
+    ]
+}
@@ -0,0 +1,68 @@
+use rand::rngs::StdRng;
+use rocket::{form::Form, serde::json::Json, State};
+use rocket_db_pools::Connection;
+use todel::{
+    http::{Cache, ClientIP, DB},
+    ids::IdGenerator,
+    models::{OAuthToken, OAuthTokenCreate, Secret, Session},
+    Conf,
+};
+use tokio::sync::Mutex;
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Exchange an OAuth2 authorization code or refresh token for an access token.
+///
+/// This takes a form encoded body as described in RFC 6749. Access tokens show up in the user's
+/// sessions and stop working once that session is deleted.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -d grant_type=authorization_code \
+///   -d client_id=48615849987334 \
+///   -d code=Q2x8pZkWm3rV9tYb \
+///   -d redirect_uri=https://pengin.example/callback \
+///   -d code_verifier=dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk \
+///   https://api.eludris.gay/oauth2/token
+///
+/// {
+///   "access_token": "<token>",
+///   "token_type": "Bearer",
+///   "expires_in": 86400,
+///   "refresh_token": "<refresh token>",
+///   "scope": "identify spheres.read"
+/// }
+/// ```
+#[autodoc("/oauth2", category = "OAuth2")]
+#[post("/token", data = "<token>")]
+pub async fn create_token(
+    token: Form<OAuthTokenCreate>,
+    secret: &State<Secret>,
+    rng: &State<Mutex<StdRng>>,
+    id_generator: &State<Mutex<IdGenerator>>,
+    conf: &State<Conf>,
+    mut db: Connection<DB>,
+    mut cache: Connection<Cache>,
+    ip: ClientIP,
+) -> RateLimitedRouteResponse<Json<OAuthToken>> {
+    let mut rate_limiter = RateLimiter::new("create_oauth_token", &ip, conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    rate_limiter.wrap_response(Json(
+        Session::create_oauth(
+            token.into_inner(),
+            *ip,
+            secret,
+            &mut *rng.lock().await,
+            &mut *id_generator.lock().await,
+            &mut db,
+            &mut cache.into_inner(),
+        )
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?,
+    ))
+}
@@ -13,6 +13,9 @@ use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
 
 /// Create a new session.
 ///
+/// Users with two-factor authentication enabled also have to provide a `two_factor_code`, a
+/// `TWO_FACTOR_REQUIRED` error is returned if it's missing.
+///
 /// -----
 ///
 /// ### Example
@@ -34,7 +37,10 @@ use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
 ///     "user_id": 48615849987333,
 ///     "platform": "linux",
 ///     "client": "pilfer",
-///     "ip": "fc00:e10d:7150:b1gb:00b5:f00d:babe:1337"
+///     "ip": "fc00:e10d:7150:b1gb:00b5:f00d:babe:1337",
+///     "created_at": 1751300000,
+///     "last_used_at": 1751300000,
+///     "last_ip": "fc00:e10d:7150:b1gb:00b5:f00d:babe:1337"
 ///   }
 /// }
 /// ```
@@ -33,7 +33,8 @@ pub async fn delete_session(
     mut cache: Connection<Cache>,
     session: TokenAuth,
 ) -> RateLimitedRouteResponse<Custom<()>> {
-    let mut rate_limiter = RateLimiter::new("delete_session", session.0.user_id, conf);
+    let mut rate_limiter =
+        RateLimiter::new("delete_session", session.0.rate_limit_identifier(), conf);
     rate_limiter.process_rate_limit(&mut cache).await?;
 
     rate_limiter.wrap_response(Custom(
@@ -10,6 +10,9 @@ use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
 
 /// Get all sessions.
 ///
+/// Sessions which go unused for longer than the instance's idle timeout or outlive its maximum
+/// lifetime expire and are periodically removed, bot sessions never expire.
+///
 /// -----
 ///
 /// ### Example
@@ -25,14 +28,20 @@ use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
 ///     "user_id": 48615849987333,
 ///     "platform": "linux",
 ///     "client": "pilfer",
-///     "ip": "fc00:e10d:7150:b1gb:00b5:f00d:babe:1337"
+///     "ip": "fc00:e10d:7150:b1gb:00b5:f00d:babe:1337",
+///     "created_at": 1751300000,
+///     "last_used_at": 1751386400,
+///     "last_ip": "fc00:e10d:7150:b1gb:00b5:f00d:babe:1337"
 ///   },
 ///   {
 ///     "id": 2472278163867,
 ///     "user_id": 48615849987333,
 ///     "platform": "python",
 ///     "client": "velum",
-///     "ip": "127.0.0.1"
+///     "ip": "127.0.0.1",
+///     "created_at": 1751312345,
+///     "last_used_at": 1751312345,
+///     "last_ip": "127.0.0.1"
 ///   }
 /// ]
 /// ```
@@ -44,7 +53,8 @@ pub async fn get_sessions(
     mut cache: Connection<Cache>,
     session: TokenAuth,
 ) -> RateLimitedRouteResponse<Json<Vec<Session>>> {
-    let mut rate_limiter = RateLimiter::new("get_sessions", session.0.user_id, conf);
+    let mut rate_limiter =
+        RateLimiter::new("get_sessions", session.0.rate_limit_identifier(), conf);
     rate_limiter.process_rate_limit(&mut cache).await?;
 
     rate_limiter.wrap_response(Json(
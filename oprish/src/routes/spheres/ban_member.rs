@@ -0,0 +1,103 @@
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
+use todel::{
+    http::{AuditLogReason, Cache, TokenAuth, DB},
+    ids::IdGenerator,
+    models::{
+        AuditLogActor, ErrorResponse, ServerPayload, Sphere, SphereBan, SphereBanCreate,
+        SpherePermission,
+    },
+    Conf,
+};
+use tokio::sync::Mutex;
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Ban a user from a sphere, removing them from it if they're a member.
+///
+/// Banned users can't join the sphere again until their ban expires or they're unbanned.
+/// Banning an already banned user replaces their current ban.
+///
+/// Requires the `BAN_MEMBERS` permission and being above the user in the sphere's role hierarchy.
+///
+/// -- STATUS: 200
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl --request PUT \
+///   -H "Authorization: <token>" \
+///   --json '{"reason":"Posting gacha pulls in #general","duration":604800}' \
+///   https://api.eludris.gay/spheres/1234/bans/5678
+///
+/// {
+///   "user_id": 5678,
+///   "sphere_id": 1234,
+///   "moderator_id": 4321,
+///   "reason": "Posting gacha pulls in #general",
+///   "expires_at": 1750000000
+/// }
+/// ```
+#[autodoc("/spheres", category = "Members")]
+#[put("/<sphere_id>/bans/<user_id>", data = "<ban>")]
+pub async fn ban_member(
+    ban: Json<SphereBanCreate>,
+    sphere_id: u64,
+    user_id: u64,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    reason: AuditLogReason,
+    id_generator: &State<Mutex<IdGenerator>>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Json<SphereBan>> {
+    let mut rate_limiter = RateLimiter::new("ban_member", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    let sphere = Sphere::get_unpopulated(sphere_id, &mut db)
+        .await
+        .map_err(|err| {
+            rate_limiter.add_headers(if let ErrorResponse::NotFound { .. } = err {
+                error!(VALIDATION, "sphere", "Sphere doesn't exist")
+            } else {
+                err
+            })
+        })?;
+
+    if user_id == session.0.user_id {
+        return Err(rate_limiter.add_headers(error!(VALIDATION, "user", "You can't ban yourself")));
+    }
+    sphere
+        .require_permission(session.0.user_id, SpherePermission::BanMembers, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+    sphere
+        .require_above_member(session.0.user_id, user_id, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+
+    let ban = sphere
+        .ban_member(
+            user_id,
+            ban.into_inner(),
+            AuditLogActor {
+                user_id: session.0.user_id,
+                reason: reason.0,
+                id_generator: &mut *id_generator.lock().await,
+            },
+            &mut db,
+        )
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+
+    cache
+        .publish::<&str, String, ()>(
+            "eludris-events",
+            serde_json::to_string(&ServerPayload::SphereMemberBan { user_id, sphere_id }).unwrap(),
+        )
+        .await
+        .unwrap();
+
+    rate_limiter.wrap_response(Json(ban))
+}
@@ -55,7 +55,8 @@ pub async fn create_sphere(
     id_generator: &State<Mutex<IdGenerator>>,
     session: TokenAuth,
 ) -> RateLimitedRouteResponse<Json<Sphere>> {
-    let mut rate_limiter = RateLimiter::new("create_sphere", session.0.user_id, conf);
+    let mut rate_limiter =
+        RateLimiter::new("create_sphere", session.0.rate_limit_identifier(), conf);
     rate_limiter.process_rate_limit(&mut cache).await?;
     let sphere = Sphere::create(
         sphere.into_inner(),
@@ -3,7 +3,7 @@ use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
 use todel::{
     http::{Cache, TokenAuth, DB},
     ids::IdGenerator,
-    models::{Category, CategoryCreate, ErrorResponse, ServerPayload, Sphere},
+    models::{Category, CategoryCreate, ErrorResponse, ServerPayload, Sphere, SpherePermission},
     Conf,
 };
 use tokio::sync::Mutex;
@@ -34,7 +34,8 @@ pub async fn create_category(
     id_generator: &State<Mutex<IdGenerator>>,
     session: TokenAuth,
 ) -> RateLimitedRouteResponse<Json<Category>> {
-    let mut rate_limiter = RateLimiter::new("create_category", session.0.user_id, conf);
+    let mut rate_limiter =
+        RateLimiter::new("create_category", session.0.rate_limit_identifier(), conf);
     rate_limiter.process_rate_limit(&mut cache).await?;
 
     let sphere = Sphere::get_unpopulated(sphere_id, &mut db)
@@ -47,9 +48,10 @@ pub async fn create_category(
             })
         })?;
 
-    if sphere.owner_id != session.0.user_id {
-        return Err(rate_limiter.add_headers(error!(FORBIDDEN)));
-    }
+    sphere
+        .require_permission(session.0.user_id, SpherePermission::ManageChannels, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
 
     let category = Category::create(
         category.into_inner(),
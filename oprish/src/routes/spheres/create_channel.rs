@@ -3,7 +3,9 @@ use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
 use todel::{
     http::{Cache, TokenAuth, DB},
     ids::IdGenerator,
-    models::{ErrorResponse, ServerPayload, Sphere, SphereChannel, SphereChannelCreate},
+    models::{
+        ErrorResponse, ServerPayload, Sphere, SphereChannel, SphereChannelCreate, SpherePermission,
+    },
     Conf,
 };
 use tokio::sync::Mutex;
@@ -41,7 +43,8 @@ pub async fn create_channel(
     id_generator: &State<Mutex<IdGenerator>>,
     session: TokenAuth,
 ) -> RateLimitedRouteResponse<Json<SphereChannel>> {
-    let mut rate_limiter = RateLimiter::new("create_channel", session.0.user_id, conf);
+    let mut rate_limiter =
+        RateLimiter::new("create_channel", session.0.rate_limit_identifier(), conf);
     rate_limiter.process_rate_limit(&mut cache).await?;
 
     let sphere = Sphere::get_unpopulated(sphere_id, &mut db)
@@ -54,9 +57,10 @@ pub async fn create_channel(
             })
         })?;
 
-    if sphere.owner_id != session.0.user_id {
-        return Err(rate_limiter.add_headers(error!(FORBIDDEN)));
-    }
+    sphere
+        .require_permission(session.0.user_id, SpherePermission::ManageChannels, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
 
     let channel = SphereChannel::create(
         channel.into_inner(),
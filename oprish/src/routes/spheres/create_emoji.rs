@@ -3,7 +3,7 @@ use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
 use todel::{
     http::{Cache, SphereIdentifier, TokenAuth, DB},
     ids::IdGenerator,
-    models::{Emoji, EmojiCreate, ServerPayload, Sphere},
+    models::{Emoji, EmojiCreate, ServerPayload, Sphere, SpherePermission},
     Conf,
 };
 use tokio::sync::Mutex;
@@ -21,7 +21,8 @@ pub async fn create_emoji(
     id_generator: &State<Mutex<IdGenerator>>,
     session: TokenAuth,
 ) -> RateLimitedRouteResponse<Json<Emoji>> {
-    let mut rate_limiter = RateLimiter::new("create_emoji", session.0.user_id, conf);
+    let mut rate_limiter =
+        RateLimiter::new("create_emoji", session.0.rate_limit_identifier(), conf);
     rate_limiter.process_rate_limit(&mut cache).await?;
     let sphere = match identifier {
         SphereIdentifier::ID(id) => Sphere::get_unpopulated(id, &mut db).await,
@@ -31,6 +32,11 @@ pub async fn create_emoji(
     }
     .map_err(|err| rate_limiter.add_headers(err))?;
 
+    sphere
+        .require_permission(session.0.user_id, SpherePermission::ManageEmojis, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+
     let emoji = sphere
         .add_emoji(
             emoji.into_inner(),
@@ -0,0 +1,80 @@
+use rand::rngs::StdRng;
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::Connection;
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{ErrorResponse, Invite, InviteCreate, Sphere, SpherePermission},
+    Conf,
+};
+use tokio::sync::Mutex;
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Create an invite to a sphere.
+///
+/// Invites let users join the sphere even if it's invite-only or not discoverable.
+///
+/// Requires the `CREATE_INVITES` permission.
+///
+/// -- STATUS: 200
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -H "Authorization: <token>" \
+///   --json '{"max_uses":10,"duration":86400}' \
+///   https://api.eludris.gay/spheres/1234/invites
+///
+/// {
+///   "code": "hK3vQz8p",
+///   "sphere_id": 1234,
+///   "creator_id": 4321,
+///   "uses": 0,
+///   "max_uses": 10,
+///   "expires_at": 1750000000
+/// }
+/// ```
+#[autodoc("/spheres", category = "Invites")]
+#[post("/<sphere_id>/invites", data = "<invite>")]
+pub async fn create_invite(
+    invite: Json<InviteCreate>,
+    sphere_id: u64,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    rng: &State<Mutex<StdRng>>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Json<Invite>> {
+    let mut rate_limiter =
+        RateLimiter::new("create_invite", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    let sphere = Sphere::get_unpopulated(sphere_id, &mut db)
+        .await
+        .map_err(|err| {
+            rate_limiter.add_headers(if let ErrorResponse::NotFound { .. } = err {
+                error!(VALIDATION, "sphere", "Sphere doesn't exist")
+            } else {
+                err
+            })
+        })?;
+
+    sphere
+        .require_permission(session.0.user_id, SpherePermission::CreateInvites, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+
+    rate_limiter.wrap_response(Json(
+        Invite::create(
+            invite.into_inner(),
+            sphere_id,
+            session.0.user_id,
+            &mut *rng.lock().await,
+            &mut db,
+        )
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?,
+    ))
+}
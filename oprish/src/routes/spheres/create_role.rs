@@ -0,0 +1,93 @@
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    ids::IdGenerator,
+    models::{ErrorResponse, Role, RoleCreate, ServerPayload, Sphere, SpherePermission},
+    Conf,
+};
+use tokio::sync::Mutex;
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Create a role inside a sphere.
+///
+/// New roles are placed at the bottom of the sphere's role hierarchy. Requires the
+/// `MANAGE_ROLES` permission and members can't create roles with permissions they don't have.
+///
+/// -- STATUS: 200
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -H "Authorization: <token>" \
+///   --json '{"name":"moderators","colour":16750848,"permissions":228}' \
+///   https://api.eludris.gay/spheres/1234/roles
+///
+/// {
+///   "id": 5490083823625,
+///   "sphere_id": 1234,
+///   "name": "moderators",
+///   "colour": 16750848,
+///   "position": 0,
+///   "permissions": 228
+/// }
+/// ```
+#[autodoc("/spheres", category = "Roles")]
+#[post("/<sphere_id>/roles", data = "<role>")]
+pub async fn create_role(
+    role: Json<RoleCreate>,
+    sphere_id: u64,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    id_generator: &State<Mutex<IdGenerator>>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Json<Role>> {
+    let mut rate_limiter = RateLimiter::new("create_role", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    let sphere = Sphere::get_unpopulated(sphere_id, &mut db)
+        .await
+        .map_err(|err| {
+            rate_limiter.add_headers(if let ErrorResponse::NotFound { .. } = err {
+                error!(VALIDATION, "sphere", "Sphere doesn't exist")
+            } else {
+                err
+            })
+        })?;
+
+    sphere
+        .require_permission(session.0.user_id, SpherePermission::ManageRoles, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+    sphere
+        .require_permissions(session.0.user_id, role.permissions, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+
+    let role = Role::create(
+        role.into_inner(),
+        sphere_id,
+        &mut *id_generator.lock().await,
+        &mut db,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
+
+    cache
+        .publish::<&str, String, ()>(
+            "eludris-events",
+            serde_json::to_string(&ServerPayload::RoleCreate {
+                role: role.clone(),
+                sphere_id,
+            })
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    rate_limiter.wrap_response(Json(role))
+}
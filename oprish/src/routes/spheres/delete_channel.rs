@@ -1,10 +1,14 @@
 use rocket::State;
 use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
 use todel::{
-    http::{Cache, TokenAuth, DB},
-    models::{ErrorResponse, ServerPayload, Sphere, SphereChannel},
+    http::{AuditLogReason, Cache, TokenAuth, DB},
+    ids::IdGenerator,
+    models::{
+        AuditLogActor, ErrorResponse, ServerPayload, Sphere, SphereChannel, SpherePermission,
+    },
     Conf,
 };
+use tokio::sync::Mutex;
 
 use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
 
@@ -28,9 +32,12 @@ pub async fn delete_channel(
     conf: &State<Conf>,
     mut cache: Connection<Cache>,
     mut db: Connection<DB>,
+    reason: AuditLogReason,
+    id_generator: &State<Mutex<IdGenerator>>,
     session: TokenAuth,
 ) -> RateLimitedRouteResponse<()> {
-    let mut rate_limiter = RateLimiter::new("delete_category", session.0.user_id, conf);
+    let mut rate_limiter =
+        RateLimiter::new("delete_category", session.0.rate_limit_identifier(), conf);
     rate_limiter.process_rate_limit(&mut cache).await?;
 
     let sphere = Sphere::get_unpopulated(sphere_id, &mut db)
@@ -43,14 +50,24 @@ pub async fn delete_channel(
             })
         })?;
 
-    if sphere.owner_id != session.0.user_id {
-        return Err(rate_limiter.add_headers(error!(FORBIDDEN)));
-    }
+    sphere
+        .require_permission(session.0.user_id, SpherePermission::ManageChannels, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
 
     let response = rate_limiter.wrap_response(
-        SphereChannel::delete(sphere_id, channel_id, &mut db)
-            .await
-            .map_err(|err| rate_limiter.add_headers(err))?,
+        SphereChannel::delete(
+            sphere_id,
+            channel_id,
+            AuditLogActor {
+                user_id: session.0.user_id,
+                reason: reason.0,
+                id_generator: &mut *id_generator.lock().await,
+            },
+            &mut db,
+        )
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?,
     );
 
     cache
@@ -0,0 +1,69 @@
+use rocket::State;
+use rocket_db_pools::Connection;
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{ErrorResponse, Invite, Sphere, SpherePermission},
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Revoke one of a sphere's invites.
+///
+/// Members with the `CREATE_INVITES` permission can revoke their own invites, revoking other
+/// members' invites requires the `MANAGE_SPHERE` permission.
+///
+/// -- STATUS: 204
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl --request DELETE \
+///   -H "Authorization: <token>" \
+///   https://api.eludris.gay/spheres/1234/invites/hK3vQz8p
+/// ```
+#[autodoc("/spheres", category = "Invites")]
+#[delete("/<sphere_id>/invites/<code>")]
+pub async fn delete_invite(
+    sphere_id: u64,
+    code: &str,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<()> {
+    let mut rate_limiter =
+        RateLimiter::new("delete_invite", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    let sphere = Sphere::get_unpopulated(sphere_id, &mut db)
+        .await
+        .map_err(|err| {
+            rate_limiter.add_headers(if let ErrorResponse::NotFound { .. } = err {
+                error!(VALIDATION, "sphere", "Sphere doesn't exist")
+            } else {
+                err
+            })
+        })?;
+    let invite = Invite::get_unchecked(code, sphere_id, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+
+    let permission = if invite.creator_id == Some(session.0.user_id) {
+        SpherePermission::CreateInvites
+    } else {
+        SpherePermission::ManageSphere
+    };
+    sphere
+        .require_permission(session.0.user_id, permission, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+
+    rate_limiter.wrap_response(
+        invite
+            .delete(&mut db)
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?,
+    )
+}
@@ -0,0 +1,84 @@
+use rocket::State;
+use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{
+        ErrorResponse, PermissionOverwrite, ServerPayload, Sphere, SphereChannel, SpherePermission,
+    },
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Delete a channel's permission overwrite for a role or member.
+///
+/// Requires the `MANAGE_CHANNELS` permission.
+///
+/// -- STATUS: 201
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl --request DELETE \
+///   -H "Authorization: <token>" \
+///   https://api.eludris.gay/spheres/1234/channels/5678/overwrites/1234
+/// ```
+#[autodoc("/spheres", category = "Channels")]
+#[delete("/<sphere_id>/channels/<channel_id>/overwrites/<target_id>")]
+pub async fn delete_overwrite(
+    sphere_id: u64,
+    channel_id: u64,
+    target_id: u64,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<()> {
+    let mut rate_limiter =
+        RateLimiter::new("delete_overwrite", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    let sphere = Sphere::get_unpopulated(sphere_id, &mut db)
+        .await
+        .map_err(|err| {
+            rate_limiter.add_headers(if let ErrorResponse::NotFound { .. } = err {
+                error!(VALIDATION, "sphere", "Sphere doesn't exist")
+            } else {
+                err
+            })
+        })?;
+
+    sphere
+        .require_permission(session.0.user_id, SpherePermission::ManageChannels, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+
+    let channel = SphereChannel::get(channel_id, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+    if channel.get_sphere_id() != sphere_id {
+        return Err(rate_limiter.add_headers(error!(NOT_FOUND)));
+    }
+
+    let response = rate_limiter.wrap_response(
+        PermissionOverwrite::delete(channel_id, target_id, &mut db)
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?,
+    );
+
+    cache
+        .publish::<&str, String, ()>(
+            "eludris-events",
+            serde_json::to_string(&ServerPayload::ChannelOverwriteDelete {
+                target_id,
+                channel_id,
+                sphere_id,
+            })
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    response
+}
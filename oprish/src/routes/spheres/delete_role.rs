@@ -0,0 +1,77 @@
+use rocket::State;
+use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{ErrorResponse, Role, ServerPayload, Sphere, SpherePermission},
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Delete a role.
+///
+/// Requires the `MANAGE_ROLES` permission and members can only delete roles below their highest
+/// role.
+///
+/// -- STATUS: 201
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl --request DELETE \
+///   -H "Authorization: <token>" \
+///   https://api.eludris.gay/spheres/1234/roles/5678
+/// ```
+#[autodoc("/spheres", category = "Roles")]
+#[delete("/<sphere_id>/roles/<role_id>")]
+pub async fn delete_role(
+    sphere_id: u64,
+    role_id: u64,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<()> {
+    let mut rate_limiter = RateLimiter::new("delete_role", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    let sphere = Sphere::get_unpopulated(sphere_id, &mut db)
+        .await
+        .map_err(|err| {
+            rate_limiter.add_headers(if let ErrorResponse::NotFound { .. } = err {
+                error!(VALIDATION, "sphere", "Sphere doesn't exist")
+            } else {
+                err
+            })
+        })?;
+
+    sphere
+        .require_permission(session.0.user_id, SpherePermission::ManageRoles, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+
+    let role = Role::get(role_id, sphere_id, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+    sphere
+        .require_above_role(session.0.user_id, role.position, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+
+    let response = rate_limiter.wrap_response(
+        Role::delete(sphere_id, role_id, &mut db)
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?,
+    );
+
+    cache
+        .publish::<&str, String, ()>(
+            "eludris-events",
+            serde_json::to_string(&ServerPayload::RoleDelete { role_id, sphere_id }).unwrap(),
+        )
+        .await
+        .unwrap();
+
+    response
+}
@@ -1,10 +1,12 @@
 use rocket::{serde::json::Json, State};
 use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
 use todel::{
-    http::{Cache, SphereIdentifier, TokenAuth, DB},
-    models::{ErrorResponse, ServerPayload, Sphere, SphereEdit},
+    http::{AuditLogReason, Cache, SphereIdentifier, TokenAuth, DB},
+    ids::IdGenerator,
+    models::{AuditLogActor, ErrorResponse, ServerPayload, Sphere, SphereEdit, SpherePermission},
     Conf,
 };
+use tokio::sync::Mutex;
 
 use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
 
@@ -16,9 +18,12 @@ pub async fn edit(
     conf: &State<Conf>,
     mut cache: Connection<Cache>,
     mut db: Connection<DB>,
+    reason: AuditLogReason,
+    id_generator: &State<Mutex<IdGenerator>>,
     session: TokenAuth,
 ) -> RateLimitedRouteResponse<Json<Sphere>> {
-    let mut rate_limiter = RateLimiter::new("edit_channel", session.0.user_id, conf);
+    let mut rate_limiter =
+        RateLimiter::new("edit_channel", session.0.rate_limit_identifier(), conf);
     rate_limiter.process_rate_limit(&mut cache).await?;
 
     let sphere_id = match identifier {
@@ -41,14 +46,24 @@ pub async fn edit(
             })
         })?;
 
-    if sphere.owner_id != session.0.user_id {
-        return Err(rate_limiter.add_headers(error!(FORBIDDEN)));
-    }
-
-    let sphere = Sphere::edit(edit.clone().into_inner(), sphere_id, &mut db)
+    sphere
+        .require_permission(session.0.user_id, SpherePermission::ManageSphere, &mut db)
         .await
         .map_err(|err| rate_limiter.add_headers(err))?;
 
+    let sphere = Sphere::edit(
+        edit.clone().into_inner(),
+        sphere_id,
+        AuditLogActor {
+            user_id: session.0.user_id,
+            reason: reason.0,
+            id_generator: &mut *id_generator.lock().await,
+        },
+        &mut db,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
+
     cache
         .publish::<&str, String, ()>(
             "eludris-events",
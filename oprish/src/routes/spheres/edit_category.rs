@@ -1,10 +1,15 @@
 use rocket::{serde::json::Json, State};
 use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
 use todel::{
-    http::{Cache, TokenAuth, DB},
-    models::{Category, CategoryEdit, ErrorResponse, ServerPayload, Sphere},
+    http::{AuditLogReason, Cache, TokenAuth, DB},
+    ids::IdGenerator,
+    models::{
+        AuditLogActor, Category, CategoryEdit, ErrorResponse, ServerPayload, Sphere,
+        SpherePermission,
+    },
     Conf,
 };
+use tokio::sync::Mutex;
 
 use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
 
@@ -37,9 +42,12 @@ pub async fn edit_category(
     conf: &State<Conf>,
     mut cache: Connection<Cache>,
     mut db: Connection<DB>,
+    reason: AuditLogReason,
+    id_generator: &State<Mutex<IdGenerator>>,
     session: TokenAuth,
 ) -> RateLimitedRouteResponse<Json<Category>> {
-    let mut rate_limiter = RateLimiter::new("edit_category", session.0.user_id, conf);
+    let mut rate_limiter =
+        RateLimiter::new("edit_category", session.0.rate_limit_identifier(), conf);
     rate_limiter.process_rate_limit(&mut cache).await?;
 
     let sphere = Sphere::get_unpopulated(sphere_id, &mut db)
@@ -52,14 +60,24 @@ pub async fn edit_category(
             })
         })?;
 
-    if sphere.owner_id != session.0.user_id {
-        return Err(rate_limiter.add_headers(error!(FORBIDDEN)));
-    }
+    sphere
+        .require_permission(session.0.user_id, SpherePermission::ManageChannels, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
 
-    let (category, category_edit) =
-        Category::edit(category.into_inner(), sphere_id, category_id, &mut db)
-            .await
-            .map_err(|err| rate_limiter.add_headers(err))?;
+    let (category, category_edit) = Category::edit(
+        category.into_inner(),
+        sphere_id,
+        category_id,
+        AuditLogActor {
+            user_id: session.0.user_id,
+            reason: reason.0,
+            id_generator: &mut *id_generator.lock().await,
+        },
+        &mut db,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
 
     cache
         .publish::<&str, String, ()>(
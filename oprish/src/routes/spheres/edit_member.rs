@@ -1,20 +1,30 @@
 use rocket::{serde::json::Json, State};
-use rocket_db_pools::Connection;
+use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
 use todel::{
-    http::{Cache, SphereIdentifier, TokenAuth, UserIdentifier, DB},
-    models::{ErrorResponse, Member, MemberEdit, Sphere},
+    http::{AuditLogReason, Cache, SphereIdentifier, TokenAuth, UserIdentifier, DB},
+    ids::IdGenerator,
+    models::{
+        AuditLogActor, ErrorResponse, Member, MemberEdit, Role, ServerPayload, Sphere,
+        SpherePermission,
+    },
     Conf,
 };
+use tokio::sync::Mutex;
 
 use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
 
 /// Edit a member's data using a [`SphereIdentifier`] and [`UserIdentifier`].
 ///
-/// This route requires permissions to edit other users.
+/// Editing other members requires the `MANAGE_NICKNAMES` permission and being above them in the
+/// sphere's role hierarchy.
 ///
 /// Only a user can change their own avatar, banner, bio and status, moderators can reset them
 /// however.
 ///
+/// Changing a member's roles requires the `MANAGE_ROLES` permission and being above them in the
+/// sphere's role hierarchy, unless they're the requester, and every role that is given or taken
+/// away must be below the requester's highest role.
+///
 /// -----
 ///
 /// ### Example
@@ -31,10 +41,12 @@ pub async fn edit_member(
     conf: &State<Conf>,
     mut cache: Connection<Cache>,
     mut db: Connection<DB>,
+    reason: AuditLogReason,
+    id_generator: &State<Mutex<IdGenerator>>,
     session: TokenAuth,
 ) -> RateLimitedRouteResponse<Result<Json<Member>, ErrorResponse>> {
     let mut rate_limiter;
-    rate_limiter = RateLimiter::new("edit_member", session.0.user_id, conf);
+    rate_limiter = RateLimiter::new("edit_member", session.0.rate_limit_identifier(), conf);
     rate_limiter.process_rate_limit(&mut cache).await?;
     let sphere = match sphere_identifier {
         SphereIdentifier::ID(id) => Sphere::get_unpopulated(id, &mut db).await,
@@ -68,19 +80,87 @@ pub async fn edit_member(
         }
     }
     .map_err(|err| rate_limiter.add_headers(err))?;
-    if session.0.user_id != member.user.id && session.0.user_id != sphere.owner_id {
-        error!(rate_limiter, FORBIDDEN);
+    let edit = edit.into_inner();
+    if session.0.user_id != member.user.id
+        && (edit.nickname.is_some()
+            || edit.sphere_avatar.is_some()
+            || edit.sphere_banner.is_some()
+            || edit.sphere_bio.is_some()
+            || edit.sphere_status.is_some())
+    {
+        sphere
+            .require_permission(
+                session.0.user_id,
+                SpherePermission::ManageNicknames,
+                &mut db,
+            )
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?;
+        sphere
+            .require_above_member(session.0.user_id, member.user.id, &mut db)
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?;
     }
-    rate_limiter.wrap_response(Ok(Json(
-        Member::edit(
-            member.user.id,
-            sphere.id,
-            edit.into_inner(),
-            &mut db,
-            Some(session.0.user_id),
-            &mut cache,
+    if let Some(roles) = &edit.roles {
+        sphere
+            .require_permission(session.0.user_id, SpherePermission::ManageRoles, &mut db)
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?;
+        if session.0.user_id != member.user.id {
+            sphere
+                .require_above_member(session.0.user_id, member.user.id, &mut db)
+                .await
+                .map_err(|err| rate_limiter.add_headers(err))?;
+        }
+        for role_id in roles
+            .iter()
+            .filter(|r| !member.roles.contains(r))
+            .chain(member.roles.iter().filter(|r| !roles.contains(r)))
+        {
+            let role = Role::get(*role_id, sphere.id, &mut db)
+                .await
+                .map_err(|err| {
+                    rate_limiter.add_headers(if let ErrorResponse::NotFound { .. } = err {
+                        error!(
+                            VALIDATION,
+                            "roles",
+                            format!("Role {} doesn't exist in this sphere", role_id)
+                        )
+                    } else {
+                        err
+                    })
+                })?;
+            sphere
+                .require_above_role(session.0.user_id, role.position, &mut db)
+                .await
+                .map_err(|err| rate_limiter.add_headers(err))?;
+        }
+    }
+    let member = Member::edit(
+        member.user.id,
+        sphere.id,
+        edit.clone(),
+        AuditLogActor {
+            user_id: session.0.user_id,
+            reason: reason.0,
+            id_generator: &mut *id_generator.lock().await,
+        },
+        &mut db,
+        &mut cache,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
+    cache
+        .publish::<&str, String, ()>(
+            "eludris-events",
+            serde_json::to_string(&ServerPayload::SphereMemberUpdate {
+                data: edit,
+                user_id: member.user.id,
+                sphere_id: sphere.id,
+            })
+            .unwrap(),
         )
         .await
-        .map_err(|err| rate_limiter.add_headers(err))?,
-    )))
+        .unwrap();
+    rate_limiter.wrap_response(Ok(Json(member)))
 }
@@ -0,0 +1,96 @@
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{ErrorResponse, Role, RoleEdit, ServerPayload, Sphere, SpherePermission},
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Edit a role.
+///
+/// Requires the `MANAGE_ROLES` permission. Members can only edit roles below their highest role,
+/// can't move roles above it and can't grant permissions they don't have.
+///
+/// Position is automatically upper-bounded to the number of roles in the sphere.
+///
+/// -- STATUS: 200
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl --request PATCH \
+///   -H "Authorization: <token>" \
+///   --json '{"name":"janitors","position":2}' \
+///   https://api.eludris.gay/spheres/1234/roles/5678
+/// ```
+#[autodoc("/spheres", category = "Roles")]
+#[patch("/<sphere_id>/roles/<role_id>", data = "<role>")]
+pub async fn edit_role(
+    role: Json<RoleEdit>,
+    sphere_id: u64,
+    role_id: u64,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Json<Role>> {
+    let mut rate_limiter = RateLimiter::new("edit_role", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    let sphere = Sphere::get_unpopulated(sphere_id, &mut db)
+        .await
+        .map_err(|err| {
+            rate_limiter.add_headers(if let ErrorResponse::NotFound { .. } = err {
+                error!(VALIDATION, "sphere", "Sphere doesn't exist")
+            } else {
+                err
+            })
+        })?;
+
+    sphere
+        .require_permission(session.0.user_id, SpherePermission::ManageRoles, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+
+    let current_role = Role::get(role_id, sphere_id, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+    sphere
+        .require_above_role(session.0.user_id, current_role.position, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+    if let Some(position) = role.position {
+        sphere
+            .require_above_role(session.0.user_id, position, &mut db)
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?;
+    }
+    if let Some(permissions) = role.permissions {
+        sphere
+            .require_permissions(session.0.user_id, permissions, &mut db)
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?;
+    }
+
+    let (role, role_edit) = Role::edit(role.into_inner(), sphere_id, role_id, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+
+    cache
+        .publish::<&str, String, ()>(
+            "eludris-events",
+            serde_json::to_string(&ServerPayload::RoleUpdate {
+                data: role_edit,
+                role_id,
+                sphere_id,
+            })
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    rate_limiter.wrap_response(Json(role))
+}
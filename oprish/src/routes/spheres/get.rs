@@ -2,7 +2,7 @@ use rocket::{serde::json::Json, State};
 use rocket_db_pools::Connection;
 use todel::{
     http::{Cache, ClientIP, SphereIdentifier, TokenAuth, DB},
-    models::Sphere,
+    models::{ErrorResponse, Sphere},
     Conf,
 };
 
@@ -10,6 +10,8 @@ use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
 
 /// Get a sphere's data using a [`SphereIdentifier`].
 ///
+/// Spheres which aren't discoverable can only be fetched by their members.
+///
 /// -----
 ///
 /// ### Example
@@ -46,7 +48,7 @@ pub async fn get_sphere(
 ) -> RateLimitedRouteResponse<Json<Sphere>> {
     let mut rate_limiter;
     if let Some(session) = &session {
-        rate_limiter = RateLimiter::new("get_sphere", session.0.user_id, conf);
+        rate_limiter = RateLimiter::new("get_sphere", session.0.rate_limit_identifier(), conf);
     } else {
         rate_limiter = RateLimiter::new("guest_get_sphere", ip, conf);
     }
@@ -58,5 +60,10 @@ pub async fn get_sphere(
         }
     }
     .map_err(|err| rate_limiter.add_headers(err))?;
+    if !sphere.discoverable
+        && !session.is_some_and(|s| sphere.members.iter().any(|m| m.user.id == s.0.user_id))
+    {
+        return Err(rate_limiter.add_headers(error!(NOT_FOUND)));
+    }
     rate_limiter.wrap_response(Json(sphere))
 }
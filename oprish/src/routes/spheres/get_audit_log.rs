@@ -0,0 +1,93 @@
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::Connection;
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{AuditLogAction, AuditLogEntry, ErrorResponse, Sphere, SpherePermission},
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Get a sphere's audit log, newest entries first.
+///
+/// Edits and deletions of the sphere, its categories, channels and emojis are recorded along
+/// with kicks, bans, unbans, timeouts and changes to other members' nicknames and roles. The
+/// optional `X-Audit-Log-Reason` header of the request making a change is stored as the entry's
+/// reason.
+///
+/// This endpoint supports pagination via the `before`/`limit` query parameters and can be
+/// filtered by `action` type and `actor_id`.
+///
+/// Requires the `VIEW_AUDIT_LOG` permission.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -H "Authorization: <token>" \
+///   "https://api.eludris.gay/spheres/1234/audit-log?action=MEMBER_BAN&limit=1"
+///
+/// [
+///   {
+///     "id": 5490083823641,
+///     "sphere_id": 1234,
+///     "actor_id": 4321,
+///     "target_id": 5678,
+///     "reason": "Posting gacha pulls in #general",
+///     "action": "MEMBER_BAN",
+///     "after": {
+///       "user_id": 5678,
+///       "sphere_id": 1234,
+///       "moderator_id": 4321,
+///       "reason": "Posting gacha pulls in #general"
+///     }
+///   }
+/// ]
+/// ```
+#[autodoc("/spheres", category = "Spheres")]
+#[get("/<sphere_id>/audit-log?<before>&<limit>&<action>&<actor_id>")]
+pub async fn get_audit_log(
+    sphere_id: u64,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    session: TokenAuth,
+    before: Option<u64>,
+    limit: Option<u32>,
+    action: Option<AuditLogAction>,
+    actor_id: Option<u64>,
+) -> RateLimitedRouteResponse<Result<Json<Vec<AuditLogEntry>>, ErrorResponse>> {
+    let mut rate_limiter =
+        RateLimiter::new("get_audit_log", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    let sphere = Sphere::get_unpopulated(sphere_id, &mut db)
+        .await
+        .map_err(|err| {
+            rate_limiter.add_headers(if let ErrorResponse::NotFound { .. } = err {
+                error!(VALIDATION, "sphere", "Sphere doesn't exist")
+            } else {
+                err
+            })
+        })?;
+
+    sphere
+        .require_permission(session.0.user_id, SpherePermission::ViewAuditLog, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+
+    rate_limiter.wrap_response(
+        AuditLogEntry::get_all(
+            sphere_id,
+            limit.unwrap_or(50),
+            before,
+            action,
+            actor_id,
+            &mut db,
+        )
+        .await
+        .map(Json),
+    )
+}
@@ -0,0 +1,66 @@
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::Connection;
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{ErrorResponse, Sphere, SphereBan, SpherePermission},
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Get all of a sphere's active bans.
+///
+/// Requires the `BAN_MEMBERS` permission.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -H "Authorization: <token>" \
+///   https://api.eludris.gay/spheres/1234/bans
+///
+/// [
+///   {
+///     "user_id": 5678,
+///     "sphere_id": 1234,
+///     "moderator_id": 4321,
+///     "reason": "Posting gacha pulls in #general"
+///   }
+/// ]
+/// ```
+#[autodoc("/spheres", category = "Members")]
+#[get("/<sphere_id>/bans")]
+pub async fn get_bans(
+    sphere_id: u64,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Json<Vec<SphereBan>>> {
+    let mut rate_limiter = RateLimiter::new("get_bans", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    let sphere = Sphere::get_unpopulated(sphere_id, &mut db)
+        .await
+        .map_err(|err| {
+            rate_limiter.add_headers(if let ErrorResponse::NotFound { .. } = err {
+                error!(VALIDATION, "sphere", "Sphere doesn't exist")
+            } else {
+                err
+            })
+        })?;
+
+    sphere
+        .require_permission(session.0.user_id, SpherePermission::BanMembers, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+
+    rate_limiter.wrap_response(Json(
+        sphere
+            .get_bans(&mut db)
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?,
+    ))
+}
@@ -0,0 +1,61 @@
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::Connection;
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{ErrorResponse, Invite, Sphere, SpherePermission},
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Get the IDs of a sphere's members who joined through one of its invites.
+///
+/// This also works for invites which were revoked or have expired.
+///
+/// Requires the `MANAGE_SPHERE` permission.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -H "Authorization: <token>" \
+///   https://api.eludris.gay/spheres/1234/invites/hK3vQz8p/members
+///
+/// [5678, 8765]
+/// ```
+#[autodoc("/spheres", category = "Invites")]
+#[get("/<sphere_id>/invites/<code>/members")]
+pub async fn get_invite_members(
+    sphere_id: u64,
+    code: &str,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Json<Vec<u64>>> {
+    let mut rate_limiter = RateLimiter::new("get_invites", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    let sphere = Sphere::get_unpopulated(sphere_id, &mut db)
+        .await
+        .map_err(|err| {
+            rate_limiter.add_headers(if let ErrorResponse::NotFound { .. } = err {
+                error!(VALIDATION, "sphere", "Sphere doesn't exist")
+            } else {
+                err
+            })
+        })?;
+
+    sphere
+        .require_permission(session.0.user_id, SpherePermission::ManageSphere, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+
+    rate_limiter.wrap_response(Json(
+        Invite::get_members(code, sphere_id, &mut db)
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?,
+    ))
+}
@@ -0,0 +1,67 @@
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::Connection;
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{ErrorResponse, Invite, Sphere, SpherePermission},
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Get all of a sphere's invites that haven't expired yet.
+///
+/// Requires the `MANAGE_SPHERE` permission.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -H "Authorization: <token>" \
+///   https://api.eludris.gay/spheres/1234/invites
+///
+/// [
+///   {
+///     "code": "hK3vQz8p",
+///     "sphere_id": 1234,
+///     "creator_id": 4321,
+///     "uses": 3,
+///     "max_uses": 10,
+///     "expires_at": 1750000000
+///   }
+/// ]
+/// ```
+#[autodoc("/spheres", category = "Invites")]
+#[get("/<sphere_id>/invites")]
+pub async fn get_invites(
+    sphere_id: u64,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Json<Vec<Invite>>> {
+    let mut rate_limiter = RateLimiter::new("get_invites", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    let sphere = Sphere::get_unpopulated(sphere_id, &mut db)
+        .await
+        .map_err(|err| {
+            rate_limiter.add_headers(if let ErrorResponse::NotFound { .. } = err {
+                error!(VALIDATION, "sphere", "Sphere doesn't exist")
+            } else {
+                err
+            })
+        })?;
+
+    sphere
+        .require_permission(session.0.user_id, SpherePermission::ManageSphere, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+
+    rate_limiter.wrap_response(Json(
+        Invite::get_all(sphere_id, &mut db)
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?,
+    ))
+}
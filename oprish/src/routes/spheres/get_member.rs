@@ -30,7 +30,7 @@ pub async fn get_member(
 ) -> RateLimitedRouteResponse<Json<Member>> {
     let mut rate_limiter;
     if let Some(session) = &session {
-        rate_limiter = RateLimiter::new("get_member", session.0.user_id, conf);
+        rate_limiter = RateLimiter::new("get_member", session.0.rate_limit_identifier(), conf);
     } else {
         rate_limiter = RateLimiter::new("guest_get_member", ip, conf);
     }
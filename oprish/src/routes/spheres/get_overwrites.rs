@@ -0,0 +1,67 @@
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::Connection;
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{ErrorResponse, PermissionOverwrite, SphereChannel, SpherePermission},
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Get all of a channel's permission overwrites.
+///
+/// Requires being able to view the channel.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -H "Authorization: <token>" \
+///   https://api.eludris.gay/spheres/1234/channels/5678/overwrites
+///
+/// [
+///   {
+///     "id": 1234,
+///     "type": "ROLE",
+///     "allow": 0,
+///     "deny": 256
+///   }
+/// ]
+/// ```
+#[autodoc("/spheres", category = "Channels")]
+#[get("/<sphere_id>/channels/<channel_id>/overwrites")]
+pub async fn get_overwrites(
+    sphere_id: u64,
+    channel_id: u64,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Json<Vec<PermissionOverwrite>>> {
+    let mut rate_limiter =
+        RateLimiter::new("get_overwrites", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    let channel = SphereChannel::get(channel_id, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+    if channel.get_sphere_id() != sphere_id {
+        return Err(rate_limiter.add_headers(error!(NOT_FOUND)));
+    }
+    SphereChannel::require_permission(
+        channel_id,
+        session.0.user_id,
+        SpherePermission::ViewChannels,
+        &mut db,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
+
+    rate_limiter.wrap_response(Json(
+        PermissionOverwrite::get_all(channel_id, &mut db)
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?,
+    ))
+}
@@ -0,0 +1,60 @@
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::Connection;
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{ErrorResponse, Role, Sphere},
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Get all of a sphere's roles, ordered by their position in the role hierarchy.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -H "Authorization: <token>" \
+///   https://api.eludris.gay/spheres/1234/roles
+///
+/// [
+///   {
+///     "id": 5490083823625,
+///     "sphere_id": 1234,
+///     "name": "moderators",
+///     "colour": 16750848,
+///     "position": 0,
+///     "permissions": 228
+///   }
+/// ]
+/// ```
+#[autodoc("/spheres", category = "Roles")]
+#[get("/<sphere_id>/roles")]
+pub async fn get_roles(
+    sphere_id: u64,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Json<Vec<Role>>> {
+    let mut rate_limiter = RateLimiter::new("get_roles", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    Sphere::get_unpopulated(sphere_id, &mut db)
+        .await
+        .map_err(|err| {
+            rate_limiter.add_headers(if let ErrorResponse::NotFound { .. } = err {
+                error!(VALIDATION, "sphere", "Sphere doesn't exist")
+            } else {
+                err
+            })
+        })?;
+
+    rate_limiter.wrap_response(Json(
+        Role::get_all(sphere_id, &mut db)
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?,
+    ))
+}
@@ -16,7 +16,7 @@ pub async fn get_spheres(
     mut db: Connection<DB>,
     session: TokenAuth,
 ) -> RateLimitedRouteResponse<Json<Vec<Sphere>>> {
-    let mut rate_limiter = RateLimiter::new("get_spheres", session.0.user_id, conf);
+    let mut rate_limiter = RateLimiter::new("get_spheres", session.0.rate_limit_identifier(), conf);
     rate_limiter.process_rate_limit(&mut cache).await?;
     let user = User::get_unfiltered(session.0.user_id, &mut db)
         .await
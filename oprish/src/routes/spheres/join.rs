@@ -2,7 +2,7 @@ use rocket::{serde::json::Json, State};
 use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
 use todel::{
     http::{Cache, SphereIdentifier, TokenAuth, DB},
-    models::{ServerPayload, Sphere},
+    models::{ErrorResponse, ServerPayload, Sphere},
     Conf,
 };
 
@@ -10,6 +10,8 @@ use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
 
 /// Join a sphere using a [`SphereIdentifier`].
 ///
+/// Spheres which aren't discoverable or are invite-only can only be joined through an invite.
+///
 /// -----
 ///
 /// ### Example
@@ -43,7 +45,7 @@ pub async fn join_sphere(
     mut db: Connection<DB>,
     session: TokenAuth,
 ) -> RateLimitedRouteResponse<Json<Sphere>> {
-    let mut rate_limiter = RateLimiter::new("join_sphere", session.0.user_id, conf);
+    let mut rate_limiter = RateLimiter::new("join_sphere", session.0.rate_limit_identifier(), conf);
     rate_limiter.process_rate_limit(&mut cache).await?;
     let mut cache = cache.into_inner();
     let sphere = match identifier {
@@ -51,8 +53,17 @@ pub async fn join_sphere(
         SphereIdentifier::Slug(slug) => Sphere::get_slug(slug, &mut db, &mut cache).await,
     }
     .map_err(|err| rate_limiter.add_headers(err))?;
+    if !sphere.discoverable {
+        return Err(rate_limiter.add_headers(error!(NOT_FOUND)));
+    }
+    if sphere.invite_only {
+        return Err(rate_limiter.add_headers(error!(
+            VALIDATION,
+            "sphere", "This sphere can only be joined through an invite"
+        )));
+    }
     let member = sphere
-        .add_member(session.0.user_id, &mut db)
+        .add_member(session.0.user_id, None, &mut db)
         .await
         .map_err(|e| rate_limiter.add_headers(e))?;
     cache
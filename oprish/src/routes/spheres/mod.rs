@@ -1,20 +1,36 @@
 use rocket::Route;
 
+mod ban_member;
 mod create;
 mod create_category;
 mod create_channel;
 mod create_emoji;
+mod create_invite;
+mod create_role;
 mod delete_category;
 mod delete_channel;
+mod delete_invite;
+mod delete_overwrite;
+mod delete_role;
 mod edit;
 mod edit_category;
 mod edit_channel;
 mod edit_member;
+mod edit_role;
 mod get;
+mod get_audit_log;
+mod get_bans;
+mod get_invite_members;
+mod get_invites;
 mod get_member;
+mod get_overwrites;
+mod get_roles;
 mod get_spheres;
 mod join;
 mod remove_member;
+mod set_overwrite;
+mod timeout_member;
+mod unban_member;
 
 pub fn get_routes() -> Vec<Route> {
     routes![
@@ -33,5 +49,21 @@ pub fn get_routes() -> Vec<Route> {
         edit_member::edit_member,
         get_spheres::get_spheres,
         create_emoji::create_emoji,
+        create_role::create_role,
+        get_roles::get_roles,
+        edit_role::edit_role,
+        delete_role::delete_role,
+        get_overwrites::get_overwrites,
+        set_overwrite::set_overwrite,
+        delete_overwrite::delete_overwrite,
+        ban_member::ban_member,
+        unban_member::unban_member,
+        get_bans::get_bans,
+        timeout_member::timeout_member,
+        get_audit_log::get_audit_log,
+        create_invite::create_invite,
+        get_invites::get_invites,
+        get_invite_members::get_invite_members,
+        delete_invite::delete_invite,
     ]
 }
@@ -1,20 +1,28 @@
 use rocket::{http::Status, response::status::Custom, State};
 use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
 use todel::{
-    http::{Cache, SphereIdentifier, TokenAuth, UserIdentifier, DB},
-    models::{ErrorResponse, ServerPayload, Sphere, User},
+    http::{AuditLogReason, Cache, SphereIdentifier, TokenAuth, UserIdentifier, DB},
+    ids::IdGenerator,
+    models::{AuditLogActor, ErrorResponse, ServerPayload, Sphere, SpherePermission, User},
     Conf,
 };
+use tokio::sync::Mutex;
 
 use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
 
-/// Leave a sphere using a [`SphereIdentifier`].
+/// Leave a sphere or kick a member from it using a [`SphereIdentifier`].
+///
+/// Kicking other members requires the `KICK_MEMBERS` permission and being above them in the
+/// sphere's role hierarchy. Unlike banned users, kicked users can join the sphere again.
 ///
 /// -----
 ///
 /// ### Example
 ///
 /// ```sh
+/// curl --request DELETE \
+///   -H "Authorization: <token>" \
+///   https://api.eludris.gay/spheres/1234/members/5678
 /// ```
 #[autodoc("/spheres", category = "Spheres")]
 #[delete("/<sphere_identifier>/members/<user_identifier>")]
@@ -24,9 +32,11 @@ pub async fn remove_member(
     conf: &State<Conf>,
     mut cache: Connection<Cache>,
     mut db: Connection<DB>,
+    reason: AuditLogReason,
+    id_generator: &State<Mutex<IdGenerator>>,
     session: TokenAuth,
 ) -> RateLimitedRouteResponse<Result<Custom<()>, ErrorResponse>> {
-    let mut rate_limiter = RateLimiter::new("join_sphere", session.0.user_id, conf);
+    let mut rate_limiter = RateLimiter::new("join_sphere", session.0.rate_limit_identifier(), conf);
     rate_limiter.process_rate_limit(&mut cache).await?;
     let mut cache = cache.into_inner();
     let sphere = match sphere_identifier {
@@ -44,13 +54,30 @@ pub async fn remove_member(
                 .id
         }
     };
-    if session.0.user_id != sphere.owner_id && user_id != session.0.user_id {
-        error!(rate_limiter, UNAUTHORIZED);
+    if user_id == session.0.user_id {
+        sphere.remove_member(user_id, &mut db).await
+    } else {
+        sphere
+            .require_permission(session.0.user_id, SpherePermission::KickMembers, &mut db)
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?;
+        sphere
+            .require_above_member(session.0.user_id, user_id, &mut db)
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?;
+        sphere
+            .kick_member(
+                user_id,
+                AuditLogActor {
+                    user_id: session.0.user_id,
+                    reason: reason.0,
+                    id_generator: &mut *id_generator.lock().await,
+                },
+                &mut db,
+            )
+            .await
     }
-    sphere
-        .remove_member(user_id, &mut db)
-        .await
-        .map_err(|e| rate_limiter.add_headers(e))?;
+    .map_err(|e| rate_limiter.add_headers(e))?;
     cache
         .publish::<&str, String, ()>(
             "eludris-events",
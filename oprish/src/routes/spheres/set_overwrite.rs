@@ -0,0 +1,101 @@
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{
+        ErrorResponse, PermissionOverwrite, PermissionOverwriteCreate, ServerPayload, Sphere,
+        SpherePermission,
+    },
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Create or replace a channel's permission overwrite for a role or member.
+///
+/// Using the sphere's ID as a role ID targets every member of the sphere.
+///
+/// Requires the `MANAGE_CHANNELS` permission and members can't allow or deny permissions they
+/// don't have themselves.
+///
+/// -- STATUS: 200
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl --request PUT \
+///   -H "Authorization: <token>" \
+///   --json '{"type":"ROLE","allow":0,"deny":256}' \
+///   https://api.eludris.gay/spheres/1234/channels/5678/overwrites/1234
+///
+/// {
+///   "id": 1234,
+///   "type": "ROLE",
+///   "allow": 0,
+///   "deny": 256
+/// }
+/// ```
+#[autodoc("/spheres", category = "Channels")]
+#[put(
+    "/<sphere_id>/channels/<channel_id>/overwrites/<target_id>",
+    data = "<overwrite>"
+)]
+pub async fn set_overwrite(
+    overwrite: Json<PermissionOverwriteCreate>,
+    sphere_id: u64,
+    channel_id: u64,
+    target_id: u64,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Json<PermissionOverwrite>> {
+    let mut rate_limiter =
+        RateLimiter::new("set_overwrite", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    let sphere = Sphere::get_unpopulated(sphere_id, &mut db)
+        .await
+        .map_err(|err| {
+            rate_limiter.add_headers(if let ErrorResponse::NotFound { .. } = err {
+                error!(VALIDATION, "sphere", "Sphere doesn't exist")
+            } else {
+                err
+            })
+        })?;
+
+    sphere
+        .require_permission(session.0.user_id, SpherePermission::ManageChannels, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+    sphere
+        .require_permissions(session.0.user_id, overwrite.allow | overwrite.deny, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+
+    let overwrite = PermissionOverwrite::set(
+        overwrite.into_inner(),
+        sphere_id,
+        channel_id,
+        target_id,
+        &mut db,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
+
+    cache
+        .publish::<&str, String, ()>(
+            "eludris-events",
+            serde_json::to_string(&ServerPayload::ChannelOverwriteUpdate {
+                overwrite: overwrite.clone(),
+                channel_id,
+                sphere_id,
+            })
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    rate_limiter.wrap_response(Json(overwrite))
+}
@@ -0,0 +1,115 @@
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
+use todel::{
+    http::{AuditLogReason, Cache, TokenAuth, DB},
+    ids::IdGenerator,
+    models::{
+        AuditLogActor, ErrorResponse, Member, MemberTimeout, ServerPayload, Sphere,
+        SpherePermission,
+    },
+    Conf,
+};
+use tokio::sync::Mutex;
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Time a sphere member out or remove their timeout.
+///
+/// Timed out members can't send messages or add reactions until their timeout expires.
+/// A duration of 0 removes the member's current timeout.
+///
+/// Requires the `TIMEOUT_MEMBERS` permission and being above the member in the sphere's role
+/// hierarchy.
+///
+/// -- STATUS: 200
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl --request PUT \
+///   -H "Authorization: <token>" \
+///   --json '{"duration":3600}' \
+///   https://api.eludris.gay/spheres/1234/members/5678/timeout
+/// ```
+#[autodoc("/spheres", category = "Members")]
+#[put("/<sphere_id>/members/<user_id>/timeout", data = "<timeout>")]
+pub async fn timeout_member(
+    timeout: Json<MemberTimeout>,
+    sphere_id: u64,
+    user_id: u64,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    reason: AuditLogReason,
+    id_generator: &State<Mutex<IdGenerator>>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Json<Member>> {
+    let mut rate_limiter =
+        RateLimiter::new("timeout_member", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    let sphere = Sphere::get_unpopulated(sphere_id, &mut db)
+        .await
+        .map_err(|err| {
+            rate_limiter.add_headers(if let ErrorResponse::NotFound { .. } = err {
+                error!(VALIDATION, "sphere", "Sphere doesn't exist")
+            } else {
+                err
+            })
+        })?;
+
+    if user_id == session.0.user_id {
+        return Err(
+            rate_limiter.add_headers(error!(VALIDATION, "user", "You can't time yourself out"))
+        );
+    }
+    sphere
+        .require_permission(session.0.user_id, SpherePermission::TimeoutMembers, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+    sphere
+        .require_above_member(session.0.user_id, user_id, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+
+    let timed_out_until = Member::timeout(
+        user_id,
+        sphere_id,
+        timeout.into_inner(),
+        AuditLogActor {
+            user_id: session.0.user_id,
+            reason: reason.0,
+            id_generator: &mut *id_generator.lock().await,
+        },
+        &mut db,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
+
+    let mut cache = cache.into_inner();
+    let member = Member::get(
+        user_id,
+        sphere_id,
+        Some(session.0.user_id),
+        &mut db,
+        &mut cache,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
+
+    cache
+        .publish::<&str, String, ()>(
+            "eludris-events",
+            serde_json::to_string(&ServerPayload::SphereMemberTimeout {
+                user_id,
+                sphere_id,
+                timed_out_until,
+            })
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    rate_limiter.wrap_response(Json(member))
+}
@@ -0,0 +1,72 @@
+use rocket::State;
+use rocket_db_pools::Connection;
+use todel::{
+    http::{AuditLogReason, Cache, TokenAuth, DB},
+    ids::IdGenerator,
+    models::{AuditLogActor, ErrorResponse, Sphere, SpherePermission},
+    Conf,
+};
+use tokio::sync::Mutex;
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Unban a user from a sphere.
+///
+/// Requires the `BAN_MEMBERS` permission.
+///
+/// -- STATUS: 201
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl --request DELETE \
+///   -H "Authorization: <token>" \
+///   https://api.eludris.gay/spheres/1234/bans/5678
+/// ```
+#[autodoc("/spheres", category = "Members")]
+#[delete("/<sphere_id>/bans/<user_id>")]
+pub async fn unban_member(
+    sphere_id: u64,
+    user_id: u64,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    reason: AuditLogReason,
+    id_generator: &State<Mutex<IdGenerator>>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<()> {
+    let mut rate_limiter =
+        RateLimiter::new("unban_member", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    let sphere = Sphere::get_unpopulated(sphere_id, &mut db)
+        .await
+        .map_err(|err| {
+            rate_limiter.add_headers(if let ErrorResponse::NotFound { .. } = err {
+                error!(VALIDATION, "sphere", "Sphere doesn't exist")
+            } else {
+                err
+            })
+        })?;
+
+    sphere
+        .require_permission(session.0.user_id, SpherePermission::BanMembers, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+
+    rate_limiter.wrap_response(
+        sphere
+            .unban_member(
+                user_id,
+                AuditLogActor {
+                    user_id: session.0.user_id,
+                    reason: reason.0,
+                    id_generator: &mut *id_generator.lock().await,
+                },
+                &mut db,
+            )
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?,
+    )
+}
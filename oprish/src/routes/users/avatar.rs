@@ -1,17 +1,24 @@
+use rocket::State;
 use rocket_db_pools::Connection;
 use todel::{
-    http::{Cache, TokenAuth, UserIdentifier, DB},
-    models::{ErrorResponse, FetchResponse, File, User},
+    http::{Cache, FetchHeaders, TokenAuth, UserIdentifier, DB},
+    models::{ErrorResponse, FetchResponse, File, FileVariant, ThumbnailFormat, User},
+    storage::Storage,
+    Conf,
 };
 
 #[autodoc("/users", category = "Users")]
-#[get("/<identifier>/avatar?<size>")]
+#[get("/<identifier>/avatar?<size>&<format>")]
 pub async fn get_avatar<'a>(
     identifier: UserIdentifier,
     size: Option<u32>,
+    format: Option<ThumbnailFormat>,
     mut db: Connection<DB>,
     cache: Connection<Cache>,
     session: Option<TokenAuth>,
+    headers: FetchHeaders,
+    storage: &State<Storage>,
+    conf: &State<Conf>,
 ) -> Result<FetchResponse<'a>, ErrorResponse> {
     let user = match identifier {
         UserIdentifier::Me => match session {
@@ -30,7 +37,18 @@ pub async fn get_avatar<'a>(
         }
     }?;
     match user.avatar {
-        Some(avatar) => File::fetch_file(avatar, "avatars", size, &mut db).await,
+        Some(avatar) => {
+            File::fetch_file(
+                avatar,
+                "avatars",
+                FileVariant { size, format },
+                &headers,
+                storage,
+                conf,
+                &mut db,
+            )
+            .await
+        }
         None => Err(error!(NOT_FOUND)),
     }
 }
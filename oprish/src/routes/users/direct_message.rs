@@ -0,0 +1,72 @@
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::Connection;
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    ids::IdGenerator,
+    models::{DirectMessageChannel, ErrorResponse},
+    Conf,
+};
+use tokio::sync::Mutex;
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Open a direct message channel with a user.
+///
+/// This returns the existing channel if the two users already have one.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -X POST \
+///   -H "Authorization: <token>" \
+///   https://api.eludris.gay/users/48615849987333/dm
+///
+/// {
+///   "id": 4080402038800,
+///   "owner": {
+///     "id": 48615849987334,
+///     "username": "barbaz",
+///     "social_credit": 3,
+///     "badges": 0,
+///     "permissions": 0
+///   },
+///   "recipient": {
+///     "id": 48615849987333,
+///     "username": "yendri",
+///     "social_credit": 0,
+///     "badges": 0,
+///     "permissions": 0
+///   }
+/// }
+/// ```
+#[autodoc("/users", category = "Users")]
+#[post("/<user_id>/dm")]
+pub async fn open_direct_message(
+    user_id: u64,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    id_generator: &State<Mutex<IdGenerator>>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Result<Json<DirectMessageChannel>, ErrorResponse>> {
+    let mut rate_limiter = RateLimiter::new(
+        "open_direct_message",
+        session.0.rate_limit_identifier(),
+        conf,
+    );
+    rate_limiter.process_rate_limit(&mut cache).await?;
+    rate_limiter.wrap_response(
+        DirectMessageChannel::get_or_create(
+            session.0.user_id,
+            user_id,
+            &mut *id_generator.lock().await,
+            &mut db,
+            &mut cache.into_inner(),
+        )
+        .await
+        .map(Json),
+    )
+}
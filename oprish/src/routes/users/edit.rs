@@ -46,7 +46,7 @@ pub async fn edit_user(
     mut cache: Connection<Cache>,
     session: TokenAuth,
 ) -> RateLimitedRouteResponse<Json<User>> {
-    let mut rate_limiter = RateLimiter::new("edit_user", session.0.user_id, conf);
+    let mut rate_limiter = RateLimiter::new("edit_user", session.0.rate_limit_identifier(), conf);
     rate_limiter.process_rate_limit(&mut cache).await?;
     let payload = ServerPayload::UserUpdate(
         User::edit(
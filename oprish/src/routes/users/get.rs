@@ -43,7 +43,7 @@ pub async fn get_user(
 ) -> RateLimitedRouteResponse<Json<User>> {
     let mut rate_limiter;
     if let Some(session) = &session {
-        rate_limiter = RateLimiter::new("get_user", session.0.user_id, conf);
+        rate_limiter = RateLimiter::new("get_user", session.0.rate_limit_identifier(), conf);
     } else {
         rate_limiter = RateLimiter::new("guest_get_user", ip, conf);
     }
@@ -3,11 +3,13 @@ use rocket::Route;
 mod avatar;
 mod create;
 mod delete;
+mod direct_message;
 mod edit;
 mod get;
 mod profile;
 mod resend_verification;
 mod reset_password;
+mod two_factor;
 mod verify;
 
 pub fn get_routes() -> Vec<Route> {
@@ -20,7 +22,11 @@ pub fn get_routes() -> Vec<Route> {
         delete::delete_user,
         reset_password::create_password_reset_code,
         reset_password::reset_password,
+        two_factor::setup_two_factor,
+        two_factor::confirm_two_factor,
+        two_factor::disable_two_factor,
         resend_verification::resend_verification,
         avatar::get_avatar,
+        direct_message::open_direct_message,
     ]
 }
@@ -40,7 +40,8 @@ pub async fn edit_profile(
     mut db: Connection<DB>,
     session: TokenAuth,
 ) -> RateLimitedRouteResponse<Json<User>> {
-    let mut rate_limiter = RateLimiter::new("edit_profile", session.0.user_id, conf);
+    let mut rate_limiter =
+        RateLimiter::new("edit_profile", session.0.rate_limit_identifier(), conf);
     rate_limiter.process_rate_limit(&mut cache).await?;
     let payload = ServerPayload::UserUpdate(
         User::edit_profile(session.0.user_id, profile.into_inner(), &mut db)
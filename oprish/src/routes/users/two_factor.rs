@@ -0,0 +1,163 @@
+use argon2::Argon2;
+use rand::rngs::StdRng;
+use rocket::{http::Status, response::status::Custom, serde::json::Json, State};
+use rocket_db_pools::Connection;
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{
+        PasswordDeleteCredentials, TwoFactorConfirm, TwoFactorRecoveryCodes, TwoFactorSetup, User,
+    },
+    Conf,
+};
+use tokio::sync::Mutex;
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Start enabling two-factor authentication for your user.
+///
+/// This returns a TOTP secret which has to be confirmed with a code from an authenticator app
+/// within 10 minutes.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -H "Authorization: <token>" \
+///   --json '{"password": "wowsuchpassword"}' \
+///   https://api.eludris.gay/users/2fa
+///
+/// {
+///   "secret": "JBSWY3DPEHPK3PXP",
+///   "uri": "otpauth://totp/eludris:yendri?secret=JBSWY3DPEHPK3PXP&issuer=eludris"
+/// }
+/// ```
+#[autodoc("/users", category = "Users")]
+#[post("/2fa", data = "<credentials>")]
+pub async fn setup_two_factor(
+    credentials: Json<PasswordDeleteCredentials>,
+    conf: &State<Conf>,
+    verifier: &State<Argon2<'static>>,
+    rng: &State<Mutex<StdRng>>,
+    mut db: Connection<DB>,
+    mut cache: Connection<Cache>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Json<TwoFactorSetup>> {
+    let mut rate_limiter =
+        RateLimiter::new("setup_two_factor", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    rate_limiter.wrap_response(Json(
+        User::setup_two_factor(
+            session.0.user_id,
+            credentials.into_inner(),
+            verifier.inner(),
+            &mut *rng.lock().await,
+            conf,
+            &mut db,
+            &mut cache.into_inner(),
+        )
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?,
+    ))
+}
+
+/// Finish enabling two-factor authentication for your user.
+///
+/// This returns your recovery codes which can each be used once in place of a code from your
+/// authenticator app. They are only shown once.
+///
+/// -- STATUS: 201
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -H "Authorization: <token>" \
+///   --json '{"code": "123456"}' \
+///   https://api.eludris.gay/users/2fa/confirm
+///
+/// {
+///   "codes": ["a8k2-x9q3", "m4p7-c2z8", ...]
+/// }
+/// ```
+#[autodoc("/users", category = "Users")]
+#[post("/2fa/confirm", data = "<confirm>")]
+pub async fn confirm_two_factor(
+    confirm: Json<TwoFactorConfirm>,
+    conf: &State<Conf>,
+    hasher: &State<Argon2<'static>>,
+    rng: &State<Mutex<StdRng>>,
+    mut db: Connection<DB>,
+    mut cache: Connection<Cache>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Custom<Json<TwoFactorRecoveryCodes>>> {
+    let mut rate_limiter = RateLimiter::new(
+        "confirm_two_factor",
+        session.0.rate_limit_identifier(),
+        conf,
+    );
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    rate_limiter.wrap_response(Custom(
+        Status::Created,
+        Json(
+            User::confirm_two_factor(
+                session.0.user_id,
+                confirm.into_inner(),
+                hasher.inner(),
+                &mut *rng.lock().await,
+                &mut db,
+                &mut cache.into_inner(),
+            )
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?,
+        ),
+    ))
+}
+
+/// Disable two-factor authentication for your user.
+///
+/// -- STATUS: 204
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -X DELETE \
+///   -H "Authorization: <token>" \
+///   --json '{"password": "wowsuchpassword", "two_factor_code": "123456"}' \
+///   https://api.eludris.gay/users/2fa
+/// ```
+#[autodoc("/users", category = "Users")]
+#[delete("/2fa", data = "<credentials>")]
+pub async fn disable_two_factor(
+    credentials: Json<PasswordDeleteCredentials>,
+    conf: &State<Conf>,
+    verifier: &State<Argon2<'static>>,
+    mut db: Connection<DB>,
+    mut cache: Connection<Cache>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Custom<()>> {
+    let mut rate_limiter = RateLimiter::new(
+        "disable_two_factor",
+        session.0.rate_limit_identifier(),
+        conf,
+    );
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    rate_limiter.wrap_response(Custom(
+        Status::NoContent,
+        User::disable_two_factor(
+            session.0.user_id,
+            credentials.into_inner(),
+            verifier.inner(),
+            &mut db,
+        )
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?,
+    ))
+}
@@ -30,7 +30,7 @@ pub async fn verify_user(
     mut cache: Connection<Cache>,
     session: TokenAuth,
 ) -> RateLimitedRouteResponse<Custom<()>> {
-    let mut rate_limiter = RateLimiter::new("verify_user", session.0.user_id, conf);
+    let mut rate_limiter = RateLimiter::new("verify_user", session.0.rate_limit_identifier(), conf);
     rate_limiter.process_rate_limit(&mut cache).await?;
 
     rate_limiter.wrap_response(
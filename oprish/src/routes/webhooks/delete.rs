@@ -0,0 +1,57 @@
+use rocket::State;
+use rocket_db_pools::Connection;
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{SphereChannel, SpherePermission, Webhook},
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Delete a webhook, its token stops working immediately.
+///
+/// Requires the `MANAGE_WEBHOOKS` permission.
+///
+/// -- STATUS: 204
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -X DELETE \
+///   -H "Authorization: <token>" \
+///   https://api.eludris.gay/webhooks/5490083823650
+/// ```
+#[autodoc("/webhooks", category = "Webhooks")]
+#[delete("/<webhook_id>")]
+pub async fn delete_webhook(
+    webhook_id: u64,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<()> {
+    let mut rate_limiter =
+        RateLimiter::new("delete_webhook", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    let webhook = Webhook::get(webhook_id, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+    SphereChannel::require_permission(
+        webhook.channel_id,
+        session.0.user_id,
+        SpherePermission::ManageWebhooks,
+        &mut db,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
+
+    rate_limiter.wrap_response(
+        webhook
+            .delete(&mut db)
+            .await
+            .map_err(|err| rate_limiter.add_headers(err))?,
+    )
+}
@@ -0,0 +1,66 @@
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::Connection;
+use todel::{
+    http::{Cache, TokenAuth, DB},
+    models::{SphereChannel, SpherePermission, Webhook, WebhookEdit},
+    Conf,
+};
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Edit a webhook.
+///
+/// Messages the webhook already sent keep the name and avatar it had at the time.
+///
+/// Requires the `MANAGE_WEBHOOKS` permission.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -X PATCH \
+///   -H "Authorization: <token>" \
+///   --json '{"name":"GitLab","avatar":null}' \
+///   https://api.eludris.gay/webhooks/5490083823650
+///
+/// {
+///   "id": 5490083823650,
+///   "channel_id": 4080402038789,
+///   "creator_id": 48615849987333,
+///   "name": "GitLab"
+/// }
+/// ```
+#[autodoc("/webhooks", category = "Webhooks")]
+#[patch("/<webhook_id>", data = "<edit>")]
+pub async fn edit_webhook(
+    edit: Json<WebhookEdit>,
+    webhook_id: u64,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    session: TokenAuth,
+) -> RateLimitedRouteResponse<Json<Webhook>> {
+    let mut rate_limiter =
+        RateLimiter::new("edit_webhook", session.0.rate_limit_identifier(), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    let mut webhook = Webhook::get(webhook_id, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+    SphereChannel::require_permission(
+        webhook.channel_id,
+        session.0.user_id,
+        SpherePermission::ManageWebhooks,
+        &mut db,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
+
+    webhook
+        .edit(edit.into_inner(), &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+    rate_limiter.wrap_response(Json(webhook))
+}
@@ -0,0 +1,101 @@
+use rocket::{serde::json::Json, State};
+use rocket_db_pools::{deadpool_redis::redis::AsyncCommands, Connection};
+use todel::{
+    http::{Cache, DB},
+    ids::IdGenerator,
+    models::{Message, MessageCreate, ServerPayload, Webhook},
+    Conf,
+};
+use tokio::sync::Mutex;
+
+use crate::rate_limit::{RateLimitedRouteResponse, RateLimiter};
+
+/// Send a message through a webhook.
+///
+/// This doesn't need a user session, the webhook's token authenticates the request instead. The
+/// body is a regular [`MessageCreate`] except that it can't have a `_disguise`.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   --json '{"content":"New commit pushed to main"}' \
+///   https://api.eludris.gay/webhooks/5490083823650/dWV4Q2xZb3R6c1pMbkNRQ3RVWkhqZ0x5TW1aWFlqRkE
+///
+/// {
+///   "id": 5490083823655,
+///   "author": {
+///     "id": 5490083823650,
+///     "username": "GitHub",
+///     "social_credit": 0,
+///     "status": {
+///       "type": "OFFLINE"
+///     },
+///     "avatar": 2255112175647,
+///     "badges": 0,
+///     "permissions": 0
+///   },
+///   "content": "New commit pushed to main",
+///   "channel": {
+///     "type": "TEXT",
+///     "id": 4080402038789,
+///     "sphere_id": 4080402038786,
+///     "position": 1,
+///     "name": "commits"
+///   },
+///   "attachments": [],
+///   "embeds": [],
+///   "reactions": [],
+///   "webhook": {
+///     "id": 5490083823650,
+///     "name": "GitHub",
+///     "avatar": 2255112175647
+///   }
+/// }
+/// ```
+#[autodoc("/webhooks", category = "Webhooks")]
+#[post("/<webhook_id>/<token>", data = "<message>")]
+pub async fn execute_webhook(
+    webhook_id: u64,
+    token: &str,
+    message: Json<MessageCreate>,
+    conf: &State<Conf>,
+    mut cache: Connection<Cache>,
+    mut db: Connection<DB>,
+    id_generator: &State<Mutex<IdGenerator>>,
+) -> RateLimitedRouteResponse<Json<Message>> {
+    let mut rate_limiter =
+        RateLimiter::new("execute_webhook", format!("webhook:{}", webhook_id), conf);
+    rate_limiter.process_rate_limit(&mut cache).await?;
+
+    let webhook = Webhook::get_with_token(webhook_id, token, &mut db)
+        .await
+        .map_err(|err| rate_limiter.add_headers(err))?;
+
+    let mut cache = cache.into_inner();
+    let message = Message::create_from_webhook(
+        message.into_inner(),
+        &webhook,
+        &mut *id_generator.lock().await,
+        &mut db,
+        &mut cache,
+    )
+    .await
+    .map_err(|err| rate_limiter.add_headers(err))?;
+
+    cache
+        .publish::<&str, String, ()>(
+            "eludris-events",
+            serde_json::to_string(&ServerPayload::MessageCreate(Box::new(message.clone())))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let message_clone = message.clone();
+    tokio::spawn(async move { message_clone.populate_embeds(db.into_inner(), cache).await });
+
+    rate_limiter.wrap_response(Json(message))
+}
@@ -0,0 +1,13 @@
+use rocket::Route;
+
+mod delete;
+mod edit;
+mod execute;
+
+pub fn get_routes() -> Vec<Route> {
+    routes![
+        edit::edit_webhook,
+        delete::delete_webhook,
+        execute::execute_webhook,
+    ]
+}
@@ -1,18 +1,36 @@
 use futures::stream::{SplitSink, SplitStream};
-use futures::StreamExt;
+use futures::{SinkExt, StreamExt};
 use redis::aio::Connection;
-use redis::AsyncCommands;
+use redis::{AsyncCommands, ExistenceCheck, SetExpiry, SetOptions};
 use sqlx::{Pool, Postgres};
+use std::borrow::Cow;
+use std::collections::VecDeque;
 use std::net::IpAddr;
 use std::sync::Arc;
-use todel::models::{ClientPayload, Secret, ServerPayload, Session, StatusType, User};
+use std::time::{Duration, SystemTime};
+use todel::ids::IdGenerator;
+use todel::models::{
+    ClientPayload, DirectMessageChannel, GatewayIntent, GroupChannel, OAuthScope, ReadState,
+    Secret, ServerPayload, Session, SphereChannel, SpherePermission, StatusType, User, VoiceState,
+};
+use todel::Conf;
 use tokio::net::TcpStream;
 use tokio::sync::Mutex;
 use tokio::time::Instant;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
 use tokio_tungstenite::tungstenite::Message as WebSocketMessage;
 use tokio_tungstenite::WebSocketStream;
 
-use crate::handle_connection::{send_payload, SessionData};
+use crate::handle_connection::presence::{check_idle, mark_active, publish_presence};
+use crate::handle_connection::voice::{
+    disconnect_voice, get_channel_voice_states, get_voice_states, publish_voice_state,
+    set_voice_state,
+};
+use crate::handle_connection::{
+    channel_spheres, end_session, replay_buffer_key, resume_key, send_payload, BufferedPayload,
+    SessionData, INVALID_INTENTS_CLOSE_CODE,
+};
 use crate::rate_limit::RateLimiter;
 
 pub async fn handle_client(
@@ -26,7 +44,9 @@ pub async fn handle_client(
     pool: Arc<Pool<Postgres>>,
     last_ping: Arc<Mutex<Instant>>,
     secret: Arc<Secret>,
-) -> String {
+    id_generator: Arc<Mutex<IdGenerator>>,
+    conf: Arc<Conf>,
+) -> CloseFrame<'static> {
     while let Some(msg) = rx.next().await {
         log::trace!("New gateway message:\n{:#?}", msg);
         if let Err(wait) = rate_limiter.process_rate_limit().await {
@@ -35,7 +55,7 @@ pub async fn handle_client(
                     "Disconnected a client: {}, reason: Hit rate_limit",
                     rl_address
                 );
-                return "Client got ratelimited".to_string();
+                return close_frame(CloseCode::Error, "Client got ratelimited");
             } else {
                 send_payload(&tx, &ServerPayload::RateLimit { wait }).await;
                 rate_limited = true;
@@ -48,12 +68,29 @@ pub async fn handle_client(
                 WebSocketMessage::Text(message) => {
                     match serde_json::from_str::<ClientPayload>(&message) {
                         Ok(payload) => {
+                            if let ClientPayload::Authenticate { intents, .. } = payload {
+                                if let Err(err) = validate_intents(intents) {
+                                    return close_frame(
+                                        CloseCode::Library(INVALID_INTENTS_CLOSE_CODE),
+                                        err,
+                                    );
+                                }
+                            }
                             if let Err(err) = handle_payload(
-                                payload, &last_ping, &session, &cache, &tx, &pool, &secret,
+                                payload,
+                                &last_ping,
+                                &session,
+                                &cache,
+                                &tx,
+                                rl_address,
+                                &pool,
+                                &secret,
+                                &id_generator,
+                                &conf,
                             )
                             .await
                             {
-                                return err;
+                                return close_frame(CloseCode::Error, err);
                             }
                         }
                         _ => log::debug!("Unknown gateway payload: {}", message),
@@ -61,10 +98,31 @@ pub async fn handle_client(
                 }
                 _ => log::debug!("Unsupported Gateway message type."),
             },
-            Err(_) => return "Server failed to receive payload".to_string(),
+            Err(_) => return close_frame(CloseCode::Error, "Server failed to receive payload"),
         }
     }
-    "Connection unexpectedly died".to_string()
+    close_frame(CloseCode::Error, "Connection unexpectedly died")
+}
+
+/// How many seconds a user has to wait between typing in the same channel.
+const TYPING_THROTTLE: usize = 5;
+
+fn close_frame(code: CloseCode, reason: impl Into<Cow<'static, str>>) -> CloseFrame<'static> {
+    CloseFrame {
+        code,
+        reason: reason.into(),
+    }
+}
+
+/// Check that a client only asked for intents that exist and can be used together.
+fn validate_intents(intents: u64) -> Result<(), &'static str> {
+    if intents & !GatewayIntent::ALL != 0 {
+        return Err("Unknown intents");
+    }
+    if GatewayIntent::Reactions.is_set(intents) && !GatewayIntent::Messages.is_set(intents) {
+        return Err("The reactions intent requires the messages intent");
+    }
+    Ok(())
 }
 
 async fn handle_payload(
@@ -73,16 +131,32 @@ async fn handle_payload(
     session: &Arc<Mutex<Option<SessionData>>>,
     cache: &Arc<Mutex<Connection>>,
     tx: &Arc<Mutex<SplitSink<WebSocketStream<TcpStream>, WebSocketMessage>>>,
+    rl_address: IpAddr,
     pool: &Arc<Pool<Postgres>>,
     secret: &Arc<Secret>,
+    id_generator: &Arc<Mutex<IdGenerator>>,
+    conf: &Arc<Conf>,
 ) -> Result<(), String> {
+    if !matches!(payload, ClientPayload::Ping) {
+        if let Some(session) = session.lock().await.as_ref() {
+            let mut cache = cache.lock().await;
+            if let Some(status) = mark_active(session.user.id, &mut cache).await {
+                if !matches!(payload, ClientPayload::UpdatePresence(_)) {
+                    publish_presence(session.user.id, status, &mut cache).await;
+                }
+            }
+        }
+    }
     match payload {
         ClientPayload::Ping => {
             let mut last_ping = last_ping.lock().await;
             *last_ping = Instant::now();
             send_payload(tx, &ServerPayload::Pong).await;
+            if let Some(session) = session.lock().await.as_ref() {
+                check_idle(session, conf, &mut *cache.lock().await).await;
+            }
         }
-        ClientPayload::Authenticate(token) => {
+        ClientPayload::Authenticate { token, intents } => {
             let mut session = session.lock().await;
             if session.is_some() {
                 return Ok(());
@@ -97,10 +171,16 @@ async fn handle_payload(
                     return Err("Server failed to authenticate client".to_string());
                 }
             };
-            let user_session = match Session::validate_token(&token, secret, &mut db).await {
-                Ok(session) => session,
-                Err(_) => return Err("Invalid credentials".to_string()),
-            };
+            let user_session =
+                match Session::validate_token(&token, rl_address, secret, conf, &mut db).await {
+                    Ok(session) => session,
+                    Err(_) => return Err("Invalid credentials".to_string()),
+                };
+            if let Some(scopes) = user_session.scopes {
+                if !OAuthScope::Gateway.is_set(scopes) {
+                    return Err("Token is missing the gateway scope".to_string());
+                }
+            }
             let mut cache = cache.lock().await;
             let sessions: u32 = match cache
                 .incr(format!("session:{}", user_session.user_id), 1)
@@ -112,6 +192,7 @@ async fn handle_payload(
                     return Err("Failed to connect user".to_string());
                 }
             };
+            mark_active(user_session.user_id, &mut cache).await;
             if sessions == 1 {
                 if let Err(err) = cache
                     .sadd::<_, _, ()>("sessions", user_session.user_id)
@@ -149,16 +230,395 @@ async fn handle_payload(
                 .get_spheres(&mut db, &mut *cache)
                 .await
                 .map_err(|_| "Failed to connect user".to_string())?;
-            let payload = ServerPayload::Authenticated { user, spheres };
+            let direct_messages = DirectMessageChannel::get_all(user.id, &mut db, &mut *cache)
+                .await
+                .map_err(|_| "Failed to connect user".to_string())?;
+            let groups = GroupChannel::get_all(user.id, &mut db, &mut *cache)
+                .await
+                .map_err(|_| "Failed to connect user".to_string())?;
+            let read_states = ReadState::get_all(user.id, &mut db)
+                .await
+                .map_err(|_| "Failed to connect user".to_string())?;
+            let voice_states = get_voice_states(&spheres, &mut cache).await;
+            let payload = ServerPayload::Authenticated {
+                user,
+                spheres,
+                direct_messages,
+                groups,
+                read_states,
+                voice_states,
+                session_id: id_generator.lock().await.generate(),
+            };
             send_payload(tx, &payload).await;
-            if let ServerPayload::Authenticated { user, spheres } = payload {
+            if let ServerPayload::Authenticated {
+                user,
+                spheres,
+                direct_messages,
+                groups,
+                session_id,
+                ..
+            } = payload
+            {
                 *session = Some(SessionData {
+                    id: session_id,
                     session: user_session,
                     user,
                     sphere_ids: spheres.iter().map(|s| s.id).collect(),
+                    group_ids: groups.iter().map(|g| g.id).collect(),
+                    direct_ids: direct_messages.iter().map(|d| d.id).collect(),
+                    channel_spheres: channel_spheres(&spheres),
+                    intents,
+                    seq: 0,
+                    replay_buffer: VecDeque::new(),
+                    connected: true,
+                    resumed: false,
+                    voice_state: None,
                 });
             }
         }
+        ClientPayload::Resume {
+            token,
+            session_id,
+            seq,
+        } => {
+            let mut session = session.lock().await;
+            if session.is_some() {
+                return Ok(());
+            }
+            let mut db = match pool.acquire().await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    log::error!("Couldn't acquire database connection for Resume: {}", err);
+                    return Err("Server failed to resume session".to_string());
+                }
+            };
+            let user_session =
+                match Session::validate_token(&token, rl_address, secret, conf, &mut db).await {
+                    Ok(session) => session,
+                    Err(_) => return Err("Invalid credentials".to_string()),
+                };
+            if let Some(scopes) = user_session.scopes {
+                if !OAuthScope::Gateway.is_set(scopes) {
+                    return Err("Token is missing the gateway scope".to_string());
+                }
+            }
+            let user = match User::get_unfiltered(user_session.user_id, &mut db).await {
+                Ok(user) => user,
+                Err(err) => {
+                    log::error!("Failed to get user info: {}", err);
+                    return Err("Failed to resume session".to_string());
+                }
+            };
+            let mut cache = cache.lock().await;
+            let (user_id, intents): (Option<u64>, Option<u64>) = match cache
+                .hget(resume_key(session_id), &["user_id", "intents"])
+                .await
+            {
+                Ok(data) => data,
+                Err(err) => {
+                    log::error!("Failed to get resumable session: {}", err);
+                    return Err("Server failed to resume session".to_string());
+                }
+            };
+            let intents = match intents {
+                Some(intents) if user_id == Some(user_session.user_id) => intents,
+                _ => {
+                    send_payload(tx, &ServerPayload::InvalidSession).await;
+                    return Ok(());
+                }
+            };
+            // removing the key claims the session, stopping the dropped connection from
+            // buffering any more events or cleaning it up
+            match cache.del::<_, u32>(resume_key(session_id)).await {
+                Ok(1) => {}
+                Ok(_) => {
+                    send_payload(tx, &ServerPayload::InvalidSession).await;
+                    return Ok(());
+                }
+                Err(err) => {
+                    log::error!("Failed to claim resumable session: {}", err);
+                    return Err("Server failed to resume session".to_string());
+                }
+            }
+            let events: Vec<String> = match cache.lrange(replay_buffer_key(session_id), 0, -1).await
+            {
+                Ok(events) => events,
+                Err(err) => {
+                    log::error!("Failed to get session replay buffer: {}", err);
+                    end_session(session_id, &user, &mut cache).await;
+                    return Err("Failed to resume session".to_string());
+                }
+            };
+            let events: Vec<(u64, String)> = events
+                .into_iter()
+                .filter_map(|event| {
+                    serde_json::from_str::<BufferedPayload>(&event)
+                        .ok()
+                        .map(|payload| (payload.s, event))
+                })
+                .collect();
+            if events.first().is_some_and(|(s, _)| *s > seq + 1) {
+                // some of the events the client missed have already been evicted
+                end_session(session_id, &user, &mut cache).await;
+                send_payload(tx, &ServerPayload::InvalidSession).await;
+                return Ok(());
+            }
+            let spheres = user
+                .get_spheres(&mut db, &mut *cache)
+                .await
+                .map_err(|_| "Failed to resume session".to_string())?;
+            let direct_messages = DirectMessageChannel::get_all(user.id, &mut db, &mut *cache)
+                .await
+                .map_err(|_| "Failed to resume session".to_string())?;
+            let groups = GroupChannel::get_all(user.id, &mut db, &mut *cache)
+                .await
+                .map_err(|_| "Failed to resume session".to_string())?;
+            if mark_active(user.id, &mut cache).await.is_some()
+                && user.status.status_type != StatusType::Offline
+            {
+                publish_presence(user.id, user.status.clone(), &mut cache).await;
+            }
+            let mut last_seq = seq;
+            let mut replay_buffer = VecDeque::with_capacity(events.len());
+            {
+                let mut tx = tx.lock().await;
+                for (s, event) in events {
+                    replay_buffer.push_back(event.clone());
+                    if s > seq {
+                        last_seq = s;
+                        if let Err(err) = tx.send(WebSocketMessage::Text(event)).await {
+                            log::error!("Could not send payload: {}", err);
+                        }
+                    }
+                }
+            }
+            send_payload(tx, &ServerPayload::Resumed).await;
+            *session = Some(SessionData {
+                id: session_id,
+                session: user_session,
+                user,
+                sphere_ids: spheres.iter().map(|s| s.id).collect(),
+                group_ids: groups.iter().map(|g| g.id).collect(),
+                direct_ids: direct_messages.iter().map(|d| d.id).collect(),
+                channel_spheres: channel_spheres(&spheres),
+                intents,
+                seq: last_seq,
+                replay_buffer,
+                connected: true,
+                resumed: false,
+                voice_state: None,
+            });
+        }
+        ClientPayload::UpdatePresence(status) => {
+            let mut session = session.lock().await;
+            let session = match session.as_mut() {
+                Some(session) => session,
+                None => return Ok(()),
+            };
+            let mut rate_limiter = RateLimiter::new(
+                Arc::clone(cache),
+                format!("presence:{}", session.session.rate_limit_identifier()),
+                Duration::from_secs(conf.pandemonium.presence_rate_limit.reset_after as u64),
+                conf.pandemonium.presence_rate_limit.limit,
+            );
+            if let Err(wait) = rate_limiter.process_rate_limit().await {
+                send_payload(tx, &ServerPayload::RateLimit { wait }).await;
+                return Ok(());
+            }
+            let mut db = match pool.acquire().await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    log::error!(
+                        "Couldn't acquire database connection for UpdatePresence: {}",
+                        err
+                    );
+                    return Ok(());
+                }
+            };
+            if let Err(err) = User::update_status(session.user.id, &status, &mut db).await {
+                log::debug!("Couldn't update presence: {:?}", err);
+                return Ok(());
+            }
+            session.user.status = status.clone();
+            publish_presence(session.user.id, status, &mut *cache.lock().await).await;
+        }
+        ClientPayload::TypingStart { channel_id } => {
+            let user_id = match session.lock().await.as_ref() {
+                Some(session) => session.user.id,
+                None => return Ok(()),
+            };
+            let throttled = !cache
+                .lock()
+                .await
+                .set_options::<_, _, bool>(
+                    format!("typing:{}:{}", user_id, channel_id),
+                    1,
+                    SetOptions::default()
+                        .conditional_set(ExistenceCheck::NX)
+                        .with_expiration(SetExpiry::EX(TYPING_THROTTLE)),
+                )
+                .await
+                .unwrap_or(false);
+            if throttled {
+                return Ok(());
+            }
+            let mut db = match pool.acquire().await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    log::error!(
+                        "Couldn't acquire database connection for TypingStart: {}",
+                        err
+                    );
+                    return Ok(());
+                }
+            };
+            let sphere_id = match SphereChannel::get(channel_id, &mut db).await {
+                Ok(SphereChannel::Text(channel)) => channel.sphere_id,
+                _ => return Ok(()),
+            };
+            match SphereChannel::has_member(channel_id, user_id, &mut db).await {
+                Ok(true) => {}
+                _ => return Ok(()),
+            }
+            if SphereChannel::require_permission(
+                channel_id,
+                user_id,
+                SpherePermission::SendMessages,
+                &mut db,
+            )
+            .await
+            .is_err()
+            {
+                return Ok(());
+            }
+            if let Err(err) = cache
+                .lock()
+                .await
+                .publish::<_, _, ()>(
+                    "eludris-events",
+                    serde_json::to_string(&ServerPayload::TypingStart {
+                        channel_id,
+                        sphere_id,
+                        user_id,
+                        timestamp: SystemTime::now()
+                            .duration_since(SystemTime::UNIX_EPOCH)
+                            .unwrap_or(Duration::ZERO)
+                            .as_secs(),
+                    })
+                    .expect("Couldn't serialize TYPING_START event"),
+                )
+                .await
+            {
+                log::error!("Failed to publish TYPING_START: {}", err);
+            }
+        }
+        ClientPayload::VoiceStateUpdate {
+            channel_id,
+            self_mute,
+            self_deaf,
+        } => {
+            let mut session = session.lock().await;
+            let session = match session.as_mut() {
+                Some(session) => session,
+                None => return Ok(()),
+            };
+            let channel_id = match channel_id {
+                Some(channel_id) => channel_id,
+                None => {
+                    disconnect_voice(session, &mut *cache.lock().await).await;
+                    return Ok(());
+                }
+            };
+            let mut db = match pool.acquire().await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    log::error!(
+                        "Couldn't acquire database connection for VoiceStateUpdate: {}",
+                        err
+                    );
+                    return Ok(());
+                }
+            };
+            let sphere_id = match SphereChannel::get(channel_id, &mut db).await {
+                Ok(SphereChannel::Voice(channel)) => channel.sphere_id,
+                _ => return Ok(()),
+            };
+            match SphereChannel::has_member(channel_id, session.user.id, &mut db).await {
+                Ok(true) => {}
+                _ => return Ok(()),
+            }
+            if SphereChannel::require_permission(
+                channel_id,
+                session.user.id,
+                SpherePermission::ViewChannels,
+                &mut db,
+            )
+            .await
+            .is_err()
+            {
+                return Ok(());
+            }
+            let state = VoiceState {
+                user_id: session.user.id,
+                session_id: session.id,
+                channel_id: Some(channel_id),
+                sphere_id,
+                self_mute,
+                self_deaf,
+            };
+            let mut cache = cache.lock().await;
+            let previous = match set_voice_state(&state, &mut cache).await {
+                Ok(previous) => previous,
+                Err(()) => return Ok(()),
+            };
+            if let Some(mut previous) = previous {
+                // the new state's sphere won't hear about the user leaving another one
+                if previous.sphere_id != sphere_id {
+                    previous.channel_id = None;
+                    publish_voice_state(&previous, &mut cache).await;
+                }
+            }
+            session.voice_state = Some(state.clone());
+            publish_voice_state(&state, &mut cache).await;
+        }
+        ClientPayload::VoiceSignal {
+            session_id: target_session_id,
+            signal,
+        } => {
+            let session = session.lock().await;
+            let session = match session.as_ref() {
+                Some(session) => session,
+                None => return Ok(()),
+            };
+            let channel_id = match session.voice_state.as_ref().and_then(|s| s.channel_id) {
+                Some(channel_id) => channel_id,
+                None => return Ok(()),
+            };
+            let mut cache = cache.lock().await;
+            let states = get_channel_voice_states(channel_id, &mut cache).await;
+            // both sessions have to still be the ones their users are connected from
+            if !states.iter().any(|s| s.session_id == session.id)
+                || !states.iter().any(|s| s.session_id == target_session_id)
+            {
+                return Ok(());
+            }
+            if let Err(err) = cache
+                .publish::<_, _, ()>(
+                    "eludris-events",
+                    serde_json::to_string(&ServerPayload::VoiceSignal {
+                        user_id: session.user.id,
+                        session_id: session.id,
+                        target_session_id,
+                        channel_id,
+                        signal,
+                    })
+                    .expect("Couldn't serialize VOICE_SIGNAL event"),
+                )
+                .await
+            {
+                log::error!("Failed to publish VOICE_SIGNAL: {}", err);
+            }
+        }
     }
     Ok(())
 }
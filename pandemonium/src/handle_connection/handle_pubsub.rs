@@ -6,7 +6,8 @@ use redis::AsyncCommands;
 use sqlx::Pool;
 use sqlx::Postgres;
 use std::sync::Arc;
-use todel::models::{ServerPayload, Sphere, SphereChannel, StatusType};
+use todel::models::{Channel, GatewayIntent, GroupChannel, ServerPayload, Sphere, StatusType};
+use todel::Conf;
 use tokio::net::TcpStream;
 use tokio::sync::Mutex;
 use tokio_tungstenite::tungstenite::Message as WebSocketMessage;
@@ -14,7 +15,8 @@ use tokio_tungstenite::WebSocketStream;
 
 use crate::utils::deserialize_message;
 
-use super::{send_payload, SessionData};
+use super::voice::disconnect_voice;
+use super::{channel_spheres, dispatch_event, SessionData};
 
 pub async fn handle_pubsub(
     pubsub: PubSub,
@@ -22,21 +24,24 @@ pub async fn handle_pubsub(
     cache: Arc<Mutex<Connection>>,
     tx: Arc<Mutex<SplitSink<WebSocketStream<TcpStream>, WebSocketMessage>>>,
     pool: Arc<Pool<Postgres>>,
+    conf: Arc<Conf>,
 ) {
-    pubsub
-        .into_on_message()
-        .for_each(|msg| async {
-            let mut session = session.lock().await;
-            if session.is_none() {
-                return;
-            }
-            let session = session.as_mut().unwrap();
-            match deserialize_message(msg) {
-                Ok(payload) => handle_event(payload, session, &cache, &tx, &pool).await,
-                Err(err) => log::warn!("Failed to deserialize event payload: {}", err),
-            }
-        })
-        .await;
+    let mut messages = pubsub.into_on_message();
+    while let Some(msg) = messages.next().await {
+        let mut session = session.lock().await;
+        if session.is_none() {
+            continue;
+        }
+        let session = session.as_mut().unwrap();
+        match deserialize_message(msg) {
+            Ok(payload) => handle_event(payload, session, &cache, &tx, &pool, &conf).await,
+            Err(err) => log::warn!("Failed to deserialize event payload: {}", err),
+        }
+        if session.resumed {
+            // the session lives on in another connection
+            return;
+        }
+    }
 }
 
 async fn handle_event(
@@ -45,13 +50,23 @@ async fn handle_event(
     cache: &Arc<Mutex<Connection>>,
     tx: &Arc<Mutex<SplitSink<WebSocketStream<TcpStream>, WebSocketMessage>>>,
     pool: &Arc<Pool<Postgres>>,
+    conf: &Conf,
 ) {
     match payload {
         ServerPayload::PresenceUpdate { user_id, status } => {
             if user_id == session.user.id {
                 session.user.status = status.clone();
+            } else if !session.has_intent(GatewayIntent::Presences) {
+                return;
             }
-            send_payload(tx, &ServerPayload::PresenceUpdate { user_id, status }).await;
+            dispatch_event(
+                tx,
+                session,
+                cache,
+                conf,
+                &ServerPayload::PresenceUpdate { user_id, status },
+            )
+            .await;
         }
         ServerPayload::UserUpdate(mut user) => {
             if user.id == session.user.id {
@@ -75,7 +90,7 @@ async fn handle_event(
                     user.status.text = None;
                 }
             }
-            send_payload(tx, &ServerPayload::UserUpdate(user)).await;
+            dispatch_event(tx, session, cache, conf, &ServerPayload::UserUpdate(user)).await;
         }
         ServerPayload::SphereMemberJoin { user, sphere_id } => {
             if user.id == session.user.id {
@@ -97,33 +112,120 @@ async fn handle_event(
                     }
                 };
                 session.sphere_ids.push(sphere_id);
-                send_payload(tx, &ServerPayload::SphereJoin(sphere)).await;
-            } else if session.sphere_ids.contains(&sphere_id) {
-                send_payload(tx, &ServerPayload::SphereMemberJoin { user, sphere_id }).await;
+                session
+                    .channel_spheres
+                    .extend(channel_spheres(std::slice::from_ref(&sphere)));
+                dispatch_event(tx, session, cache, conf, &ServerPayload::SphereJoin(sphere)).await;
+            } else if session.sphere_ids.contains(&sphere_id)
+                && session.has_intent(GatewayIntent::Members)
+            {
+                dispatch_event(
+                    tx,
+                    session,
+                    cache,
+                    conf,
+                    &ServerPayload::SphereMemberJoin { user, sphere_id },
+                )
+                .await;
             }
         }
         ServerPayload::SphereMemberLeave { user_id, sphere_id } => {
             if user_id == session.user.id {
                 session.sphere_ids.retain(|i| *i != sphere_id);
-                send_payload(tx, &ServerPayload::SphereLeave { sphere_id }).await;
-            } else if session.sphere_ids.contains(&sphere_id) {
-                send_payload(tx, &ServerPayload::SphereMemberLeave { user_id, sphere_id }).await;
+                if session
+                    .voice_state
+                    .as_ref()
+                    .is_some_and(|s| s.sphere_id == sphere_id)
+                {
+                    disconnect_voice(session, &mut *cache.lock().await).await;
+                }
+                dispatch_event(
+                    tx,
+                    session,
+                    cache,
+                    conf,
+                    &ServerPayload::SphereLeave { sphere_id },
+                )
+                .await;
+            } else if session.sphere_ids.contains(&sphere_id)
+                && session.has_intent(GatewayIntent::Members)
+            {
+                dispatch_event(
+                    tx,
+                    session,
+                    cache,
+                    conf,
+                    &ServerPayload::SphereMemberLeave { user_id, sphere_id },
+                )
+                .await;
             }
         }
         ServerPayload::MessageCreate(message) => {
-            if let SphereChannel::Text(channel) = &message.channel {
-                if session.sphere_ids.contains(&channel.sphere_id) {
-                    send_payload(tx, &ServerPayload::MessageCreate(message)).await;
+            if !session.has_intent(GatewayIntent::Messages) {
+                return;
+            }
+            let can_view = match &message.channel {
+                Channel::Text(channel) => {
+                    session.sphere_ids.contains(&channel.sphere_id)
+                        && can_view_channel(channel.id, session, pool, "MessageCreate").await
+                }
+                Channel::Direct(channel) => {
+                    let is_member = channel.owner.id == session.user.id
+                        || channel.recipient.id == session.user.id;
+                    // Direct message channels are created when their first message is sent
+                    if is_member && !session.direct_ids.contains(&channel.id) {
+                        session.direct_ids.push(channel.id);
+                    }
+                    is_member
                 }
+                Channel::Group(channel) => session.group_ids.contains(&channel.id),
+                _ => false,
+            };
+            if can_view {
+                dispatch_event(
+                    tx,
+                    session,
+                    cache,
+                    conf,
+                    &ServerPayload::MessageCreate(message),
+                )
+                .await;
+            }
+        }
+        ServerPayload::MessageEmbedPopulate {
+            channel_id,
+            message_id,
+            embeds,
+        } => {
+            if session.has_intent(GatewayIntent::Messages)
+                && can_view_channel(channel_id, session, pool, "MessageEmbedPopulate").await
+            {
+                dispatch_event(
+                    tx,
+                    session,
+                    cache,
+                    conf,
+                    &ServerPayload::MessageEmbedPopulate {
+                        channel_id,
+                        message_id,
+                        embeds,
+                    },
+                )
+                .await;
             }
         }
         ServerPayload::CategoryCreate {
             category,
             sphere_id,
         } => {
-            if session.sphere_ids.contains(&sphere_id) {
-                send_payload(
+            if session.sphere_ids.contains(&sphere_id)
+                && session.has_intent(GatewayIntent::SphereStructure)
+            {
+                dispatch_event(
                     tx,
+                    session,
+                    cache,
+                    conf,
                     &ServerPayload::CategoryCreate {
                         category,
                         sphere_id,
@@ -137,9 +239,14 @@ async fn handle_event(
             category_id,
             sphere_id,
         } => {
-            if session.sphere_ids.contains(&sphere_id) {
-                send_payload(
+            if session.sphere_ids.contains(&sphere_id)
+                && session.has_intent(GatewayIntent::SphereStructure)
+            {
+                dispatch_event(
                     tx,
+                    session,
+                    cache,
+                    conf,
                     &ServerPayload::CategoryUpdate {
                         data,
                         category_id,
@@ -153,9 +260,14 @@ async fn handle_event(
             category_id,
             sphere_id,
         } => {
-            if session.sphere_ids.contains(&sphere_id) {
-                send_payload(
+            if session.sphere_ids.contains(&sphere_id)
+                && session.has_intent(GatewayIntent::SphereStructure)
+            {
+                dispatch_event(
                     tx,
+                    session,
+                    cache,
+                    conf,
                     &ServerPayload::CategoryDelete {
                         category_id,
                         sphere_id,
@@ -166,8 +278,16 @@ async fn handle_event(
         }
         ServerPayload::SphereChannelCreate { channel, sphere_id } => {
             if session.sphere_ids.contains(&sphere_id) {
-                send_payload(
+                session.channel_spheres.insert(channel.get_id(), sphere_id);
+            }
+            if session.sphere_ids.contains(&sphere_id)
+                && session.has_intent(GatewayIntent::SphereStructure)
+            {
+                dispatch_event(
                     tx,
+                    session,
+                    cache,
+                    conf,
                     &ServerPayload::SphereChannelCreate { channel, sphere_id },
                 )
                 .await;
@@ -178,9 +298,14 @@ async fn handle_event(
             channel_id,
             sphere_id,
         } => {
-            if session.sphere_ids.contains(&sphere_id) {
-                send_payload(
+            if session.sphere_ids.contains(&sphere_id)
+                && session.has_intent(GatewayIntent::SphereStructure)
+            {
+                dispatch_event(
                     tx,
+                    session,
+                    cache,
+                    conf,
                     &ServerPayload::SphereChannelUpdate {
                         data,
                         channel_id,
@@ -194,9 +319,22 @@ async fn handle_event(
             channel_id,
             sphere_id,
         } => {
-            if session.sphere_ids.contains(&sphere_id) {
-                send_payload(
+            session.channel_spheres.remove(&channel_id);
+            if session
+                .voice_state
+                .as_ref()
+                .is_some_and(|s| s.channel_id == Some(channel_id))
+            {
+                disconnect_voice(session, &mut *cache.lock().await).await;
+            }
+            if session.sphere_ids.contains(&sphere_id)
+                && session.has_intent(GatewayIntent::SphereStructure)
+            {
+                dispatch_event(
                     tx,
+                    session,
+                    cache,
+                    conf,
                     &ServerPayload::SphereChannelDelete {
                         channel_id,
                         sphere_id,
@@ -206,8 +344,17 @@ async fn handle_event(
             }
         }
         ServerPayload::SphereUpdate { data, sphere_id } => {
-            if session.sphere_ids.contains(&sphere_id) {
-                send_payload(tx, &ServerPayload::SphereUpdate { data, sphere_id }).await;
+            if session.sphere_ids.contains(&sphere_id)
+                && session.has_intent(GatewayIntent::SphereStructure)
+            {
+                dispatch_event(
+                    tx,
+                    session,
+                    cache,
+                    conf,
+                    &ServerPayload::SphereUpdate { data, sphere_id },
+                )
+                .await;
             }
         }
         ServerPayload::SphereMemberUpdate {
@@ -215,9 +362,14 @@ async fn handle_event(
             user_id,
             sphere_id,
         } => {
-            if session.sphere_ids.contains(&sphere_id) {
-                send_payload(
+            if session.sphere_ids.contains(&sphere_id)
+                && (user_id == session.user.id || session.has_intent(GatewayIntent::Members))
+            {
+                dispatch_event(
                     tx,
+                    session,
+                    cache,
+                    conf,
                     &ServerPayload::SphereMemberUpdate {
                         data,
                         user_id,
@@ -231,26 +383,14 @@ async fn handle_event(
             channel_id,
             message_id,
         } => {
-            let mut db = match pool.acquire().await {
-                Ok(conn) => conn,
-                Err(err) => {
-                    log::error!(
-                        "Couldn't acquire database connection for MessageDelete: {}",
-                        err
-                    );
-                    return;
-                }
-            };
-            let channel = match SphereChannel::get(channel_id, &mut db).await {
-                Ok(channel) => channel,
-                Err(err) => {
-                    log::error!("Couldn't fetch channel data for MessageDelete: {}", err);
-                    return;
-                }
-            };
-            if session.sphere_ids.contains(&channel.get_sphere_id()) {
-                send_payload(
+            if session.has_intent(GatewayIntent::Messages)
+                && can_view_channel(channel_id, session, pool, "MessageDelete").await
+            {
+                dispatch_event(
                     tx,
+                    session,
+                    cache,
+                    conf,
                     &ServerPayload::MessageDelete {
                         channel_id,
                         message_id,
@@ -264,26 +404,14 @@ async fn handle_event(
             message_id,
             data,
         } => {
-            let mut db = match pool.acquire().await {
-                Ok(conn) => conn,
-                Err(err) => {
-                    log::error!(
-                        "Couldn't acquire database connection for MessageUpdate: {}",
-                        err
-                    );
-                    return;
-                }
-            };
-            let channel = match SphereChannel::get(channel_id, &mut db).await {
-                Ok(channel) => channel,
-                Err(err) => {
-                    log::error!("Couldn't fetch channel data for MessageUpdate: {}", err);
-                    return;
-                }
-            };
-            if session.sphere_ids.contains(&channel.get_sphere_id()) {
-                send_payload(
+            if session.has_intent(GatewayIntent::Messages)
+                && can_view_channel(channel_id, session, pool, "MessageUpdate").await
+            {
+                dispatch_event(
                     tx,
+                    session,
+                    cache,
+                    conf,
                     &ServerPayload::MessageUpdate {
                         channel_id,
                         message_id,
@@ -294,8 +422,16 @@ async fn handle_event(
             }
         }
         ServerPayload::EmojiCreate { sphere_id, emoji } => {
-            if session.sphere_ids.contains(&sphere_id) {
-                send_payload(tx, &ServerPayload::EmojiCreate { sphere_id, emoji }).await;
+            if session.sphere_ids.contains(&sphere_id) && session.has_intent(GatewayIntent::Emojis)
+            {
+                dispatch_event(
+                    tx,
+                    session,
+                    cache,
+                    conf,
+                    &ServerPayload::EmojiCreate { sphere_id, emoji },
+                )
+                .await;
             }
         }
         ServerPayload::EmojiUpdate {
@@ -303,9 +439,13 @@ async fn handle_event(
             emoji_id,
             data,
         } => {
-            if session.sphere_ids.contains(&sphere_id) {
-                send_payload(
+            if session.sphere_ids.contains(&sphere_id) && session.has_intent(GatewayIntent::Emojis)
+            {
+                dispatch_event(
                     tx,
+                    session,
+                    cache,
+                    conf,
                     &ServerPayload::EmojiUpdate {
                         sphere_id,
                         emoji_id,
@@ -319,9 +459,13 @@ async fn handle_event(
             sphere_id,
             emoji_id,
         } => {
-            if session.sphere_ids.contains(&sphere_id) {
-                send_payload(
+            if session.sphere_ids.contains(&sphere_id) && session.has_intent(GatewayIntent::Emojis)
+            {
+                dispatch_event(
                     tx,
+                    session,
+                    cache,
+                    conf,
                     &ServerPayload::EmojiDelete {
                         sphere_id,
                         emoji_id,
@@ -336,26 +480,14 @@ async fn handle_event(
             user_id,
             emoji,
         } => {
-            let mut db = match pool.acquire().await {
-                Ok(conn) => conn,
-                Err(err) => {
-                    log::error!(
-                        "Couldn't acquire database connection for MessageReact: {}",
-                        err
-                    );
-                    return;
-                }
-            };
-            let channel = match SphereChannel::get(channel_id, &mut db).await {
-                Ok(channel) => channel,
-                Err(err) => {
-                    log::error!("Couldn't fetch channel data for MessageReact: {}", err);
-                    return;
-                }
-            };
-            if session.sphere_ids.contains(&channel.get_sphere_id()) {
-                send_payload(
+            if session.has_intent(GatewayIntent::Reactions)
+                && can_view_channel(channel_id, session, pool, "MessageReact").await
+            {
+                dispatch_event(
                     tx,
+                    session,
+                    cache,
+                    conf,
                     &ServerPayload::MessageReact {
                         channel_id,
                         message_id,
@@ -372,29 +504,14 @@ async fn handle_event(
             user_id,
             emoji,
         } => {
-            let mut db = match pool.acquire().await {
-                Ok(conn) => conn,
-                Err(err) => {
-                    log::error!(
-                        "Couldn't acquire database connection for MessageReactionDelete: {}",
-                        err
-                    );
-                    return;
-                }
-            };
-            let channel = match SphereChannel::get(channel_id, &mut db).await {
-                Ok(channel) => channel,
-                Err(err) => {
-                    log::error!(
-                        "Couldn't fetch channel data for MessageReactionDelete: {}",
-                        err
-                    );
-                    return;
-                }
-            };
-            if session.sphere_ids.contains(&channel.get_sphere_id()) {
-                send_payload(
+            if session.has_intent(GatewayIntent::Reactions)
+                && can_view_channel(channel_id, session, pool, "MessageReactionDelete").await
+            {
+                dispatch_event(
                     tx,
+                    session,
+                    cache,
+                    conf,
                     &ServerPayload::MessageReactionDelete {
                         channel_id,
                         message_id,
@@ -409,30 +526,194 @@ async fn handle_event(
             channel_id,
             message_id,
         } => {
-            let mut db = match pool.acquire().await {
-                Ok(conn) => conn,
-                Err(err) => {
-                    log::error!(
-                        "Couldn't acquire database connection for MessageReactionClear: {}",
-                        err
-                    );
-                    return;
-                }
-            };
-            let channel = match SphereChannel::get(channel_id, &mut db).await {
-                Ok(channel) => channel,
-                Err(err) => {
-                    log::error!(
-                        "Couldn't fetch channel data for MessageReactionClear: {}",
-                        err
-                    );
+            if session.has_intent(GatewayIntent::Reactions)
+                && can_view_channel(channel_id, session, pool, "MessageReactionClear").await
+            {
+                dispatch_event(
+                    tx,
+                    session,
+                    cache,
+                    conf,
+                    &ServerPayload::MessageReactionClear {
+                        channel_id,
+                        message_id,
+                    },
+                )
+                .await;
+            }
+        }
+        ServerPayload::RoleCreate { role, sphere_id } => {
+            if session.sphere_ids.contains(&sphere_id)
+                && session.has_intent(GatewayIntent::SphereStructure)
+            {
+                dispatch_event(
+                    tx,
+                    session,
+                    cache,
+                    conf,
+                    &ServerPayload::RoleCreate { role, sphere_id },
+                )
+                .await;
+            }
+        }
+        ServerPayload::RoleUpdate {
+            data,
+            role_id,
+            sphere_id,
+        } => {
+            if session.sphere_ids.contains(&sphere_id)
+                && session.has_intent(GatewayIntent::SphereStructure)
+            {
+                dispatch_event(
+                    tx,
+                    session,
+                    cache,
+                    conf,
+                    &ServerPayload::RoleUpdate {
+                        data,
+                        role_id,
+                        sphere_id,
+                    },
+                )
+                .await;
+            }
+        }
+        ServerPayload::RoleDelete { role_id, sphere_id } => {
+            if session.sphere_ids.contains(&sphere_id)
+                && session.has_intent(GatewayIntent::SphereStructure)
+            {
+                dispatch_event(
+                    tx,
+                    session,
+                    cache,
+                    conf,
+                    &ServerPayload::RoleDelete { role_id, sphere_id },
+                )
+                .await;
+            }
+        }
+        ServerPayload::ChannelOverwriteUpdate {
+            overwrite,
+            channel_id,
+            sphere_id,
+        } => {
+            if session.sphere_ids.contains(&sphere_id)
+                && session.has_intent(GatewayIntent::SphereStructure)
+            {
+                dispatch_event(
+                    tx,
+                    session,
+                    cache,
+                    conf,
+                    &ServerPayload::ChannelOverwriteUpdate {
+                        overwrite,
+                        channel_id,
+                        sphere_id,
+                    },
+                )
+                .await;
+            }
+        }
+        ServerPayload::ChannelOverwriteDelete {
+            target_id,
+            channel_id,
+            sphere_id,
+        } => {
+            if session.sphere_ids.contains(&sphere_id)
+                && session.has_intent(GatewayIntent::SphereStructure)
+            {
+                dispatch_event(
+                    tx,
+                    session,
+                    cache,
+                    conf,
+                    &ServerPayload::ChannelOverwriteDelete {
+                        target_id,
+                        channel_id,
+                        sphere_id,
+                    },
+                )
+                .await;
+            }
+        }
+        ServerPayload::SphereMemberBan { user_id, sphere_id } => {
+            if session.sphere_ids.contains(&sphere_id) {
+                if user_id == session.user.id {
+                    session.sphere_ids.retain(|i| *i != sphere_id);
+                } else if !session.has_intent(GatewayIntent::Members) {
                     return;
                 }
-            };
-            if session.sphere_ids.contains(&channel.get_sphere_id()) {
-                send_payload(
+                dispatch_event(
                     tx,
-                    &ServerPayload::MessageReactionClear {
+                    session,
+                    cache,
+                    conf,
+                    &ServerPayload::SphereMemberBan { user_id, sphere_id },
+                )
+                .await;
+            }
+        }
+        ServerPayload::SphereMemberTimeout {
+            user_id,
+            sphere_id,
+            timed_out_until,
+        } => {
+            if session.sphere_ids.contains(&sphere_id)
+                && (user_id == session.user.id || session.has_intent(GatewayIntent::Members))
+            {
+                dispatch_event(
+                    tx,
+                    session,
+                    cache,
+                    conf,
+                    &ServerPayload::SphereMemberTimeout {
+                        user_id,
+                        sphere_id,
+                        timed_out_until,
+                    },
+                )
+                .await;
+            }
+        }
+        ServerPayload::TypingStart {
+            channel_id,
+            sphere_id,
+            user_id,
+            timestamp,
+        } => {
+            if user_id != session.user.id
+                && session.sphere_ids.contains(&sphere_id)
+                && session.has_intent(GatewayIntent::Typing)
+                && can_view_channel(channel_id, session, pool, "TypingStart").await
+            {
+                dispatch_event(
+                    tx,
+                    session,
+                    cache,
+                    conf,
+                    &ServerPayload::TypingStart {
+                        channel_id,
+                        sphere_id,
+                        user_id,
+                        timestamp,
+                    },
+                )
+                .await;
+            }
+        }
+        ServerPayload::MessageAck {
+            user_id,
+            channel_id,
+            message_id,
+        } => {
+            if user_id == session.user.id {
+                dispatch_event(
+                    tx,
+                    session,
+                    cache,
+                    conf,
+                    &ServerPayload::MessageAck {
+                        user_id,
                         channel_id,
                         message_id,
                     },
@@ -440,8 +721,217 @@ async fn handle_event(
                 .await;
             }
         }
+        ServerPayload::GroupCreate(group) => {
+            if group.members.iter().any(|m| m.id == session.user.id) {
+                if !session.group_ids.contains(&group.id) {
+                    session.group_ids.push(group.id);
+                }
+                dispatch_event(tx, session, cache, conf, &ServerPayload::GroupCreate(group)).await;
+            }
+        }
+        ServerPayload::GroupUpdate { data, channel_id } => {
+            if session.group_ids.contains(&channel_id) {
+                dispatch_event(
+                    tx,
+                    session,
+                    cache,
+                    conf,
+                    &ServerPayload::GroupUpdate { data, channel_id },
+                )
+                .await;
+            }
+        }
+        ServerPayload::GroupMemberAdd { user, channel_id } => {
+            if user.id == session.user.id {
+                let mut db = match pool.acquire().await {
+                    Ok(conn) => conn,
+                    Err(err) => {
+                        log::error!(
+                            "Couldn't acquire database connection for GroupMemberAdd: {}",
+                            err
+                        );
+                        return;
+                    }
+                };
+                let group =
+                    match GroupChannel::get(channel_id, &mut db, &mut *cache.lock().await).await {
+                        Ok(group) => group,
+                        Err(err) => {
+                            log::error!("Couldn't fetch group data for GroupMemberAdd: {}", err);
+                            return;
+                        }
+                    };
+                session.group_ids.push(channel_id);
+                dispatch_event(tx, session, cache, conf, &ServerPayload::GroupCreate(group)).await;
+            } else if session.group_ids.contains(&channel_id) {
+                dispatch_event(
+                    tx,
+                    session,
+                    cache,
+                    conf,
+                    &ServerPayload::GroupMemberAdd { user, channel_id },
+                )
+                .await;
+            }
+        }
+        ServerPayload::GroupMemberRemove {
+            user_id,
+            channel_id,
+        } => {
+            if session.group_ids.contains(&channel_id) {
+                if user_id == session.user.id {
+                    session.group_ids.retain(|i| *i != channel_id);
+                }
+                dispatch_event(
+                    tx,
+                    session,
+                    cache,
+                    conf,
+                    &ServerPayload::GroupMemberRemove {
+                        user_id,
+                        channel_id,
+                    },
+                )
+                .await;
+            }
+        }
+        ServerPayload::PostCreate(post) => {
+            if session.has_intent(GatewayIntent::Posts)
+                && can_view_channel(post.channel_id, session, pool, "PostCreate").await
+            {
+                dispatch_event(tx, session, cache, conf, &ServerPayload::PostCreate(post)).await;
+            }
+        }
+        ServerPayload::PostUpdate {
+            channel_id,
+            post_id,
+            data,
+        } => {
+            if session.has_intent(GatewayIntent::Posts)
+                && can_view_channel(channel_id, session, pool, "PostUpdate").await
+            {
+                dispatch_event(
+                    tx,
+                    session,
+                    cache,
+                    conf,
+                    &ServerPayload::PostUpdate {
+                        channel_id,
+                        post_id,
+                        data,
+                    },
+                )
+                .await;
+            }
+        }
+        ServerPayload::PostDelete {
+            channel_id,
+            post_id,
+        } => {
+            if session.has_intent(GatewayIntent::Posts)
+                && can_view_channel(channel_id, session, pool, "PostDelete").await
+            {
+                dispatch_event(
+                    tx,
+                    session,
+                    cache,
+                    conf,
+                    &ServerPayload::PostDelete {
+                        channel_id,
+                        post_id,
+                    },
+                )
+                .await;
+            }
+        }
+        ServerPayload::VoiceStateUpdate(state) => {
+            if state.user_id == session.user.id {
+                if state.session_id == session.id {
+                    session.voice_state = state.channel_id.map(|_| state.clone());
+                } else if state.channel_id.is_some() {
+                    // the user connected to voice from another session
+                    session.voice_state = None;
+                }
+            } else if !session.has_intent(GatewayIntent::VoiceStates) {
+                return;
+            }
+            if session.sphere_ids.contains(&state.sphere_id) {
+                dispatch_event(
+                    tx,
+                    session,
+                    cache,
+                    conf,
+                    &ServerPayload::VoiceStateUpdate(state),
+                )
+                .await;
+            }
+        }
+        ServerPayload::VoiceSignal {
+            user_id,
+            session_id,
+            target_session_id,
+            channel_id,
+            signal,
+        } => {
+            if target_session_id == session.id {
+                dispatch_event(
+                    tx,
+                    session,
+                    cache,
+                    conf,
+                    &ServerPayload::VoiceSignal {
+                        user_id,
+                        session_id,
+                        target_session_id,
+                        channel_id,
+                        signal,
+                    },
+                )
+                .await;
+            }
+        }
         payload => {
-            send_payload(tx, &payload).await;
+            dispatch_event(tx, session, cache, conf, &payload).await;
+        }
+    }
+}
+
+/// Check whether a session's user is allowed to view a channel.
+///
+/// Only sphere channels need their permissions to be resolved from the database, which is skipped
+/// for channels outside of the session's spheres.
+async fn can_view_channel(
+    channel_id: u64,
+    session: &SessionData,
+    pool: &Arc<Pool<Postgres>>,
+    event: &str,
+) -> bool {
+    if session.group_ids.contains(&channel_id) || session.direct_ids.contains(&channel_id) {
+        return true;
+    }
+    if !session.in_sphere_channel(channel_id) {
+        return false;
+    }
+    let mut db = match pool.acquire().await {
+        Ok(conn) => conn,
+        Err(err) => {
+            log::error!(
+                "Couldn't acquire database connection for {}: {}",
+                event,
+                err
+            );
+            return false;
+        }
+    };
+    match Channel::can_view(channel_id, session.user.id, &mut db).await {
+        Ok(can_view) => can_view,
+        Err(err) => {
+            log::error!(
+                "Couldn't resolve channel permissions for {}: {}",
+                event,
+                err
+            );
+            false
         }
     }
 }
@@ -1,22 +1,30 @@
 mod handle_client;
 mod handle_pubsub;
+mod presence;
+mod voice;
 
-use futures::stream::{SplitSink, SplitStream};
+use futures::stream::SplitSink;
 use futures::{SinkExt, StreamExt};
 use redis::aio::Connection;
 use redis::aio::PubSub;
-use redis::AsyncCommands;
+use redis::{AsyncCommands, Script};
+use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Postgres};
 use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
 use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
-use todel::models::{InstanceInfo, Secret, ServerPayload, Session, Status, StatusType, User};
+use todel::ids::IdGenerator;
+use todel::models::{
+    GatewayIntent, InstanceInfo, Secret, ServerPayload, Session, Sphere, Status, StatusType, User,
+    VoiceState,
+};
 use todel::Conf;
 use tokio::net::TcpStream;
 use tokio::sync::Mutex;
-use tokio::time::{interval, Instant};
+use tokio::time::{interval, sleep, Instant};
 use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
 use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
 use tokio_tungstenite::tungstenite::protocol::CloseFrame;
@@ -25,6 +33,8 @@ use tokio_tungstenite::{accept_hdr_async, WebSocketStream};
 
 use crate::handle_connection::handle_client::handle_client;
 use crate::handle_connection::handle_pubsub::handle_pubsub;
+use crate::handle_connection::presence::{idle_key, last_active_key, publish_presence};
+use crate::handle_connection::voice::disconnect_voice;
 use crate::rate_limit::RateLimiter;
 
 // /// Some padding to account for network latency.
@@ -37,12 +47,105 @@ const TIMEOUT: Duration = Duration::from_secs(45);
 /// disconnected.
 const TIMEOUT_DURATION: Duration = Duration::from_secs(48); // TIMEOUT_PADDING
 
+/// The close code sent when a client authenticates with unknown or unsupported intents.
+pub(super) const INVALID_INTENTS_CLOSE_CODE: u16 = 4001;
+
+/// Some padding to keep a dropped session's data around for slightly longer than it can be
+/// resumed for.
+const RESUME_PADDING: u64 = 10;
+
+/// Only buffer an event for a dropped session if nobody has resumed it yet.
+///
+/// Returns 0 if the session has already been resumed or has expired.
+const BUFFER_EVENT_SCRIPT: &str = r#"
+if redis.call('EXISTS', KEYS[1]) == 0 then
+    return 0
+end
+redis.call('RPUSH', KEYS[2], ARGV[1])
+redis.call('LTRIM', KEYS[2], -tonumber(ARGV[2]), -1)
+redis.call('EXPIRE', KEYS[2], ARGV[3])
+return 1
+"#;
+
 /// Internal pandemonium specific-struct for stored user session-related data.
 #[derive(Debug, Clone, PartialEq)]
 pub struct SessionData {
+    /// The ID of this gateway session.
+    id: u64,
     session: Session,
     user: User,
     sphere_ids: Vec<u64>,
+    /// The IDs of the group channels the session's user is a member of.
+    group_ids: Vec<u64>,
+    /// The IDs of the direct message channels the session's user is in.
+    direct_ids: Vec<u64>,
+    /// The sphere each channel of the session's spheres belongs to.
+    channel_spheres: HashMap<u64, u64>,
+    /// The intents of this session as a bitfield of [`GatewayIntent`]s.
+    intents: u64,
+    /// The sequence number of the last event dispatched to this session.
+    seq: u64,
+    /// The session's most recently dispatched events, stored in the cache once it drops.
+    replay_buffer: VecDeque<String>,
+    /// Whether the session's client is still connected.
+    connected: bool,
+    /// Whether the session was resumed by another connection after it dropped.
+    resumed: bool,
+    /// The session's voice state if it's connected to a voice channel.
+    voice_state: Option<VoiceState>,
+}
+
+impl SessionData {
+    /// Check whether this session subscribed to an intent.
+    fn has_intent(&self, intent: GatewayIntent) -> bool {
+        intent.is_set(self.intents)
+    }
+
+    /// Check whether a channel is in one of the session's spheres.
+    fn in_sphere_channel(&self, channel_id: u64) -> bool {
+        self.channel_spheres
+            .get(&channel_id)
+            .is_some_and(|sphere_id| self.sphere_ids.contains(sphere_id))
+    }
+}
+
+/// Map the channels of a list of spheres to the sphere they belong to.
+fn channel_spheres(spheres: &[Sphere]) -> HashMap<u64, u64> {
+    spheres
+        .iter()
+        .flat_map(|sphere| {
+            sphere
+                .categories
+                .iter()
+                .flat_map(|category| category.channels.iter())
+                .map(|channel| (channel.get_id(), sphere.id))
+        })
+        .collect()
+}
+
+/// A dispatched event along with its sequence number.
+#[derive(Debug, Serialize)]
+struct SequencedPayload<'a> {
+    #[serde(flatten)]
+    payload: &'a ServerPayload,
+    s: u64,
+}
+
+/// The part of a buffered event needed to replay it.
+#[derive(Debug, Deserialize)]
+pub(super) struct BufferedPayload {
+    pub(super) s: u64,
+}
+
+/// The key of a dropped session which can still be resumed, holding the session's user ID and
+/// intents.
+pub(super) fn resume_key(session_id: u64) -> String {
+    format!("gateway_session:{}", session_id)
+}
+
+/// The key of the list of a session's most recently dispatched events.
+pub(super) fn replay_buffer_key(session_id: u64) -> String {
+    format!("gateway_session:{}:events", session_id)
 }
 
 /// A simple function that check's if a client's last ping was over TIMEOUT_DURATION seconds ago and
@@ -67,6 +170,7 @@ pub async fn handle_connection(
     pool: Arc<Pool<Postgres>>,
     conf: Arc<Conf>,
     secret: Arc<Secret>,
+    id_generator: Arc<Mutex<IdGenerator>>,
 ) {
     let mut rl_address = IpAddr::from_str("127.0.0.1").unwrap();
 
@@ -131,12 +235,23 @@ pub async fn handle_connection(
 
     let session = Arc::new(Mutex::new(None::<SessionData>));
 
+    let pubsub = handle_pubsub(
+        pubsub,
+        Arc::clone(&session),
+        Arc::clone(&cache),
+        Arc::clone(&tx),
+        Arc::clone(&pool),
+        Arc::clone(&conf),
+    );
+    tokio::pin!(pubsub);
+    let mut pubsub_alive = true;
+
     tokio::select! {
         _ = check_connection(last_ping.clone()) => {
             log::debug!("Dead connection with client {}", rl_address);
-            close_socket(tx, rx, CloseFrame { code: CloseCode::Error, reason: Cow::Borrowed("Client connection dead") }, rl_address).await
+            close_socket(&tx, CloseFrame { code: CloseCode::Error, reason: Cow::Borrowed("Client connection dead") }, rl_address).await
         }
-        reason = handle_client(
+        frame = handle_client(
             Arc::clone(&session),
             Arc::clone(&cache),
             &mut rx,
@@ -146,65 +261,148 @@ pub async fn handle_connection(
             rl_address,
             Arc::clone(&pool),
             Arc::clone(&last_ping),
-            Arc::clone(&secret)
+            Arc::clone(&secret),
+            Arc::clone(&id_generator),
+            Arc::clone(&conf),
           ) => {
-            close_socket(tx, rx, CloseFrame { code: CloseCode::Error, reason: Cow::Owned(reason) }, rl_address).await;
+            close_socket(&tx, frame, rl_address).await;
         },
-        _ = handle_pubsub(pubsub, Arc::clone(&session), Arc::clone(&cache), Arc::clone(&tx), pool) => {
-            close_socket(tx, rx, CloseFrame { code: CloseCode::Error, reason: Cow::Borrowed("Server Error") }, rl_address).await;
+        _ = &mut pubsub => {
+            pubsub_alive = false;
+            close_socket(&tx, CloseFrame { code: CloseCode::Error, reason: Cow::Borrowed("Server Error") }, rl_address).await;
         },
     };
 
-    let mut cache = cache.lock().await;
-    let session = session.lock().await;
-    if session.is_some() {
-        let session = session.as_ref().unwrap();
-        let sessions: u32 = match cache.decr(format!("session:{}", session.user.id), 1).await {
-            Ok(sessions) => sessions,
-            Err(err) => {
-                log::error!("Failed to decrement user active session counter: {}", err);
-                return;
-            }
-        };
-        if sessions == 0 {
-            if let Err(err) = cache.srem::<_, _, ()>("sessions", session.user.id).await {
-                log::error!("Failed to remove user from online users: {}", err);
-            }
-            if session.user.status.status_type != StatusType::Offline {
-                if let Err(err) = cache
-                    .publish::<_, _, ()>(
-                        "eludris-events",
-                        serde_json::to_string(&ServerPayload::PresenceUpdate {
-                            user_id: session.user.id,
-                            status: Status {
-                                status_type: StatusType::Offline,
-                                text: None,
-                            },
-                        })
-                        .expect("Couldn't serialize PRESENCE_UPDATE event"),
-                    )
-                    .await
-                {
-                    log::error!("Failed to publish PRESENCE_UPDATE: {}", err);
+    let session_id = match session.lock().await.as_mut() {
+        Some(session) => {
+            session.connected = false;
+            // voice connections don't survive the socket they were made through
+            disconnect_voice(session, &mut *cache.lock().await).await;
+            session.id
+        }
+        None => return,
+    };
+
+    if pubsub_alive {
+        // keep buffering events while the session can still be resumed
+        let resume_timeout = conf.pandemonium.resume_timeout as u64;
+        let (user_id, intents, events) = session
+            .lock()
+            .await
+            .as_mut()
+            .map(|session| {
+                (
+                    session.user.id,
+                    session.intents,
+                    std::mem::take(&mut session.replay_buffer),
+                )
+            })
+            .unwrap();
+        let mut pipe = redis::pipe();
+        pipe.hset_multiple(
+            resume_key(session_id),
+            &[("user_id", user_id), ("intents", intents)],
+        )
+        .ignore()
+        .expire(
+            resume_key(session_id),
+            (resume_timeout + RESUME_PADDING) as usize,
+        )
+        .ignore()
+        .del(replay_buffer_key(session_id))
+        .ignore();
+        if !events.is_empty() {
+            pipe.rpush(replay_buffer_key(session_id), Vec::from(events))
+                .ignore();
+        }
+        let res = pipe
+            .expire(
+                replay_buffer_key(session_id),
+                (resume_timeout + RESUME_PADDING) as usize,
+            )
+            .ignore()
+            .query_async::<_, ()>(&mut *cache.lock().await)
+            .await;
+        match res {
+            Ok(()) => {
+                tokio::select! {
+                    _ = sleep(Duration::from_secs(resume_timeout)) => {},
+                    _ = &mut pubsub => {},
+                }
+                // whoever removes the key owns the session, if it's already gone then the
+                // session was resumed by another connection
+                let expired: u32 = match cache.lock().await.del(resume_key(session_id)).await {
+                    Ok(expired) => expired,
+                    Err(err) => {
+                        log::error!("Failed to remove resumable session: {}", err);
+                        return;
+                    }
                 };
+                if expired == 0 {
+                    return;
+                }
             }
+            Err(err) => log::error!("Failed to store resumable session: {}", err),
+        }
+    }
+
+    let session = session.lock().await;
+    end_session(
+        session_id,
+        &session.as_ref().unwrap().user,
+        &mut *cache.lock().await,
+    )
+    .await;
+}
+
+/// Clean up after a session that can no longer be resumed, marking its user as offline if it
+/// was their last one.
+pub(super) async fn end_session(session_id: u64, user: &User, cache: &mut Connection) {
+    if let Err(err) = cache.del::<_, ()>(replay_buffer_key(session_id)).await {
+        log::error!("Failed to remove session replay buffer: {}", err);
+    }
+    let sessions: u32 = match cache.decr(format!("session:{}", user.id), 1).await {
+        Ok(sessions) => sessions,
+        Err(err) => {
+            log::error!("Failed to decrement user active session counter: {}", err);
+            return;
+        }
+    };
+    if sessions == 0 {
+        if let Err(err) = cache.srem::<_, _, ()>("sessions", user.id).await {
+            log::error!("Failed to remove user from online users: {}", err);
+        }
+        if let Err(err) = cache
+            .del::<_, ()>(&[last_active_key(user.id), idle_key(user.id)])
+            .await
+        {
+            log::error!("Failed to remove user activity: {}", err);
+        }
+        if user.status.status_type != StatusType::Offline {
+            publish_presence(
+                user.id,
+                Status {
+                    status_type: StatusType::Offline,
+                    text: None,
+                },
+                cache,
+            )
+            .await;
         }
     }
 }
 
 async fn close_socket(
-    tx: Arc<Mutex<SplitSink<WebSocketStream<TcpStream>, WebSocketMessage>>>,
-    rx: SplitStream<WebSocketStream<TcpStream>>,
-    frame: CloseFrame<'_>,
+    tx: &Arc<Mutex<SplitSink<WebSocketStream<TcpStream>, WebSocketMessage>>>,
+    frame: CloseFrame<'static>,
     rl_address: IpAddr,
 ) {
-    let tx = Arc::try_unwrap(tx).expect("Couldn't obtain tx from MutexLock");
-    let tx = tx.into_inner();
-
+    // the sink is still shared with the pubsub task which outlives the connection while the
+    // session can be resumed
     if let Err(err) = tx
-        .reunite(rx)
-        .expect("Couldn't reunite WebSocket stream")
-        .close(Some(frame))
+        .lock()
+        .await
+        .send(WebSocketMessage::Close(Some(frame)))
         .await
     {
         log::debug!("Couldn't close socket with {}: {}", rl_address, err);
@@ -226,3 +424,47 @@ async fn send_payload(
         log::error!("Could not send payload: {}", err);
     }
 }
+
+/// Dispatch an event to an authenticated session, numbering it and storing it in the session's
+/// replay buffer.
+///
+/// Events are kept in memory while the session is connected and only stored in the cache once it
+/// drops, until it gets resumed or expires.
+async fn dispatch_event(
+    tx: &Arc<Mutex<SplitSink<WebSocketStream<TcpStream>, WebSocketMessage>>>,
+    session: &mut SessionData,
+    cache: &Arc<Mutex<Connection>>,
+    conf: &Conf,
+    payload: &ServerPayload,
+) {
+    session.seq += 1;
+    let event = serde_json::to_string(&SequencedPayload {
+        payload,
+        s: session.seq,
+    })
+    .unwrap();
+    let buffer_size = conf.pandemonium.replay_buffer_size as usize;
+    if session.connected {
+        if session.replay_buffer.len() >= buffer_size {
+            session.replay_buffer.pop_front();
+        }
+        session.replay_buffer.push_back(event.clone());
+        if let Err(err) = tx.lock().await.send(WebSocketMessage::Text(event)).await {
+            log::error!("Could not send payload: {}", err);
+        }
+    } else {
+        let res = Script::new(BUFFER_EVENT_SCRIPT)
+            .key(resume_key(session.id))
+            .key(replay_buffer_key(session.id))
+            .arg(&event)
+            .arg(buffer_size)
+            .arg(conf.pandemonium.resume_timeout as u64 + RESUME_PADDING)
+            .invoke_async::<_, bool>(&mut *cache.lock().await)
+            .await;
+        match res {
+            Ok(true) => {}
+            Ok(false) => session.resumed = true,
+            Err(err) => log::error!("Failed to buffer event: {}", err),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::time::{Duration, SystemTime};
+
+use redis::aio::Connection;
+use redis::AsyncCommands;
+use todel::models::{ServerPayload, Status, StatusType};
+use todel::Conf;
+
+use super::SessionData;
+
+/// The key holding the last time a user was active on the gateway in seconds since the Unix epoch.
+pub(super) fn last_active_key(user_id: u64) -> String {
+    format!("last_active:{}", user_id)
+}
+
+/// The key holding the actual status of a user who was automatically marked as idle.
+pub(super) fn idle_key(user_id: u64) -> String {
+    format!("idle:{}", user_id)
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs()
+}
+
+/// Publish a `PRESENCE_UPDATE` event for a user.
+pub(super) async fn publish_presence(user_id: u64, status: Status, cache: &mut Connection) {
+    if let Err(err) = cache
+        .publish::<_, _, ()>(
+            "eludris-events",
+            serde_json::to_string(&ServerPayload::PresenceUpdate { user_id, status })
+                .expect("Couldn't serialize PRESENCE_UPDATE event"),
+        )
+        .await
+    {
+        log::error!("Failed to publish PRESENCE_UPDATE: {}", err);
+    }
+}
+
+/// Record gateway activity from a user.
+///
+/// Returns the user's actual status if they were automatically marked as idle.
+pub(super) async fn mark_active(user_id: u64, cache: &mut Connection) -> Option<Status> {
+    if let Err(err) = cache.set::<_, _, ()>(last_active_key(user_id), now()).await {
+        log::error!("Failed to update user activity: {}", err);
+    }
+    let status: Option<String> = match redis::cmd("GETDEL")
+        .arg(idle_key(user_id))
+        .query_async(cache)
+        .await
+    {
+        Ok(status) => status,
+        Err(err) => {
+            log::error!("Failed to get idle user status: {}", err);
+            return None;
+        }
+    };
+    status.and_then(|status| serde_json::from_str(&status).ok())
+}
+
+/// Automatically mark a session's user as idle if they haven't been active for long enough.
+///
+/// This is checked on every heartbeat.
+pub(super) async fn check_idle(session: &SessionData, conf: &Conf, cache: &mut Connection) {
+    if session.user.status.status_type != StatusType::Online {
+        return;
+    }
+    let last_active: Option<u64> = match cache.get(last_active_key(session.user.id)).await {
+        Ok(last_active) => last_active,
+        Err(err) => {
+            log::error!("Failed to get user activity: {}", err);
+            return;
+        }
+    };
+    match last_active {
+        Some(last_active)
+            if now().saturating_sub(last_active) >= conf.pandemonium.idle_timeout as u64 => {}
+        Some(_) => return,
+        None => {
+            mark_active(session.user.id, cache).await;
+            return;
+        }
+    }
+    // only one of the user's sessions gets to mark them as idle
+    let marked: bool = match cache
+        .set_nx(
+            idle_key(session.user.id),
+            serde_json::to_string(&session.user.status).unwrap(),
+        )
+        .await
+    {
+        Ok(marked) => marked,
+        Err(err) => {
+            log::error!("Failed to mark user as idle: {}", err);
+            return;
+        }
+    };
+    if marked {
+        publish_presence(
+            session.user.id,
+            Status {
+                status_type: StatusType::Idle,
+                text: session.user.status.text.clone(),
+            },
+            cache,
+        )
+        .await;
+    }
+}
@@ -0,0 +1,140 @@
+use redis::aio::Connection;
+use redis::AsyncCommands;
+use todel::models::{ServerPayload, Sphere, SphereChannel, VoiceState};
+
+use super::SessionData;
+
+/// The key holding a user's current voice state.
+fn voice_state_key(user_id: u64) -> String {
+    format!("voice_state:{}", user_id)
+}
+
+/// The key of the hash holding the voice states of everyone connected to a voice channel, keyed
+/// by their user IDs.
+fn voice_channel_key(channel_id: u64) -> String {
+    format!("voice_channel:{}", channel_id)
+}
+
+/// Publish a `VOICE_STATE_UPDATE` event.
+pub(super) async fn publish_voice_state(state: &VoiceState, cache: &mut Connection) {
+    if let Err(err) = cache
+        .publish::<_, _, ()>(
+            "eludris-events",
+            serde_json::to_string(&ServerPayload::VoiceStateUpdate(state.clone()))
+                .expect("Couldn't serialize VOICE_STATE_UPDATE event"),
+        )
+        .await
+    {
+        log::error!("Failed to publish VOICE_STATE_UPDATE: {}", err);
+    }
+}
+
+/// Get the states of everyone connected to a voice channel.
+pub(super) async fn get_channel_voice_states(
+    channel_id: u64,
+    cache: &mut Connection,
+) -> Vec<VoiceState> {
+    match cache
+        .hvals::<_, Vec<String>>(voice_channel_key(channel_id))
+        .await
+    {
+        Ok(states) => states
+            .iter()
+            .filter_map(|state| serde_json::from_str(state).ok())
+            .collect(),
+        Err(err) => {
+            log::error!("Failed to get voice channel states: {}", err);
+            vec![]
+        }
+    }
+}
+
+/// Get the states of everyone connected to the voice channels of a list of spheres.
+pub(super) async fn get_voice_states(
+    spheres: &[Sphere],
+    cache: &mut Connection,
+) -> Vec<VoiceState> {
+    let mut states = vec![];
+    for sphere in spheres {
+        for category in sphere.categories.iter() {
+            for channel in category.channels.iter() {
+                if let SphereChannel::Voice(channel) = channel {
+                    states.extend(get_channel_voice_states(channel.id, cache).await);
+                }
+            }
+        }
+    }
+    states
+}
+
+/// Store a user's new voice state, replacing the one they had before.
+///
+/// Returns the user's previous voice state.
+pub(super) async fn set_voice_state(
+    state: &VoiceState,
+    cache: &mut Connection,
+) -> Result<Option<VoiceState>, ()> {
+    let channel_id = state.channel_id.ok_or(())?;
+    let previous: Option<VoiceState> = match cache
+        .get::<_, Option<String>>(voice_state_key(state.user_id))
+        .await
+    {
+        Ok(previous) => previous.and_then(|previous| serde_json::from_str(&previous).ok()),
+        Err(err) => {
+            log::error!("Failed to get previous voice state: {}", err);
+            return Err(());
+        }
+    };
+    let data = serde_json::to_string(state).unwrap();
+    let mut pipe = redis::pipe();
+    pipe.atomic();
+    if let Some(previous_channel_id) = previous.as_ref().and_then(|p| p.channel_id) {
+        if previous_channel_id != channel_id {
+            pipe.hdel(voice_channel_key(previous_channel_id), state.user_id)
+                .ignore();
+        }
+    }
+    pipe.set(voice_state_key(state.user_id), &data)
+        .ignore()
+        .hset(voice_channel_key(channel_id), state.user_id, &data)
+        .ignore();
+    if let Err(err) = pipe.query_async::<_, ()>(cache).await {
+        log::error!("Failed to store voice state: {}", err);
+        return Err(());
+    }
+    Ok(previous)
+}
+
+/// Disconnect a session from voice if it's the one its user is connected from, broadcasting
+/// it to the voice channel's sphere.
+pub(super) async fn disconnect_voice(session: &mut SessionData, cache: &mut Connection) {
+    if session.voice_state.take().is_none() {
+        return;
+    }
+    let state: Option<VoiceState> = match cache
+        .get::<_, Option<String>>(voice_state_key(session.user.id))
+        .await
+    {
+        Ok(state) => state.and_then(|state| serde_json::from_str(&state).ok()),
+        Err(err) => {
+            log::error!("Failed to get voice state: {}", err);
+            return;
+        }
+    };
+    let mut state = match state {
+        Some(state) if state.session_id == session.id => state,
+        _ => return,
+    };
+    let mut pipe = redis::pipe();
+    pipe.atomic().del(voice_state_key(session.user.id)).ignore();
+    if let Some(channel_id) = state.channel_id {
+        pipe.hdel(voice_channel_key(channel_id), session.user.id)
+            .ignore();
+    }
+    if let Err(err) = pipe.query_async::<_, ()>(cache).await {
+        log::error!("Failed to remove voice state: {}", err);
+        return;
+    }
+    state.channel_id = None;
+    publish_voice_state(&state, cache).await;
+}
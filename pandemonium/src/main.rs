@@ -9,7 +9,7 @@ use std::{env, sync::Arc};
 use anyhow::Context;
 use redis::AsyncCommands;
 use sqlx::{pool::PoolOptions, Pool, Postgres};
-use todel::{models::Secret, Conf};
+use todel::{ids::IdGenerator, models::Secret, Conf};
 use tokio::{net::TcpListener, sync::Mutex, task};
 
 #[cfg(test)]
@@ -53,15 +53,18 @@ async fn main() -> Result<(), anyhow::Error> {
             .del::<_, ()>("sessions")
             .await
             .context("Couldn't remove the sessions key")?; // wei wei wei wei
-        let keys: Vec<String> = cache
-            .keys("session:*")
-            .await
-            .context("Couldn't list session keys")?;
-        for key in keys {
-            cache
-                .del::<_, ()>(&key)
+        for pattern in ["session:*", "last_active:*", "idle:*"] {
+            let keys: Vec<String> = cache
+                .keys(pattern)
                 .await
-                .with_context(|| format!("Couldn't remove the {} key", key))?; // wei wei wei wei
+                .with_context(|| format!("Couldn't list {} keys", pattern))?;
+            for key in keys {
+                cache
+                    .del::<_, ()>(&key)
+                    .await
+                    .with_context(|| format!("Couldn't remove the {} key", key))?;
+                // wei wei wei wei
+            }
         }
     }
 
@@ -78,6 +81,7 @@ async fn main() -> Result<(), anyhow::Error> {
     )?);
 
     let conf = Arc::new(Conf::new_from_env()?);
+    let id_generator = Arc::new(Mutex::new(IdGenerator::new()));
 
     let socket = TcpListener::bind(&gateway_address)
         .await
@@ -106,6 +110,7 @@ async fn main() -> Result<(), anyhow::Error> {
             Arc::clone(&pool),
             Arc::clone(&conf),
             Arc::clone(&secret),
+            Arc::clone(&id_generator),
         ));
         log::debug!("Spawned connection handling task for {}", addr);
     }
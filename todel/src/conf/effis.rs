@@ -18,6 +18,14 @@ pub struct EffisConf {
     pub proxy_file_size: u64,
     #[serde(default)]
     pub rate_limits: EffisRateLimits,
+    #[serde(default)]
+    pub storage: StorageConf,
+    #[serde(default = "thumbnail_sizes_default")]
+    pub thumbnail_sizes: Vec<u32>,
+    /// Whether to remove EXIF, XMP, IPTC and container metadata from uploaded images and
+    /// videos, disabling this keeps uploads as they were sent.
+    #[serde(default = "strip_metadata_default")]
+    pub strip_metadata: bool,
 }
 
 impl Default for EffisConf {
@@ -28,6 +36,9 @@ impl Default for EffisConf {
             attachment_file_size: attachment_file_size_default(),
             proxy_file_size: proxy_file_size_default(),
             rate_limits: EffisRateLimits::default(),
+            storage: StorageConf::default(),
+            thumbnail_sizes: thumbnail_sizes_default(),
+            strip_metadata: strip_metadata_default(),
         }
     }
 }
@@ -44,6 +55,73 @@ fn proxy_file_size_default() -> u64 {
     50_000_000 // 100MB
 }
 
+fn thumbnail_sizes_default() -> Vec<u32> {
+    vec![32, 64, 128, 256, 512]
+}
+
+fn strip_metadata_default() -> bool {
+    true
+}
+
+/// Where Effis stores its files.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "backend", rename_all = "lowercase")]
+pub enum StorageConf {
+    /// Store files in a directory on the local disk.
+    Local {
+        #[serde(default = "local_path_default")]
+        path: String,
+    },
+    /// Store files in an S3 compatible object store, like AWS S3 or MinIO.
+    S3 {
+        endpoint: String,
+        bucket: String,
+        #[serde(default = "region_default")]
+        region: String,
+        access_key: String,
+        secret_key: String,
+        /// Whether to address the bucket as `{endpoint}/{bucket}` instead of
+        /// `{bucket}.{endpoint}`, most self-hosted object stores need this.
+        #[serde(default = "path_style_default")]
+        path_style: bool,
+        /// How many seconds to wait for a connection to the object store before giving up.
+        #[serde(default = "connect_timeout_default")]
+        connect_timeout: u64,
+        /// How many seconds a request to the object store can take in total, including
+        /// transferring the object.
+        #[serde(default = "timeout_default")]
+        timeout: u64,
+    },
+}
+
+impl Default for StorageConf {
+    fn default() -> Self {
+        Self::Local {
+            path: local_path_default(),
+        }
+    }
+}
+
+fn local_path_default() -> String {
+    "files".to_string()
+}
+
+fn region_default() -> String {
+    "us-east-1".to_string()
+}
+
+fn path_style_default() -> bool {
+    true
+}
+
+fn connect_timeout_default() -> u64 {
+    10
+}
+
+fn timeout_default() -> u64 {
+    300
+}
+
 /// Rate limits that apply to Effis (The CDN).
 ///
 /// -----
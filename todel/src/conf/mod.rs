@@ -141,7 +141,10 @@ impl Conf {
             bail!("Message limit can not be less than 1024 characters");
         }
         validate_rate_limit_limits!(self.oprish.rate_limits, get_instance_info, create_message);
-        validate_rate_limit_limits!(self.pandemonium, rate_limit);
+        validate_rate_limit_limits!(self.pandemonium, rate_limit, presence_rate_limit);
+        if self.pandemonium.replay_buffer_size == 0 {
+            bail!("Replay buffer size can't be 0");
+        }
         validate_rate_limit_limits!(self.effis.rate_limits, assets, attachments, fetch_file);
 
         Url::parse(&self.oprish.url)
@@ -150,6 +153,23 @@ impl Conf {
             .with_context(|| format!("Invalid pandemonium url {}", self.pandemonium.url))?;
         Url::parse(&self.effis.url)
             .with_context(|| format!("Invalid effis url {}", self.effis.url))?;
+        if let StorageConf::S3 {
+            endpoint,
+            bucket,
+            connect_timeout,
+            timeout,
+            ..
+        } = &self.effis.storage
+        {
+            Url::parse(endpoint)
+                .with_context(|| format!("Invalid effis storage endpoint {}", endpoint))?;
+            if bucket.is_empty() {
+                bail!("Invalid effis storage bucket");
+            }
+            if *connect_timeout == 0 || *timeout == 0 {
+                bail!("Effis storage timeouts can't be 0");
+            }
+        }
 
         if let Some(email) = &self.email {
             if email.relay.is_empty() {
@@ -163,6 +183,15 @@ impl Conf {
             }
         }
 
+        if self
+            .effis
+            .thumbnail_sizes
+            .iter()
+            .any(|size| *size == 0 || *size > 4096)
+        {
+            bail!("Thumbnail sizes must be between 1 and 4096 pixels");
+        }
+
         validate_file_sizes!(
             self.effis.file_size,
             self.effis.attachment_file_size,
@@ -241,6 +270,7 @@ mod tests {
                     limit: 10,
                 },
                 url: "wss://foo.bar".to_string(),
+                ..Default::default()
             },
             effis: EffisConf {
                 file_size: 100_000_000,
@@ -337,6 +367,7 @@ mod tests {
         test_limit!(
             conf,
             conf.pandemonium.rate_limit,
+            conf.pandemonium.presence_rate_limit,
             conf.effis.rate_limits.assets,
             conf.effis.rate_limits.attachments,
             conf.effis.rate_limits.fetch_file,
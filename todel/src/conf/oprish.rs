@@ -10,6 +10,12 @@ pub struct OprishConf {
     pub message_limit: usize,
     #[serde(default = "bio_limit_default")]
     pub bio_limit: usize,
+    /// How many seconds a session can go unused before it expires, 0 disables this.
+    #[serde(default = "session_idle_timeout_default")]
+    pub session_idle_timeout: u64,
+    /// How many seconds a session can last for regardless of its use, 0 disables this.
+    #[serde(default = "session_max_lifetime_default")]
+    pub session_max_lifetime: u64,
     #[serde(default)]
     pub rate_limits: OprishRateLimits,
 }
@@ -20,6 +26,8 @@ impl Default for OprishConf {
             url: "https://example.com".to_string(),
             message_limit: message_limit_default(),
             bio_limit: bio_limit_default(),
+            session_idle_timeout: session_idle_timeout_default(),
+            session_max_lifetime: session_max_lifetime_default(),
             rate_limits: OprishRateLimits::default(),
         }
     }
@@ -33,6 +41,14 @@ fn bio_limit_default() -> usize {
     250
 }
 
+fn session_idle_timeout_default() -> u64 {
+    2_592_000 // 30 days
+}
+
+fn session_max_lifetime_default() -> u64 {
+    31_536_000 // 365 days
+}
+
 macro_rules! oprish_ratelimits {
     ($($bucket:ident => ($bucket_str:literal, $reset_after:literal, $limit:literal)),+$(,)?) => {
         /// Rate limits that apply to Oprish (The REST API).
@@ -99,9 +115,20 @@ oprish_ratelimits!(
     delete_user => ("delete_user", 30, 1),
     create_password_reset_code => ("create_password_reset_code", 60, 3),
     reset_password => ("reset_password", 60, 3),
+    setup_two_factor => ("setup_two_factor", 60, 3),
+    confirm_two_factor => ("confirm_two_factor", 60, 5),
+    disable_two_factor => ("disable_two_factor", 60, 3),
     create_session => ("create_session", 60, 3),
     get_sessions => ("get_sessions", 5, 10),
     delete_session => ("delete_session", 5, 10),
+    create_application => ("create_application", 60, 3),
+    get_applications => ("get_applications", 5, 10),
+    rotate_application_token => ("rotate_application_token", 60, 3),
+    edit_application => ("edit_application", 10, 5),
+    delete_application => ("delete_application", 30, 3),
+    get_oauth_authorization => ("get_oauth_authorization", 5, 10),
+    authorize_application => ("authorize_application", 10, 5),
+    create_oauth_token => ("create_oauth_token", 10, 10),
     resend_verification => ("resend_verification", 60, 3),
     create_sphere => ("create_sphere", 20, 3),
     get_sphere => ("get_sphere", 5, 10),
@@ -132,4 +159,38 @@ oprish_ratelimits!(
     add_reaction => ("add_reaction", 5, 10),
     remove_reaction => ("remove_reaction", 5, 10),
     clear_reactions => ("clear_reactions", 5, 10),
+    create_role => ("create_role", 10, 5),
+    get_roles => ("get_roles", 5, 10),
+    edit_role => ("edit_role", 10, 5),
+    delete_role => ("delete_role", 10, 5),
+    get_overwrites => ("get_overwrites", 5, 10),
+    set_overwrite => ("set_overwrite", 10, 5),
+    delete_overwrite => ("delete_overwrite", 10, 5),
+    ban_member => ("ban_member", 10, 5),
+    unban_member => ("unban_member", 10, 5),
+    get_bans => ("get_bans", 5, 10),
+    timeout_member => ("timeout_member", 10, 5),
+    get_audit_log => ("get_audit_log", 5, 10),
+    ack_message => ("ack_message", 5, 20),
+    open_direct_message => ("open_direct_message", 10, 5),
+    create_group => ("create_group", 20, 3),
+    edit_group => ("edit_group", 10, 5),
+    add_group_member => ("add_group_member", 10, 5),
+    remove_group_member => ("remove_group_member", 10, 5),
+    create_post => ("create_post", 10, 5),
+    get_posts => ("get_posts", 5, 10),
+    get_post => ("get_post", 5, 10),
+    edit_post => ("edit_post", 5, 10),
+    delete_post => ("delete_post", 5, 10),
+    vote_post => ("vote_post", 5, 20),
+    remove_post_vote => ("remove_post_vote", 5, 20),
+    create_invite => ("create_invite", 10, 5),
+    get_invites => ("get_invites", 5, 10),
+    delete_invite => ("delete_invite", 5, 10),
+    use_invite => ("use_invite", 5, 20),
+    create_webhook => ("create_webhook", 10, 5),
+    get_webhooks => ("get_webhooks", 5, 10),
+    edit_webhook => ("edit_webhook", 5, 10),
+    delete_webhook => ("delete_webhook", 5, 10),
+    execute_webhook => ("execute_webhook", 5, 10),
 );
@@ -8,6 +8,19 @@ pub struct PandemoniumConf {
     pub url: String,
     #[serde(default = "pandemonium_rate_limit_default")]
     pub rate_limit: RateLimitConf,
+    /// How many seconds a dropped session can be resumed for.
+    #[serde(default = "resume_timeout_default")]
+    pub resume_timeout: u32,
+    /// How many of a session's most recent events are kept to be replayed when it's resumed.
+    #[serde(default = "replay_buffer_size_default")]
+    pub replay_buffer_size: u32,
+    /// The rate limit of presence updates, shared between all of a user's sessions.
+    #[serde(default = "presence_rate_limit_default")]
+    pub presence_rate_limit: RateLimitConf,
+    /// How many seconds a user can go without any gateway activity before they're automatically
+    /// marked as idle.
+    #[serde(default = "idle_timeout_default")]
+    pub idle_timeout: u32,
 }
 
 impl Default for PandemoniumConf {
@@ -15,6 +28,10 @@ impl Default for PandemoniumConf {
         Self {
             url: "https://example.com".to_string(),
             rate_limit: pandemonium_rate_limit_default(),
+            resume_timeout: resume_timeout_default(),
+            replay_buffer_size: replay_buffer_size_default(),
+            presence_rate_limit: presence_rate_limit_default(),
+            idle_timeout: idle_timeout_default(),
         }
     }
 }
@@ -25,3 +42,22 @@ fn pandemonium_rate_limit_default() -> RateLimitConf {
         limit: 5,
     }
 }
+
+fn resume_timeout_default() -> u32 {
+    60
+}
+
+fn replay_buffer_size_default() -> u32 {
+    256
+}
+
+fn presence_rate_limit_default() -> RateLimitConf {
+    RateLimitConf {
+        reset_after: 60,
+        limit: 5,
+    }
+}
+
+fn idle_timeout_default() -> u32 {
+    600
+}
@@ -0,0 +1,41 @@
+use std::convert::Infallible;
+
+use rocket::{
+    async_trait,
+    form::{self, FromFormField, ValueField},
+    request::{FromRequest, Outcome, Request},
+};
+use serde::{
+    de::{value::StrDeserializer, IntoDeserializer},
+    Deserialize,
+};
+
+use crate::models::AuditLogAction;
+
+/// The reason for a change, taken from the optional `X-Audit-Log-Reason` header and recorded
+/// in the sphere's audit log.
+#[derive(Debug, Clone)]
+pub struct AuditLogReason(pub Option<String>);
+
+#[async_trait]
+impl<'r> FromRequest<'r> for AuditLogReason {
+    type Error = Infallible;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(Self(
+            req.headers()
+                .get_one("X-Audit-Log-Reason")
+                .map(|reason| reason.to_string()),
+        ))
+    }
+}
+
+#[async_trait]
+impl<'v> FromFormField<'v> for AuditLogAction {
+    fn from_value(field: ValueField<'v>) -> form::Result<'v, Self> {
+        let deserializer: StrDeserializer<'_, serde::de::value::Error> =
+            field.value.into_deserializer();
+        Self::deserialize(deserializer)
+            .map_err(|_| form::Error::validation("Unknown audit log action").into())
+    }
+}
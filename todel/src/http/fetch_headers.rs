@@ -0,0 +1,230 @@
+use std::{
+    convert::Infallible,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use chrono::{DateTime, Utc};
+use rocket::{
+    async_trait,
+    request::{FromRequest, Outcome, Request},
+};
+
+/// A byte range requested with a `Range` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteRange {
+    /// `bytes=start-end`, `end` being inclusive and `None` meaning the end of the file.
+    FromStart { start: u64, end: Option<u64> },
+    /// `bytes=-length`, the last `length` bytes of the file.
+    Suffix { length: u64 },
+}
+
+/// The headers a client sends to fetch part of a file or to revalidate a cached copy of it.
+#[derive(Debug, Clone, Default)]
+pub struct FetchHeaders {
+    pub range: Option<String>,
+    pub if_range: Option<String>,
+    pub if_none_match: Option<String>,
+    pub if_modified_since: Option<String>,
+}
+
+#[async_trait]
+impl<'r> FromRequest<'r> for FetchHeaders {
+    type Error = Infallible;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let header = |name| req.headers().get_one(name).map(|v| v.to_string());
+        Outcome::Success(Self {
+            range: header("Range"),
+            if_range: header("If-Range"),
+            if_none_match: header("If-None-Match"),
+            if_modified_since: header("If-Modified-Since"),
+        })
+    }
+}
+
+impl FetchHeaders {
+    /// Check whether the client's cached copy of a file with these validators is still fresh.
+    pub fn is_fresh(&self, etag: Option<&str>, last_modified: Option<SystemTime>) -> bool {
+        // If-Modified-Since is only used when If-None-Match isn't sent
+        if let Some(if_none_match) = &self.if_none_match {
+            return etag
+                .map(|etag| {
+                    if_none_match.trim() == "*"
+                        || if_none_match
+                            .split(',')
+                            .any(|tag| weak_tag(tag.trim()) == weak_tag(etag))
+                })
+                .unwrap_or(false);
+        }
+        match (&self.if_modified_since, last_modified) {
+            (Some(since), Some(last_modified)) => parse_http_date(since)
+                .map(|since| truncate_to_secs(last_modified) <= since)
+                .unwrap_or(false),
+            _ => false,
+        }
+    }
+
+    /// Get the byte range the client asked for.
+    ///
+    /// This is `None` when no range was requested, when the range can't be understood or when
+    /// the file changed since the client's `If-Range`, all of which mean that the whole file
+    /// should be sent. Requests for multiple ranges are not supported and are treated the same
+    /// way.
+    pub fn byte_range(
+        &self,
+        etag: Option<&str>,
+        last_modified: Option<SystemTime>,
+    ) -> Option<ByteRange> {
+        let range = self.range.as_ref()?.trim().strip_prefix("bytes=")?;
+        if let Some(if_range) = &self.if_range {
+            let if_range = if_range.trim();
+            let unchanged = if if_range.starts_with("W/") {
+                // Weak validators can't be used with If-Range
+                false
+            } else if if_range.starts_with('"') {
+                etag.map(|etag| etag == if_range).unwrap_or(false)
+            } else {
+                match (parse_http_date(if_range), last_modified) {
+                    (Some(date), Some(last_modified)) => truncate_to_secs(last_modified) == date,
+                    _ => false,
+                }
+            };
+            if !unchanged {
+                return None;
+            }
+        }
+        if range.contains(',') {
+            return None;
+        }
+        let (start, end) = range.split_once('-')?;
+        let (start, end) = (start.trim(), end.trim());
+        if start.is_empty() {
+            let length = end.parse().ok()?;
+            if length == 0 {
+                return None;
+            }
+            return Some(ByteRange::Suffix { length });
+        }
+        let start = start.parse().ok()?;
+        let end = match end {
+            "" => None,
+            end => Some(end.parse().ok()?),
+        };
+        if end.map(|end| end < start).unwrap_or(false) {
+            return None;
+        }
+        Some(ByteRange::FromStart { start, end })
+    }
+}
+
+/// Format a time as an HTTP date, like `Sun, 06 Nov 1994 08:49:37 GMT`.
+pub fn format_http_date(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time)
+        .format("%a, %d %b %Y %H:%M:%S GMT")
+        .to_string()
+}
+
+fn parse_http_date(date: &str) -> Option<SystemTime> {
+    DateTime::parse_from_rfc2822(date.trim())
+        .ok()
+        .map(|date| UNIX_EPOCH + Duration::from_secs(date.timestamp().max(0) as u64))
+}
+
+/// HTTP dates only have a precision of seconds.
+fn truncate_to_secs(time: SystemTime) -> SystemTime {
+    UNIX_EPOCH
+        + Duration::from_secs(
+            time.duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        )
+}
+
+fn weak_tag(tag: &str) -> &str {
+    tag.strip_prefix("W/").unwrap_or(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(range: &str) -> Option<ByteRange> {
+        FetchHeaders {
+            range: Some(range.to_string()),
+            ..Default::default()
+        }
+        .byte_range(None, None)
+    }
+
+    #[test]
+    fn byte_ranges() {
+        assert_eq!(
+            range("bytes=0-499"),
+            Some(ByteRange::FromStart {
+                start: 0,
+                end: Some(499)
+            })
+        );
+        assert_eq!(
+            range("bytes=9500-"),
+            Some(ByteRange::FromStart {
+                start: 9500,
+                end: None
+            })
+        );
+        assert_eq!(range("bytes=-500"), Some(ByteRange::Suffix { length: 500 }));
+        assert_eq!(range("bytes=500-400"), None);
+        assert_eq!(range("bytes=0-1,5-6"), None);
+        assert_eq!(range("lines=0-5"), None);
+        assert_eq!(range("bytes=-0"), None);
+    }
+
+    #[test]
+    fn if_range() {
+        let last_modified = UNIX_EPOCH + Duration::from_secs(784111777);
+        let headers = |if_range: &str| FetchHeaders {
+            range: Some("bytes=0-".to_string()),
+            if_range: Some(if_range.to_string()),
+            ..Default::default()
+        };
+        let matches = |if_range| {
+            headers(if_range)
+                .byte_range(Some("\"abc\""), Some(last_modified))
+                .is_some()
+        };
+        assert!(matches("\"abc\""));
+        assert!(!matches("\"abd\""));
+        assert!(!matches("W/\"abc\""));
+        assert!(matches("Sun, 06 Nov 1994 08:49:37 GMT"));
+        assert!(!matches("Sun, 06 Nov 1994 08:49:38 GMT"));
+    }
+
+    #[test]
+    fn freshness() {
+        let last_modified = UNIX_EPOCH + Duration::from_secs(784111777);
+        let if_none_match = |tag: &str| FetchHeaders {
+            if_none_match: Some(tag.to_string()),
+            if_modified_since: Some("Sun, 06 Nov 1994 08:49:37 GMT".to_string()),
+            ..Default::default()
+        };
+        assert!(if_none_match("\"abc\"").is_fresh(Some("\"abc\""), Some(last_modified)));
+        assert!(if_none_match("\"xyz\", W/\"abc\"").is_fresh(Some("\"abc\""), None));
+        assert!(if_none_match("*").is_fresh(Some("\"abc\""), None));
+        assert!(!if_none_match("\"xyz\"").is_fresh(Some("\"abc\""), Some(last_modified)));
+
+        let if_modified_since = |date: &str| FetchHeaders {
+            if_modified_since: Some(date.to_string()),
+            ..Default::default()
+        };
+        assert!(
+            if_modified_since("Sun, 06 Nov 1994 08:49:37 GMT").is_fresh(None, Some(last_modified))
+        );
+        assert!(
+            !if_modified_since("Sun, 06 Nov 1994 08:49:36 GMT").is_fresh(None, Some(last_modified))
+        );
+        assert_eq!(
+            format_http_date(last_modified),
+            "Sun, 06 Nov 1994 08:49:37 GMT"
+        );
+    }
+}
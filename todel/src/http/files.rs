@@ -0,0 +1,20 @@
+use rocket::{
+    async_trait,
+    form::{self, FromFormField, ValueField},
+};
+use serde::{
+    de::{value::StrDeserializer, IntoDeserializer},
+    Deserialize,
+};
+
+use crate::models::ThumbnailFormat;
+
+#[async_trait]
+impl<'v> FromFormField<'v> for ThumbnailFormat {
+    fn from_value(field: ValueField<'v>) -> form::Result<'v, Self> {
+        let deserializer: StrDeserializer<'_, serde::de::value::Error> =
+            field.value.into_deserializer();
+        Self::deserialize(deserializer)
+            .map_err(|_| form::Error::validation("Unknown thumbnail format").into())
+    }
+}
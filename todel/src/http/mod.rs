@@ -1,10 +1,16 @@
+mod audit_log;
 mod client_ip;
 mod databases;
+mod fetch_headers;
+mod files;
 mod identifiers;
+mod posts;
 mod response;
 mod token_auth;
 
+pub use audit_log::AuditLogReason;
 pub use client_ip::ClientIP;
 pub use databases::*;
+pub use fetch_headers::*;
 pub use identifiers::*;
 pub use token_auth::*;
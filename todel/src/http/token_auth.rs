@@ -1,5 +1,5 @@
 use rocket::{
-    http::Status,
+    http::{Method, Status},
     request::{FromRequest, Outcome},
     Request,
 };
@@ -7,14 +7,35 @@ use rocket_db_pools::Database;
 
 use crate::{
     error,
-    models::{ErrorResponse, Secret, Session},
+    models::{ErrorResponse, OAuthScope, Secret, Session},
+    Conf,
 };
 
-use super::DB;
+use super::{ClientIP, DB};
 
 #[derive(Clone, Debug)]
 pub struct TokenAuth(pub Session);
 
+/// Get the scope an OAuth2 token needs to access a route.
+///
+/// Routes which aren't covered by any scope, like managing sessions or applications, return
+/// `None` and can only be accessed with unscoped tokens.
+fn required_scope(method: Method, path: &str) -> Option<OAuthScope> {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let read = method == Method::Get;
+    match segments.as_slice() {
+        ["users", _, "dm"] => Some(OAuthScope::MessagesWrite),
+        ["users", ..] if read => Some(OAuthScope::Identify),
+        ["spheres", ..] | ["emojis", ..] if read => Some(OAuthScope::SpheresRead),
+        ["spheres", ..] | ["emojis", ..] | ["invites", ..] => Some(OAuthScope::SpheresWrite),
+        ["channels", _, "messages" | "posts", ..] if read => Some(OAuthScope::MessagesRead),
+        ["channels", _, "messages" | "posts", ..] => Some(OAuthScope::MessagesWrite),
+        ["channels", ..] if read => Some(OAuthScope::SpheresRead),
+        ["channels", ..] => Some(OAuthScope::SpheresWrite),
+        _ => None,
+    }
+}
+
 #[rocket::async_trait]
 impl<'r> FromRequest<'r> for TokenAuth {
     type Error = ErrorResponse;
@@ -30,12 +51,64 @@ impl<'r> FromRequest<'r> for TokenAuth {
             .rocket()
             .state::<Secret>()
             .expect("Could not obtain the managed Secret");
+        let conf = request
+            .rocket()
+            .state::<Conf>()
+            .expect("Could not obtain the managed Conf");
+        let ip = *request
+            .guard::<ClientIP>()
+            .await
+            .expect("Could not get the client's IP");
         match request.headers().get_one("Authorization") {
-            Some(token) => match Session::validate_token(token, secret, &mut db).await {
-                Ok(session) => Outcome::Success(Self(session)),
-                Err(err) => Outcome::Error((Status::Unauthorized, err)),
-            },
+            Some(token) => {
+                // OAuth2 clients send their access tokens as bearer tokens.
+                let token = token.strip_prefix("Bearer ").unwrap_or(token);
+                match Session::validate_token(token, ip, secret, conf, &mut db).await {
+                    Ok(session) => {
+                        if let Some(scopes) = session.scopes {
+                            match required_scope(request.method(), request.uri().path().as_str()) {
+                                Some(scope) if scope.is_set(scopes) => {}
+                                _ => return Outcome::Error((Status::Forbidden, error!(FORBIDDEN))),
+                            }
+                        }
+                        Outcome::Success(Self(session))
+                    }
+                    Err(err) => Outcome::Error((Status::Unauthorized, err)),
+                }
+            }
             None => Outcome::Error((Status::Unauthorized, error!(UNAUTHORIZED))),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rocket::http::Method;
+
+    use super::required_scope;
+    use crate::models::OAuthScope;
+
+    #[test]
+    fn scopes() {
+        assert_eq!(
+            required_scope(Method::Get, "/users/@me"),
+            Some(OAuthScope::Identify)
+        );
+        assert_eq!(required_scope(Method::Patch, "/users"), None);
+        assert_eq!(
+            required_scope(Method::Get, "/channels/1/messages"),
+            Some(OAuthScope::MessagesRead)
+        );
+        assert_eq!(
+            required_scope(Method::Put, "/channels/1/posts/2/vote"),
+            Some(OAuthScope::MessagesWrite)
+        );
+        assert_eq!(
+            required_scope(Method::Post, "/spheres/1/join"),
+            Some(OAuthScope::SpheresWrite)
+        );
+        assert_eq!(required_scope(Method::Get, "/sessions"), None);
+        assert_eq!(required_scope(Method::Post, "/applications"), None);
+        assert_eq!(required_scope(Method::Post, "/oauth2/authorize"), None);
+    }
+}
@@ -12,6 +12,8 @@ pub mod http;
 #[cfg(feature = "logic")]
 pub mod ids;
 pub mod models;
+#[cfg(feature = "http")]
+pub mod storage;
 
 pub use conf::Conf;
 
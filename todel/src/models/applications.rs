@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+
+use super::User;
+
+/// The Application payload. Applications let users run bots, every application having its own
+/// bot user which shares its ID.
+///
+/// Bots can't log in with a password, they instead use tokens issued through their application.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "id": 48615849987334,
+///   "owner_id": 48615849987333,
+///   "redirect_uris": ["https://pengin.example/callback"],
+///   "bot": {
+///     "id": 48615849987334,
+///     "username": "pengin-bot",
+///     "social_credit": 0,
+///     "status": {
+///       "type": "ONLINE"
+///     },
+///     "badges": 1,
+///     "permissions": 0,
+///     "verified": true
+///   }
+/// }
+/// ```
+#[autodoc(category = "Applications")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Application {
+    /// The application's ID, this is the same as its bot's ID.
+    pub id: u64,
+    /// The ID of the user who owns this application.
+    pub owner_id: u64,
+    /// The URIs users can be redirected to after authorizing this application through OAuth2.
+    pub redirect_uris: Vec<String>,
+    /// The application's bot user.
+    pub bot: User,
+}
+
+/// The ApplicationCreate payload.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "username": "pengin-bot",
+///   "display_name": "Pengin Bot",
+///   "redirect_uris": ["https://pengin.example/callback"]
+/// }
+/// ```
+#[autodoc(category = "Applications", hidden = true)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApplicationCreate {
+    /// The bot's username. This has to be unique across all users.
+    pub username: String,
+    /// The bot's display name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    /// The application's OAuth2 redirect URIs, there can be up to 10 of them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect_uris: Option<Vec<String>>,
+}
+
+/// The ApplicationEdit payload.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "redirect_uris": ["https://pengin.example/callback", "http://localhost:8080/callback"]
+/// }
+/// ```
+#[autodoc(category = "Applications", hidden = true)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApplicationEdit {
+    /// The application's new OAuth2 redirect URIs, there can be up to 10 of them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect_uris: Option<Vec<String>>,
+}
+
+/// The response to an [`ApplicationCreate`].
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "token": "",
+///   "application": {
+///     "id": 48615849987334,
+///     "owner_id": 48615849987333,
+///     "redirect_uris": [],
+///     "bot": {
+///       "id": 48615849987334,
+///       "username": "pengin-bot",
+///       "social_credit": 0,
+///       "status": {
+///         "type": "ONLINE"
+///       },
+///       "badges": 1,
+///       "permissions": 0,
+///       "verified": true
+///     }
+///   }
+/// }
+/// ```
+#[autodoc(category = "Applications")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApplicationCreated {
+    /// The bot's token. This will only be shown once and can be rotated later on.
+    pub token: String,
+    /// The created application.
+    pub application: Application,
+}
+
+/// The response to rotating an application's bot token.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "token": ""
+/// }
+/// ```
+#[autodoc(category = "Applications")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApplicationToken {
+    /// The bot's new token, all of its previous tokens stop working.
+    pub token: String,
+}
@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Category, CategoryEdit, Emoji, EmojiEdit, MemberEdit, SphereBan, SphereChannel,
+    SphereChannelEdit, SphereEdit,
+};
+
+/// The type of change an [`AuditLogEntry`] records.
+#[autodoc(category = "Audit Log")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[cfg_attr(feature = "logic", derive(sqlx::Type))]
+#[cfg_attr(feature = "logic", sqlx(type_name = "audit_log_action"))]
+#[cfg_attr(feature = "logic", sqlx(rename_all = "SCREAMING_SNAKE_CASE"))]
+pub enum AuditLogAction {
+    /// The sphere itself was edited.
+    SphereUpdate,
+    /// A category was edited.
+    CategoryUpdate,
+    /// A category was deleted.
+    CategoryDelete,
+    /// A channel was edited.
+    ChannelUpdate,
+    /// A channel was deleted.
+    ChannelDelete,
+    /// An emoji was edited.
+    EmojiUpdate,
+    /// An emoji was deleted.
+    EmojiDelete,
+    /// A member was kicked from the sphere.
+    MemberKick,
+    /// A user was banned from the sphere.
+    MemberBan,
+    /// A user was unbanned from the sphere.
+    MemberUnban,
+    /// A member was timed out or had their timeout removed.
+    MemberTimeout,
+    /// Another member's nickname or roles were edited.
+    MemberUpdate,
+}
+
+/// The changes recorded by an [`AuditLogEntry`].
+///
+/// Edits store the previous values of the changed fields in `before` and their new values in
+/// `after` while deletions store the deleted object in `before`.
+#[autodoc(category = "Audit Log")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AuditLogChanges {
+    /// The sphere itself was edited.
+    SphereUpdate {
+        before: SphereEdit,
+        after: SphereEdit,
+    },
+    /// A category was edited.
+    CategoryUpdate {
+        before: CategoryEdit,
+        after: CategoryEdit,
+    },
+    /// A category was deleted.
+    CategoryDelete { before: Category },
+    /// A channel was edited.
+    ChannelUpdate {
+        before: SphereChannelEdit,
+        after: SphereChannelEdit,
+    },
+    /// A channel was deleted.
+    ChannelDelete { before: SphereChannel },
+    /// An emoji was edited.
+    EmojiUpdate { before: EmojiEdit, after: EmojiEdit },
+    /// An emoji was deleted.
+    EmojiDelete { before: Emoji },
+    /// A member was kicked from the sphere.
+    MemberKick,
+    /// A user was banned from the sphere.
+    MemberBan { after: SphereBan },
+    /// A user was unbanned from the sphere.
+    MemberUnban,
+    /// A member was timed out or had their timeout removed.
+    MemberTimeout {
+        /// When the member's previous timeout would have ended.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        before: Option<u64>,
+        /// When the member's new timeout ends, not present if the timeout was removed.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        after: Option<u64>,
+    },
+    /// Another member's nickname or roles were edited.
+    MemberUpdate {
+        before: MemberEdit,
+        after: MemberEdit,
+    },
+}
+
+impl AuditLogChanges {
+    /// Get the type of change these changes belong to.
+    pub fn action(&self) -> AuditLogAction {
+        match self {
+            Self::SphereUpdate { .. } => AuditLogAction::SphereUpdate,
+            Self::CategoryUpdate { .. } => AuditLogAction::CategoryUpdate,
+            Self::CategoryDelete { .. } => AuditLogAction::CategoryDelete,
+            Self::ChannelUpdate { .. } => AuditLogAction::ChannelUpdate,
+            Self::ChannelDelete { .. } => AuditLogAction::ChannelDelete,
+            Self::EmojiUpdate { .. } => AuditLogAction::EmojiUpdate,
+            Self::EmojiDelete { .. } => AuditLogAction::EmojiDelete,
+            Self::MemberKick => AuditLogAction::MemberKick,
+            Self::MemberBan { .. } => AuditLogAction::MemberBan,
+            Self::MemberUnban => AuditLogAction::MemberUnban,
+            Self::MemberTimeout { .. } => AuditLogAction::MemberTimeout,
+            Self::MemberUpdate { .. } => AuditLogAction::MemberUpdate,
+        }
+    }
+}
+
+/// An entry of a sphere's audit log, recording who changed what inside of the sphere.
+///
+/// The time an entry was made at can be derived from its ID.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "id": 5490083823641,
+///   "sphere_id": 4080402038786,
+///   "actor_id": 48615849987334,
+///   "target_id": 4080402038800,
+///   "reason": "Keeping it on topic",
+///   "action": "CHANNEL_UPDATE",
+///   "before": {
+///     "topic": "anything goes"
+///   },
+///   "after": {
+///     "topic": "gacha game channel"
+///   }
+/// }
+/// ```
+#[autodoc(category = "Audit Log")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditLogEntry {
+    /// The ID of this entry.
+    pub id: u64,
+    /// The ID of the sphere this entry belongs to.
+    pub sphere_id: u64,
+    /// The ID of the user who made the change.
+    pub actor_id: u64,
+    /// The ID of the sphere, category, channel, emoji or user that was changed.
+    pub target_id: u64,
+    /// The reason the actor gave for the change.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// The changes that were made.
+    #[serde(flatten)]
+    pub changes: AuditLogChanges,
+}
@@ -20,6 +20,8 @@ pub(crate) enum ChannelType {
     Group,
     /// A direct message channel.
     Direct,
+    /// A sphere forum channel.
+    Forum,
 }
 
 /// Valid Eludris sphere "channel" types.
@@ -31,6 +33,8 @@ pub enum SphereChannelType {
     Text,
     /// A sphere voice channel.
     Voice,
+    /// A sphere forum channel.
+    Forum,
 }
 
 impl SphereChannelType {
@@ -38,6 +42,7 @@ impl SphereChannelType {
         match self {
             Self::Text => ChannelType::Text,
             Self::Voice => ChannelType::Voice,
+            Self::Forum => ChannelType::Forum,
         }
     }
 }
@@ -52,12 +57,46 @@ pub enum Channel {
     Text(TextChannel),
     /// A sphere voice channel.
     Voice(VoiceChannel),
+    /// A sphere forum channel.
+    Forum(ForumChannel),
     /// A group channel.
     Group(GroupChannel),
     /// A direct message channel.
     Direct(DirectMessageChannel),
 }
 
+impl Channel {
+    pub fn get_id(&self) -> u64 {
+        match self {
+            Channel::Text(channel) => channel.id,
+            Channel::Voice(channel) => channel.id,
+            Channel::Forum(channel) => channel.id,
+            Channel::Group(channel) => channel.id,
+            Channel::Direct(channel) => channel.id,
+        }
+    }
+
+    /// Get the ID of the sphere this channel belongs to, if it's a sphere channel.
+    pub fn get_sphere_id(&self) -> Option<u64> {
+        match self {
+            Channel::Text(channel) => Some(channel.sphere_id),
+            Channel::Voice(channel) => Some(channel.sphere_id),
+            Channel::Forum(channel) => Some(channel.sphere_id),
+            Channel::Group(..) | Channel::Direct(..) => None,
+        }
+    }
+}
+
+impl From<SphereChannel> for Channel {
+    fn from(channel: SphereChannel) -> Self {
+        match channel {
+            SphereChannel::Text(channel) => Self::Text(channel),
+            SphereChannel::Voice(channel) => Self::Voice(channel),
+            SphereChannel::Forum(channel) => Self::Forum(channel),
+        }
+    }
+}
+
 /// The generic definition of all the different types an Eludris "channel" inside
 /// a sphere can be.
 #[autodoc(category = "Channels")]
@@ -69,6 +108,8 @@ pub enum SphereChannel {
     Text(TextChannel),
     /// A voice channel.
     Voice(VoiceChannel),
+    /// A forum channel.
+    Forum(ForumChannel),
 }
 
 impl SphereChannel {
@@ -76,6 +117,7 @@ impl SphereChannel {
         match self {
             SphereChannel::Text(channel) => channel.id,
             SphereChannel::Voice(channel) => channel.id,
+            SphereChannel::Forum(channel) => channel.id,
         }
     }
 
@@ -83,6 +125,7 @@ impl SphereChannel {
         match self {
             SphereChannel::Text(channel) => channel.category_id,
             SphereChannel::Voice(channel) => channel.category_id,
+            SphereChannel::Forum(channel) => channel.category_id,
         }
     }
 
@@ -90,6 +133,7 @@ impl SphereChannel {
         match self {
             SphereChannel::Text(channel) => channel.position,
             SphereChannel::Voice(channel) => channel.position,
+            SphereChannel::Forum(channel) => channel.position,
         }
     }
 
@@ -97,6 +141,7 @@ impl SphereChannel {
         match self {
             SphereChannel::Text(channel) => &channel.name,
             SphereChannel::Voice(channel) => &channel.name,
+            SphereChannel::Forum(channel) => &channel.name,
         }
     }
 
@@ -104,6 +149,7 @@ impl SphereChannel {
         match self {
             SphereChannel::Text(channel) => channel.topic.as_ref(),
             SphereChannel::Voice(..) => None,
+            SphereChannel::Forum(channel) => channel.topic.as_ref(),
         }
     }
 
@@ -111,6 +157,7 @@ impl SphereChannel {
         match self {
             SphereChannel::Text(channel) => channel.sphere_id,
             SphereChannel::Voice(channel) => channel.sphere_id,
+            SphereChannel::Forum(channel) => channel.sphere_id,
         }
     }
 }
@@ -181,6 +228,41 @@ pub struct VoiceChannel {
     pub category_id: u64,
 }
 
+/// A Reddit-like forum channel where members create posts and comment on them.
+///
+/// This type of channel can only exist inside `FORUM` and `HYBRID` spheres.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "id": 4080402038801,
+///   "sphere_id": 4080402038786,
+///   "name": "showcase",
+///   "topic": "Show off what you've been working on",
+///   "position": 4
+/// }
+/// ```
+#[autodoc(category = "Channels")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForumChannel {
+    /// The ID of this forum channel.
+    pub id: u64,
+    /// The ID of the sphere that this forum channel belongs to.
+    pub sphere_id: u64,
+    /// The name of this forum channel.
+    pub name: String,
+    /// The topic of this forum channel.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+    /// This forum channel's position inside of its sphere.
+    pub position: u32,
+    /// The ID of the category this channel belongs to.
+    pub category_id: u64,
+}
+
 /// A Discord-like group channel, also known as a group DM.
 ///
 /// -----
@@ -190,7 +272,11 @@ pub struct VoiceChannel {
 /// ```json
 /// {
 ///   "id": 4080402038800,
-///   "owner": 4080402038776,
+///   "owner": {
+///     "id": 4080402038776,
+///     "username": "sinsaint",
+///     ...
+///   },
 ///   "name": "abandoned project",
 ///   "members": [ ... ],
 ///   "topic": "The amazing group chat for our new banger world-changing project"
@@ -205,7 +291,7 @@ pub struct GroupChannel {
     pub owner: User,
     /// The name of this group channel.
     pub name: String,
-    /// The list of members inside this group channel.
+    /// The list of members inside this group channel, including its owner.
     pub members: Vec<User>,
     /// The file ID of this group channel's icon.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -217,6 +303,9 @@ pub struct GroupChannel {
 
 /// A Discord-like private direct message channel.
 ///
+/// There can only be a single direct message channel between two users, the owner being the
+/// user who opened it.
+///
 /// -----
 ///
 /// ### Example
@@ -224,8 +313,16 @@ pub struct GroupChannel {
 /// ```json
 /// {
 ///   "id": 4080402038800,
-///   "owner": 4080402038776,
-///   "recipient": 4080402038777
+///   "owner": {
+///     "id": 4080402038776,
+///     "username": "sinsaint",
+///     ...
+///   },
+///   "recipient": {
+///     "id": 4080402038777,
+///     "username": "ooliver",
+///     ...
+///   }
 /// }
 /// ```
 #[autodoc(category = "Channels")]
@@ -239,6 +336,75 @@ pub struct DirectMessageChannel {
     pub recipient: User,
 }
 
+/// The GroupChannelCreate payload.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "name": "abandoned project",
+///   "members": [4080402038777, 4080402038778],
+///   "topic": "The amazing group chat for our new banger world-changing project"
+/// }
+/// ```
+#[autodoc(category = "Channels", hidden = true)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupChannelCreate {
+    /// The name of the new group channel.
+    pub name: String,
+    /// The IDs of the users to add to the group channel alongside its creator.
+    pub members: Vec<u64>,
+    /// The file ID of the group channel's icon. This has to be a valid file ID in the
+    /// "group-icons" bucket.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<u64>,
+    /// The topic of the new group channel.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+}
+
+/// The GroupChannelEdit payload.
+///
+/// Only the owner of a group channel can transfer its ownership.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "name": "finished project",
+///   "icon": null
+/// }
+/// ```
+#[autodoc(category = "Channels", hidden = true)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupChannelEdit {
+    /// The new name of the group channel.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The file ID of the group channel's new icon. This has to be a valid file ID in the
+    /// "group-icons" bucket.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    pub icon: Option<Option<u64>>,
+    /// The new topic of the group channel.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    pub topic: Option<Option<String>>,
+    /// The ID of the group channel's new owner, who has to be a member of it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner_id: Option<u64>,
+}
+
 /// The SphereChannelCreate payload.
 ///
 /// -----
@@ -299,3 +465,81 @@ pub struct SphereChannelEdit {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub category_id: Option<u64>,
 }
+
+/// The type of target a [`PermissionOverwrite`] applies to.
+#[autodoc(category = "Channels")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+#[cfg_attr(feature = "logic", derive(sqlx::Type))]
+#[cfg_attr(feature = "logic", sqlx(type_name = "overwrite_type"))]
+#[cfg_attr(feature = "logic", sqlx(rename_all = "UPPERCASE"))]
+pub enum PermissionOverwriteType {
+    /// The overwrite applies to every member with a role.
+    ///
+    /// Role overwrites using the sphere's ID apply to every member of the sphere.
+    Role,
+    /// The overwrite applies to a single member.
+    Member,
+}
+
+/// A per-channel permission overwrite, applied on top of a member's sphere permissions.
+///
+/// The `@everyone` overwrite is applied first, then the combined overwrites of all the
+/// member's roles and lastly the member's own overwrite, with allowed permissions taking
+/// precedence over denied ones at each step.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "id": 5490083823625,
+///   "type": "ROLE",
+///   "allow": 768,
+///   "deny": 1024
+/// }
+/// ```
+#[autodoc(category = "Channels")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PermissionOverwrite {
+    /// The ID of the role or member this overwrite applies to.
+    pub id: u64,
+    /// The type of this overwrite's target.
+    #[serde(rename = "type")]
+    pub overwrite_type: PermissionOverwriteType,
+    /// The permissions this overwrite grants as a bitfield of [`SpherePermission`]s.
+    pub allow: u64,
+    /// The permissions this overwrite takes away as a bitfield of [`SpherePermission`]s.
+    pub deny: u64,
+}
+
+/// The PermissionOverwriteCreate payload.
+///
+/// Only the `MANAGE_MESSAGES`, `VIEW_CHANNELS`, `SEND_MESSAGES` and `ADD_REACTIONS` permissions
+/// can be overwritten.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "type": "MEMBER",
+///   "allow": 0,
+///   "deny": 512
+/// }
+/// ```
+#[autodoc(category = "Channels", hidden = true)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PermissionOverwriteCreate {
+    /// The type of the overwrite's target.
+    #[serde(rename = "type")]
+    pub overwrite_type: PermissionOverwriteType,
+    /// The permissions the overwrite grants.
+    #[serde(default)]
+    pub allow: u64,
+    /// The permissions the overwrite takes away.
+    #[serde(default)]
+    pub deny: u64,
+}
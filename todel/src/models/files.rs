@@ -49,7 +49,14 @@ pub struct FileData {
 /// {
 ///   "type": "VIDEO",
 ///   "width": 1920,
-///   "height": 1080
+///   "height": 1080,
+///   "duration": 14.2,
+///   "codec": "h264"
+/// }
+/// {
+///   "type": "AUDIO",
+///   "duration": 187.04,
+///   "codec": "opus"
 /// }
 /// {
 ///   "type": "OTHER"
@@ -76,6 +83,20 @@ pub enum FileMetadata {
         /// The video's height in pixels.
         #[serde(skip_serializing_if = "Option::is_none")]
         height: Option<usize>,
+        /// The video's duration in seconds.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        duration: Option<f64>,
+        /// The codec of the video's video stream.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        codec: Option<String>,
+    },
+    Audio {
+        /// The audio's duration in seconds.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        duration: Option<f64>,
+        /// The codec of the audio's audio stream.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        codec: Option<String>,
     },
     Other,
 }
@@ -90,4 +111,16 @@ pub struct File {
     pub bucket: String,
     pub width: Option<usize>,
     pub height: Option<usize>,
+    pub duration: Option<f64>,
+    pub codec: Option<String>,
+}
+
+/// The formats images can be converted to when they're resized.
+#[autodoc(category = "Files")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThumbnailFormat {
+    Webp,
+    Avif,
+    Png,
 }
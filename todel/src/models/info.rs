@@ -24,6 +24,7 @@ const VERSION: &str = env!("CARGO_PKG_VERSION");
 ///   "effis_url": "https://cdn.eludris.gay",
 ///   "file_size": 20000000,
 ///   "attachment_file_size": 25000000,
+///   "thumbnail_sizes": [32, 64, 128, 256, 512],
 ///   "rate_limits": {
 ///     "oprish": {
 ///       "info": {
@@ -87,6 +88,8 @@ pub struct InstanceInfo {
     pub file_size: u64,
     /// The maximum file size (in bytes) of an attachment.
     pub attachment_file_size: u64,
+    /// The sizes (in pixels) images can be resized to when they're fetched from Effis.
+    pub thumbnail_sizes: Vec<u32>,
     /// The instance's email address if any.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub email_address: Option<String>,
@@ -167,6 +170,7 @@ impl InstanceInfo {
             effis_url: conf.effis.url.clone(),
             file_size: conf.effis.file_size,
             attachment_file_size: conf.effis.attachment_file_size,
+            thumbnail_sizes: conf.effis.thumbnail_sizes.clone(),
             email_address: conf.email.as_ref().map(|e| e.address.clone()),
             rate_limits: rate_limits.then_some(InstanceRateLimits {
                 oprish: conf.oprish.rate_limits.clone(),
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+/// The Invite payload. Invites let users join a sphere even if it's invite-only or not
+/// discoverable.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "code": "hK3vQz8p",
+///   "sphere_id": 4080402038786,
+///   "creator_id": 48615849987333,
+///   "uses": 3,
+///   "max_uses": 10,
+///   "expires_at": 1750000000
+/// }
+/// ```
+#[autodoc(category = "Invites")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Invite {
+    /// The invite's code.
+    pub code: String,
+    /// The ID of the sphere this invite is for.
+    pub sphere_id: u64,
+    /// The ID of the user who created this invite, not present if their account was deleted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub creator_id: Option<u64>,
+    /// How many users joined the sphere through this invite.
+    pub uses: u32,
+    /// How many times this invite can be used, unlimited if not present.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_uses: Option<u32>,
+    /// When the invite expires in seconds since the Unix epoch, permanent if not present.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+}
+
+/// The InviteCreate payload.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "max_uses": 10,
+///   "duration": 86400
+/// }
+/// ```
+#[autodoc(category = "Invites", hidden = true)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InviteCreate {
+    /// How many times the invite can be used, unlimited if not present.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_uses: Option<u32>,
+    /// How long the invite should last in seconds, the invite is permanent if not present.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<u64>,
+}
@@ -0,0 +1,392 @@
+use std::net::IpAddr;
+
+use sqlx::{pool::PoolConnection, postgres::PgRow, Acquire, FromRow, Postgres, Row};
+use url::Url;
+
+use crate::{
+    ids::IdGenerator,
+    models::{
+        Application, ApplicationCreate, ApplicationCreated, ApplicationEdit, ApplicationToken,
+        ErrorResponse, Session, User, UserBadge,
+    },
+};
+
+use super::{validate_username, Secret};
+
+/// The maximum amount of applications a user can own.
+const MAX_APPLICATIONS: i64 = 25;
+/// The maximum amount of redirect URIs an application can have.
+const MAX_REDIRECT_URIS: usize = 10;
+
+/// Validate an application's OAuth2 redirect URIs as described in RFC 6749 section 3.1.2.
+fn validate_redirect_uris(redirect_uris: &[String]) -> Result<(), ErrorResponse> {
+    if redirect_uris.len() > MAX_REDIRECT_URIS {
+        return Err(error!(
+            VALIDATION,
+            "redirect_uris",
+            format!(
+                "Applications can't have more than {} redirect URIs",
+                MAX_REDIRECT_URIS
+            )
+        ));
+    }
+    for redirect_uri in redirect_uris {
+        let uri = Url::parse(redirect_uri).map_err(|_| {
+            error!(
+                VALIDATION,
+                "redirect_uris", "The application's redirect URIs must be valid absolute URIs"
+            )
+        })?;
+        if uri.fragment().is_some() || redirect_uri.len() > 2048 {
+            return Err(error!(
+                VALIDATION,
+                "redirect_uris",
+                "The application's redirect URIs must be at most 2048 characters long and can't have a fragment"
+            ));
+        }
+    }
+    Ok(())
+}
+
+impl FromRow<'_, PgRow> for Application {
+    fn from_row(row: &PgRow) -> sqlx::Result<Self> {
+        let bot = User::from_row(row)?;
+        Ok(Self {
+            id: bot.id,
+            owner_id: row.get::<i64, _>("owner_id") as u64,
+            redirect_uris: row.get("redirect_uris"),
+            bot,
+        })
+    }
+}
+
+impl ApplicationCreate {
+    pub fn validate(&self) -> Result<(), ErrorResponse> {
+        validate_username(&self.username)?;
+        if let Some(display_name) = &self.display_name {
+            if display_name.len() < 2 || display_name.len() > 32 {
+                return Err(error!(
+                    VALIDATION,
+                    "display_name",
+                    "The bot's display name must be between 2 and 32 characters in length"
+                ));
+            }
+        }
+        if let Some(redirect_uris) = &self.redirect_uris {
+            validate_redirect_uris(redirect_uris)?;
+        }
+        Ok(())
+    }
+}
+
+impl ApplicationEdit {
+    pub fn validate(&self) -> Result<(), ErrorResponse> {
+        match &self.redirect_uris {
+            Some(redirect_uris) => validate_redirect_uris(redirect_uris),
+            None => Err(error!(
+                VALIDATION,
+                "body", "At least one field must be provided"
+            )),
+        }
+    }
+}
+
+impl Application {
+    pub async fn create(
+        application: ApplicationCreate,
+        owner_id: u64,
+        ip: IpAddr,
+        secret: &Secret,
+        id_generator: &mut IdGenerator,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<ApplicationCreated, ErrorResponse> {
+        application.validate()?;
+        let owner = User::get_unfiltered(owner_id, db).await?;
+        if UserBadge::Bot.is_set(owner.badges) {
+            return Err(error!(FORBIDDEN));
+        }
+        let count = sqlx::query!(
+            "
+SELECT COUNT(a.id)
+FROM applications a
+JOIN users u
+ON a.id = u.id
+WHERE a.owner_id = $1
+AND u.is_deleted = FALSE
+            ",
+            owner_id as i64
+        )
+        .fetch_one(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't count applications of user {}: {}", owner_id, err);
+            error!(SERVER, "Failed to create application")
+        })?
+        .count
+        .unwrap_or(0);
+        if count >= MAX_APPLICATIONS {
+            return Err(error!(
+                VALIDATION,
+                "application",
+                format!(
+                    "Users can't own more than {} applications",
+                    MAX_APPLICATIONS
+                )
+            ));
+        }
+        if let Some(existing_user) = sqlx::query!(
+            "
+SELECT is_deleted
+FROM users
+WHERE username = $1
+            ",
+            application.username,
+        )
+        .fetch_optional(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Failed to check if other users with the same username exist: {}",
+                err
+            );
+            error!(SERVER, "Failed to create application")
+        })? {
+            if !existing_user.is_deleted {
+                return Err(error!(CONFLICT, "username"));
+            }
+        }
+        let id = id_generator.generate();
+        let mut transaction = db.begin().await.map_err(|err| {
+            log::error!("Couldn't start application creation transaction: {}", err);
+            error!(SERVER, "Failed to create application")
+        })?;
+        sqlx::query!(
+            "
+DELETE FROM users
+WHERE username = $1
+AND is_deleted = TRUE
+            ",
+            application.username,
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| {
+            log::error!("Failed to clean up pre-existing deleted user: {}", err);
+            error!(SERVER, "Failed to create application")
+        })?;
+        sqlx::query!(
+            "
+INSERT INTO users(id, username, display_name, badges, verified)
+VALUES($1, $2, $3, $4, TRUE)
+            ",
+            id as i64,
+            application.username,
+            application.display_name,
+            UserBadge::Bot as i64,
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't create bot user: {}", err);
+            error!(SERVER, "Failed to create application")
+        })?;
+        sqlx::query!(
+            "
+INSERT INTO applications(id, owner_id, redirect_uris)
+VALUES($1, $2, $3)
+            ",
+            id as i64,
+            owner_id as i64,
+            &application.redirect_uris.unwrap_or_default(),
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't create application: {}", err);
+            error!(SERVER, "Failed to create application")
+        })?;
+        transaction.commit().await.map_err(|err| {
+            log::error!("Couldn't commit application creation transaction: {}", err);
+            error!(SERVER, "Failed to create application")
+        })?;
+        let token = Session::create_bot(id, ip, secret, id_generator, db)
+            .await?
+            .token;
+        Ok(ApplicationCreated {
+            token,
+            application: Self::get(id, owner_id, db).await?,
+        })
+    }
+
+    /// Get one of a user's applications.
+    pub async fn get(
+        id: u64,
+        owner_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<Self, ErrorResponse> {
+        sqlx::query_as(
+            "
+SELECT u.*, a.owner_id, a.redirect_uris
+FROM applications a
+JOIN users u
+ON a.id = u.id
+WHERE a.id = $1
+AND a.owner_id = $2
+AND u.is_deleted = FALSE
+            ",
+        )
+        .bind(id as i64)
+        .bind(owner_id as i64)
+        .fetch_optional(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch application {}: {}", id, err);
+            error!(SERVER, "Failed to get application")
+        })?
+        .ok_or_else(|| error!(NOT_FOUND))
+    }
+
+    /// Get an application regardless of who owns it.
+    pub async fn get_unchecked(
+        id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<Self, ErrorResponse> {
+        sqlx::query_as(
+            "
+SELECT u.*, a.owner_id, a.redirect_uris
+FROM applications a
+JOIN users u
+ON a.id = u.id
+WHERE a.id = $1
+AND u.is_deleted = FALSE
+            ",
+        )
+        .bind(id as i64)
+        .fetch_optional(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch application {}: {}", id, err);
+            error!(SERVER, "Failed to get application")
+        })?
+        .ok_or_else(|| error!(NOT_FOUND))
+    }
+
+    /// Get all of a user's applications.
+    pub async fn get_all(
+        owner_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<Vec<Self>, ErrorResponse> {
+        sqlx::query_as(
+            "
+SELECT u.*, a.owner_id, a.redirect_uris
+FROM applications a
+JOIN users u
+ON a.id = u.id
+WHERE a.owner_id = $1
+AND u.is_deleted = FALSE
+ORDER BY a.id
+            ",
+        )
+        .bind(owner_id as i64)
+        .fetch_all(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch applications of user {}: {}", owner_id, err);
+            error!(SERVER, "Failed to get applications")
+        })
+    }
+
+    pub async fn edit(
+        &mut self,
+        edit: ApplicationEdit,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<(), ErrorResponse> {
+        edit.validate()?;
+        if let Some(redirect_uris) = edit.redirect_uris {
+            sqlx::query!(
+                "
+UPDATE applications
+SET redirect_uris = $1
+WHERE id = $2
+                ",
+                &redirect_uris,
+                self.id as i64,
+            )
+            .execute(&mut **db)
+            .await
+            .map_err(|err| {
+                log::error!("Couldn't edit application {}: {}", self.id, err);
+                error!(SERVER, "Failed to edit application")
+            })?;
+            self.redirect_uris = redirect_uris;
+        }
+        Ok(())
+    }
+
+    /// Revoke all of this application's bot tokens and issue a new one.
+    pub async fn rotate_token(
+        &self,
+        ip: IpAddr,
+        secret: &Secret,
+        id_generator: &mut IdGenerator,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<ApplicationToken, ErrorResponse> {
+        sqlx::query!(
+            "
+DELETE FROM sessions
+WHERE user_id = $1
+            ",
+            self.id as i64,
+        )
+        .execute(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't revoke tokens of bot {}: {}", self.id, err);
+            error!(SERVER, "Failed to rotate the bot's token")
+        })?;
+        Ok(ApplicationToken {
+            token: Session::create_bot(self.id, ip, secret, id_generator, db)
+                .await?
+                .token,
+        })
+    }
+
+    pub async fn delete(&self, db: &mut PoolConnection<Postgres>) -> Result<(), ErrorResponse> {
+        let mut transaction = db.begin().await.map_err(|err| {
+            log::error!("Couldn't start application deletion transaction: {}", err);
+            error!(SERVER, "Failed to delete application")
+        })?;
+        sqlx::query!(
+            "
+UPDATE users
+SET is_deleted = TRUE
+WHERE id = $1
+            ",
+            self.id as i64,
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't mark bot {} as deleted: {}", self.id, err);
+            error!(SERVER, "Failed to delete application")
+        })?;
+        sqlx::query!(
+            "
+DELETE FROM sessions
+WHERE user_id = $1
+            ",
+            self.id as i64,
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't revoke tokens of bot {}: {}", self.id, err);
+            error!(SERVER, "Failed to delete application")
+        })?;
+        transaction.commit().await.map_err(|err| {
+            log::error!("Couldn't commit application deletion transaction: {}", err);
+            error!(SERVER, "Failed to delete application")
+        })?;
+        Ok(())
+    }
+}
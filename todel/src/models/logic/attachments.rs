@@ -1,4 +1,6 @@
-use crate::models::{AttachmentCreate, ErrorResponse};
+use sqlx::{pool::PoolConnection, Postgres};
+
+use crate::models::{Attachment, AttachmentCreate, ErrorResponse, File};
 
 impl AttachmentCreate {
     pub fn validate(&mut self) -> Result<(), ErrorResponse> {
@@ -16,3 +18,33 @@ impl AttachmentCreate {
         Ok(())
     }
 }
+
+impl Attachment {
+    /// Validate a list of attachment payloads and fetch their files from the attachments bucket.
+    pub(crate) async fn gather(
+        attachments: Vec<AttachmentCreate>,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<Vec<Self>, ErrorResponse> {
+        let mut gathered = vec![];
+        for (i, mut attachment_create) in attachments.into_iter().enumerate() {
+            attachment_create.validate()?;
+
+            let attachment_file = File::get(attachment_create.file_id, "attachments", db)
+                .await
+                .ok_or_else(|| {
+                    error!(
+                        VALIDATION,
+                        format!("attachments-{}", i),
+                        "Attachment's file must be a valid file that exists in the attachments bucket"
+                    )
+                })?;
+
+            gathered.push(Attachment {
+                file: attachment_file.get_file_data(),
+                description: attachment_create.description,
+                spoiler: attachment_create.spoiler,
+            });
+        }
+        Ok(gathered)
+    }
+}
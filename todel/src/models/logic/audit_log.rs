@@ -0,0 +1,143 @@
+use sqlx::{
+    pool::PoolConnection, postgres::PgRow, types::Json, FromRow, PgConnection, Postgres,
+    QueryBuilder, Row,
+};
+
+use crate::{
+    ids::IdGenerator,
+    models::{AuditLogAction, AuditLogChanges, AuditLogEntry, ErrorResponse},
+};
+
+/// The user responsible for a change, used to record it in the sphere's audit log.
+pub struct AuditLogActor<'a> {
+    /// The ID of the user making the change.
+    pub user_id: u64,
+    /// The reason the user gave for the change.
+    pub reason: Option<String>,
+    pub id_generator: &'a mut IdGenerator,
+}
+
+impl AuditLogActor<'_> {
+    pub fn validate(&self) -> Result<(), ErrorResponse> {
+        if let Some(reason) = &self.reason {
+            if reason.is_empty() || reason.len() > 512 {
+                return Err(error!(
+                    VALIDATION,
+                    "reason", "The audit log reason must be between 1 and 512 characters long"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromRow<'_, PgRow> for AuditLogEntry {
+    fn from_row(row: &PgRow) -> sqlx::Result<Self> {
+        Ok(Self {
+            id: row.get::<i64, _>("id") as u64,
+            sphere_id: row.get::<i64, _>("sphere_id") as u64,
+            actor_id: row.get::<i64, _>("actor_id") as u64,
+            target_id: row.get::<i64, _>("target_id") as u64,
+            reason: row.get("reason"),
+            changes: row.get::<Json<AuditLogChanges>, _>("changes").0,
+        })
+    }
+}
+
+impl AuditLogEntry {
+    /// Record a change in a sphere's audit log.
+    ///
+    /// This takes a plain connection so that entries can be written inside of the transaction
+    /// making the change.
+    pub(crate) async fn create(
+        sphere_id: u64,
+        target_id: u64,
+        changes: AuditLogChanges,
+        actor: AuditLogActor<'_>,
+        db: &mut PgConnection,
+    ) -> Result<Self, ErrorResponse> {
+        let id = actor.id_generator.generate();
+        let action = changes.action();
+        sqlx::query(
+            "
+INSERT INTO audit_log(id, sphere_id, actor_id, target_id, action, changes, reason)
+VALUES($1, $2, $3, $4, $5, $6, $7)
+            ",
+        )
+        .bind(id as i64)
+        .bind(sphere_id as i64)
+        .bind(actor.user_id as i64)
+        .bind(target_id as i64)
+        .bind(action)
+        .bind(Json(&changes))
+        .bind(&actor.reason)
+        .execute(db)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Couldn't create audit log entry in sphere {}: {}",
+                sphere_id,
+                err
+            );
+            error!(SERVER, "Failed to create audit log entry")
+        })?;
+        Ok(Self {
+            id,
+            sphere_id,
+            actor_id: actor.user_id,
+            target_id,
+            reason: actor.reason,
+            changes,
+        })
+    }
+
+    /// Get a page of a sphere's audit log, newest entries first.
+    pub async fn get_all(
+        sphere_id: u64,
+        limit: u32,
+        before: Option<u64>,
+        action: Option<AuditLogAction>,
+        actor_id: Option<u64>,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<Vec<Self>, ErrorResponse> {
+        if !(1..=100).contains(&limit) {
+            return Err(error!(
+                VALIDATION,
+                "limit", "Limit must be between 1 and 100, inclusive."
+            ));
+        }
+
+        let mut query: QueryBuilder<Postgres> = QueryBuilder::new(
+            "
+            SELECT *
+            FROM audit_log
+            WHERE sphere_id =
+            ",
+        );
+        query.push_bind(sphere_id as i64);
+
+        if let Some(id) = before {
+            query.push(" AND id < ").push_bind(id as i64);
+        }
+        if let Some(action) = action {
+            query.push(" AND action = ").push_bind(action);
+        }
+        if let Some(id) = actor_id {
+            query.push(" AND actor_id = ").push_bind(id as i64);
+        }
+
+        query
+            .push(" ORDER BY id DESC ")
+            .push(" LIMIT ")
+            .push_bind(limit as i32);
+
+        query
+            .build_query_as()
+            .fetch_all(&mut **db)
+            .await
+            .map_err(|err| {
+                log::error!("Couldn't fetch audit log for sphere {}: {}", sphere_id, err);
+                error!(SERVER, "Failed to get audit log")
+            })
+    }
+}
@@ -1,13 +1,18 @@
-use sqlx::{pool::PoolConnection, Postgres};
+use sqlx::{pool::PoolConnection, Acquire, Postgres};
 
-use crate::models::{Category, ErrorResponse, Sphere};
+use crate::models::{
+    AuditLogActor, AuditLogChanges, AuditLogEntry, Category, ErrorResponse, Sphere,
+};
 
 impl Category {
     pub async fn delete(
         sphere_id: u64,
         category_id: u64,
+        actor: AuditLogActor<'_>,
         db: &mut PoolConnection<Postgres>,
     ) -> Result<(), ErrorResponse> {
+        actor.validate()?;
+
         Sphere::get_unpopulated(sphere_id, db)
             .await
             .map_err(|err| {
@@ -25,7 +30,7 @@ impl Category {
             ));
         }
 
-        let current_position = Category::get_unpopulated(category_id, db)
+        let current_category = Category::get_unpopulated(category_id, db)
             .await
             .map_err(|err| {
                 if let ErrorResponse::NotFound { .. } = err {
@@ -33,8 +38,12 @@ impl Category {
                 } else {
                     err
                 }
-            })?
-            .position;
+            })?;
+
+        let mut transaction = db.begin().await.map_err(|err| {
+            log::error!("Couldn't start category delete transaction: {}", err);
+            error!(SERVER, "Failed to delete category")
+        })?;
 
         sqlx::query!(
             "
@@ -50,15 +59,31 @@ WHERE sphere_id = $1
     AND is_deleted = FALSE
             ",
             sphere_id as i64,
-            current_position as i32,
+            current_category.position as i32,
         )
-        .execute(&mut **db)
+        .execute(&mut *transaction)
         .await
         .map_err(|err| {
             log::error!("Couldn't update category positions after deletion: {}", err);
             error!(SERVER, "Failed to delete category")
         })?;
 
+        AuditLogEntry::create(
+            sphere_id,
+            category_id,
+            AuditLogChanges::CategoryDelete {
+                before: current_category,
+            },
+            actor,
+            &mut transaction,
+        )
+        .await?;
+
+        transaction.commit().await.map_err(|err| {
+            log::error!("Couldn't commit category delete transaction: {}", err);
+            error!(SERVER, "Failed to delete category")
+        })?;
+
         Ok(())
     }
 }
@@ -1,6 +1,8 @@
 use sqlx::{pool::PoolConnection, Acquire, Postgres};
 
-use crate::models::{Category, CategoryEdit, ErrorResponse, Sphere};
+use crate::models::{
+    AuditLogActor, AuditLogChanges, AuditLogEntry, Category, CategoryEdit, ErrorResponse, Sphere,
+};
 
 impl CategoryEdit {
     pub fn validate(&self) -> Result<(), ErrorResponse> {
@@ -35,9 +37,11 @@ impl Category {
         mut category: CategoryEdit,
         sphere_id: u64,
         category_id: u64,
+        actor: AuditLogActor<'_>,
         db: &mut PoolConnection<Postgres>,
     ) -> Result<(Category, CategoryEdit), ErrorResponse> {
         category.validate()?;
+        actor.validate()?;
 
         Sphere::get_unpopulated(sphere_id, db)
             .await
@@ -141,6 +145,24 @@ WHERE sphere_id = $3
             })?;
         }
 
+        AuditLogEntry::create(
+            sphere_id,
+            category_id,
+            AuditLogChanges::CategoryUpdate {
+                before: CategoryEdit {
+                    name: category
+                        .name
+                        .as_ref()
+                        .map(|_| current_category.name.clone()),
+                    position: category.position.map(|_| current_category.position),
+                },
+                after: category.clone(),
+            },
+            actor,
+            &mut transaction,
+        )
+        .await?;
+
         transaction.commit().await.map_err(|err| {
             log::error!("Couldn't commit category edit transaction: {}", err);
             error!(SERVER, "Failed to edit category")
@@ -1,13 +1,18 @@
-use sqlx::{pool::PoolConnection, Postgres};
+use sqlx::{pool::PoolConnection, Acquire, Postgres};
 
-use crate::models::{ErrorResponse, Sphere, SphereChannel};
+use crate::models::{
+    AuditLogActor, AuditLogChanges, AuditLogEntry, ErrorResponse, Sphere, SphereChannel,
+};
 
 impl SphereChannel {
     pub async fn delete(
         sphere_id: u64,
         channel_id: u64,
+        actor: AuditLogActor<'_>,
         db: &mut PoolConnection<Postgres>,
     ) -> Result<(), ErrorResponse> {
+        actor.validate()?;
+
         Sphere::get_unpopulated(sphere_id, db)
             .await
             .map_err(|err| {
@@ -20,6 +25,11 @@ impl SphereChannel {
 
         let current_channel = SphereChannel::get(channel_id, db).await?;
 
+        let mut transaction = db.begin().await.map_err(|err| {
+            log::error!("Couldn't start channel delete transaction: {}", err);
+            error!(SERVER, "Failed to delete channel")
+        })?;
+
         sqlx::query!(
             "
 UPDATE channels
@@ -36,13 +46,29 @@ WHERE category_id = $1
             current_channel.get_category_id() as i64,
             current_channel.get_position() as i32,
         )
-        .execute(&mut **db)
+        .execute(&mut *transaction)
         .await
         .map_err(|err| {
             log::error!("Couldn't delete channel: {}", err);
             error!(SERVER, "Failed to delete channel")
         })?;
 
+        AuditLogEntry::create(
+            sphere_id,
+            channel_id,
+            AuditLogChanges::ChannelDelete {
+                before: current_channel,
+            },
+            actor,
+            &mut transaction,
+        )
+        .await?;
+
+        transaction.commit().await.map_err(|err| {
+            log::error!("Couldn't commit channel delete transaction: {}", err);
+            error!(SERVER, "Failed to delete channel")
+        })?;
+
         Ok(())
     }
 }
@@ -0,0 +1,143 @@
+use redis::AsyncCommands;
+use sqlx::{pool::PoolConnection, Postgres};
+
+use crate::{
+    ids::IdGenerator,
+    models::{DirectMessageChannel, ErrorResponse, User},
+};
+
+impl DirectMessageChannel {
+    pub(crate) async fn populate<C: AsyncCommands>(
+        id: u64,
+        owner_id: u64,
+        recipient_id: u64,
+        db: &mut PoolConnection<Postgres>,
+        cache: &mut C,
+    ) -> Result<Self, ErrorResponse> {
+        Ok(Self {
+            id,
+            owner: User::get(owner_id, None, db, cache).await?,
+            recipient: User::get(recipient_id, None, db, cache).await?,
+        })
+    }
+
+    /// Get the direct message channel between two users, opening one if it doesn't exist yet.
+    pub async fn get_or_create<C: AsyncCommands>(
+        owner_id: u64,
+        recipient_id: u64,
+        id_generator: &mut IdGenerator,
+        db: &mut PoolConnection<Postgres>,
+        cache: &mut C,
+    ) -> Result<Self, ErrorResponse> {
+        if owner_id == recipient_id {
+            return Err(error!(
+                VALIDATION,
+                "recipient", "You can't open a direct message channel with yourself"
+            ));
+        }
+        User::get_unfiltered(recipient_id, db).await?;
+
+        // the unique index on the channel's users makes this a no-op if the channel exists
+        sqlx::query!(
+            "
+INSERT INTO channels(id, owner_id, recipient_id, channel_type)
+VALUES($1, $2, $3, 'DIRECT')
+ON CONFLICT DO NOTHING
+            ",
+            id_generator.generate() as i64,
+            owner_id as i64,
+            recipient_id as i64,
+        )
+        .execute(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Couldn't create direct message channel between {} and {}: {}",
+                owner_id,
+                recipient_id,
+                err
+            );
+            error!(SERVER, "Failed to create direct message channel")
+        })?;
+        let channel = sqlx::query!(
+            r#"
+SELECT id, owner_id AS "owner_id!", recipient_id AS "recipient_id!"
+FROM channels
+WHERE channel_type = 'DIRECT'
+    AND LEAST(owner_id, recipient_id) = LEAST($1::BIGINT, $2::BIGINT)
+    AND GREATEST(owner_id, recipient_id) = GREATEST($1::BIGINT, $2::BIGINT)
+            "#,
+            owner_id as i64,
+            recipient_id as i64,
+        )
+        .fetch_one(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Couldn't fetch direct message channel between {} and {}: {}",
+                owner_id,
+                recipient_id,
+                err
+            );
+            error!(SERVER, "Failed to create direct message channel")
+        })?;
+
+        Self::populate(
+            channel.id as u64,
+            channel.owner_id as u64,
+            channel.recipient_id as u64,
+            db,
+            cache,
+        )
+        .await
+    }
+
+    /// Get all the direct message channels a user is a part of.
+    pub async fn get_all<C: AsyncCommands>(
+        user_id: u64,
+        db: &mut PoolConnection<Postgres>,
+        cache: &mut C,
+    ) -> Result<Vec<Self>, ErrorResponse> {
+        let rows = sqlx::query!(
+            r#"
+SELECT id, owner_id AS "owner_id!", recipient_id AS "recipient_id!"
+FROM channels
+WHERE channel_type = 'DIRECT'
+    AND (owner_id = $1 OR recipient_id = $1)
+    AND is_deleted = FALSE
+    AND NOT EXISTS (
+        SELECT 1
+        FROM users
+        WHERE users.id IN (owner_id, recipient_id)
+        AND users.is_deleted = TRUE
+    )
+            "#,
+            user_id as i64,
+        )
+        .fetch_all(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Couldn't fetch direct message channels of user {}: {}",
+                user_id,
+                err
+            );
+            error!(SERVER, "Failed to fetch direct message channels")
+        })?;
+
+        let mut channels = vec![];
+        for row in rows {
+            channels.push(
+                Self::populate(
+                    row.id as u64,
+                    row.owner_id as u64,
+                    row.recipient_id as u64,
+                    db,
+                    cache,
+                )
+                .await?,
+            );
+        }
+        Ok(channels)
+    }
+}
@@ -1,7 +1,8 @@
 use sqlx::{pool::PoolConnection, Acquire, Postgres};
 
 use crate::models::{
-    ErrorResponse, Sphere, SphereChannel, SphereChannelEdit, TextChannel, VoiceChannel,
+    AuditLogActor, AuditLogChanges, AuditLogEntry, ErrorResponse, ForumChannel, Sphere,
+    SphereChannel, SphereChannelEdit, TextChannel, VoiceChannel,
 };
 
 impl SphereChannelEdit {
@@ -50,9 +51,11 @@ impl SphereChannel {
         mut channel: SphereChannelEdit,
         sphere_id: u64,
         channel_id: u64,
+        actor: AuditLogActor<'_>,
         db: &mut PoolConnection<Postgres>,
     ) -> Result<(Self, SphereChannelEdit), ErrorResponse> {
         channel.validate()?;
+        actor.validate()?;
 
         Sphere::get_unpopulated(sphere_id, db)
             .await
@@ -244,6 +247,31 @@ WHERE category_id = $3
             }
         }
 
+        AuditLogEntry::create(
+            sphere_id,
+            channel_id,
+            AuditLogChanges::ChannelUpdate {
+                before: SphereChannelEdit {
+                    name: channel
+                        .name
+                        .as_ref()
+                        .map(|_| current_channel.get_name().clone()),
+                    topic: channel
+                        .topic
+                        .as_ref()
+                        .map(|_| current_channel.get_topic().cloned()),
+                    position: channel.position.map(|_| current_channel.get_position()),
+                    category_id: channel
+                        .category_id
+                        .map(|_| current_channel.get_category_id()),
+                },
+                after: channel.clone(),
+            },
+            actor,
+            &mut transaction,
+        )
+        .await?;
+
         transaction.commit().await.map_err(|err| {
             log::error!("Couldn't commit channel edit transaction: {}", err);
             error!(SERVER, "Failed to edit channel")
@@ -267,6 +295,14 @@ WHERE category_id = $3
                     position: channel.position.unwrap_or(current_channel.position),
                     category_id: channel.category_id.unwrap_or(current_channel.category_id),
                 }),
+                SphereChannel::Forum(current_channel) => Self::Forum(ForumChannel {
+                    id: channel_id,
+                    sphere_id,
+                    name: channel.name.unwrap_or(current_channel.name),
+                    topic: channel.topic.unwrap_or(current_channel.topic),
+                    position: channel.position.unwrap_or(current_channel.position),
+                    category_id: channel.category_id.unwrap_or(current_channel.category_id),
+                }),
             }
         };
 
@@ -1,6 +1,9 @@
-use sqlx::{pool::PoolConnection, Postgres};
+use redis::AsyncCommands;
+use sqlx::{pool::PoolConnection, FromRow, Postgres, Row};
 
-use crate::models::{ErrorResponse, SphereChannel};
+use crate::models::{
+    Channel, ChannelType, DirectMessageChannel, ErrorResponse, GroupChannel, SphereChannel,
+};
 
 impl SphereChannel {
     pub async fn get(id: u64, db: &mut PoolConnection<Postgres>) -> Result<Self, ErrorResponse> {
@@ -9,6 +12,7 @@ impl SphereChannel {
 SELECT *
 FROM channels
 WHERE id = $1
+    AND channel_type IN ('TEXT', 'VOICE', 'FORUM')
     AND is_deleted = FALSE
             ",
         )
@@ -22,3 +26,58 @@ WHERE id = $1
         .ok_or_else(|| error!(NOT_FOUND))
     }
 }
+
+impl Channel {
+    pub async fn get<C: AsyncCommands>(
+        id: u64,
+        db: &mut PoolConnection<Postgres>,
+        cache: &mut C,
+    ) -> Result<Self, ErrorResponse> {
+        let row = sqlx::query(
+            "
+SELECT *
+FROM channels
+WHERE id = $1
+    AND is_deleted = FALSE
+            ",
+        )
+        .bind(id as i64)
+        .fetch_optional(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch channel data {}: {}", id, err);
+            error!(SERVER, "Failed to fetch channel data")
+        })?
+        .ok_or_else(|| error!(NOT_FOUND))?;
+        match row.get::<ChannelType, _>("channel_type") {
+            ChannelType::Text | ChannelType::Voice | ChannelType::Forum => {
+                SphereChannel::from_row(&row)
+                    .map(Self::from)
+                    .map_err(|err| {
+                        log::error!("Couldn't parse channel data {}: {}", id, err);
+                        error!(SERVER, "Failed to fetch channel data")
+                    })
+            }
+            ChannelType::Direct => DirectMessageChannel::populate(
+                id,
+                row.get::<i64, _>("owner_id") as u64,
+                row.get::<i64, _>("recipient_id") as u64,
+                db,
+                cache,
+            )
+            .await
+            .map(Self::Direct),
+            ChannelType::Group => GroupChannel::populate(
+                id,
+                row.get::<i64, _>("owner_id") as u64,
+                row.get("name"),
+                row.get::<Option<i64>, _>("icon").map(|i| i as u64),
+                row.get("topic"),
+                db,
+                cache,
+            )
+            .await
+            .map(Self::Group),
+        }
+    }
+}
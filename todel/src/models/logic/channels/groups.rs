@@ -0,0 +1,623 @@
+use redis::AsyncCommands;
+use sqlx::{pool::PoolConnection, Acquire, Postgres};
+
+use crate::{
+    ids::IdGenerator,
+    models::{ErrorResponse, File, GroupChannel, GroupChannelCreate, GroupChannelEdit, User},
+};
+
+/// The maximum amount of members a group channel can have, including its owner.
+const MAX_GROUP_MEMBERS: usize = 10;
+
+impl GroupChannelCreate {
+    pub fn validate(&mut self) -> Result<(), ErrorResponse> {
+        if self.name.is_empty() || self.name.len() > 32 {
+            return Err(error!(
+                VALIDATION,
+                "name", "The group's name must be between 1 and 32 characters long"
+            ));
+        }
+        if let Some(topic) = &self.topic {
+            if topic.is_empty() {
+                self.topic = None;
+            } else if topic.len() > 4096 {
+                return Err(error!(
+                    VALIDATION,
+                    "topic", "The group's topic must be less than 4096 characters long"
+                ));
+            }
+        }
+        self.members.sort_unstable();
+        self.members.dedup();
+        if self.members.is_empty() || self.members.len() >= MAX_GROUP_MEMBERS {
+            return Err(error!(
+                VALIDATION,
+                "members",
+                format!(
+                    "Groups must be created with between 1 and {} other members",
+                    MAX_GROUP_MEMBERS - 1
+                )
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl GroupChannelEdit {
+    pub fn validate(&self) -> Result<(), ErrorResponse> {
+        if self.name.is_none()
+            && self.icon.is_none()
+            && self.topic.is_none()
+            && self.owner_id.is_none()
+        {
+            return Err(error!(
+                VALIDATION,
+                "body", "At least one of 'name', 'icon', 'topic' or 'owner_id' must be provided."
+            ));
+        }
+        if let Some(name) = &self.name {
+            if name.is_empty() || name.len() > 32 {
+                return Err(error!(
+                    VALIDATION,
+                    "name", "The group's name must be between 1 and 32 characters long"
+                ));
+            }
+        }
+        if let Some(Some(topic)) = &self.topic {
+            if topic.len() > 4096 {
+                return Err(error!(
+                    VALIDATION,
+                    "topic", "The group's topic must be less than 4096 characters long"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl GroupChannel {
+    pub(crate) async fn populate<C: AsyncCommands>(
+        id: u64,
+        owner_id: u64,
+        name: String,
+        icon: Option<u64>,
+        topic: Option<String>,
+        db: &mut PoolConnection<Postgres>,
+        cache: &mut C,
+    ) -> Result<Self, ErrorResponse> {
+        let member_ids = sqlx::query!(
+            "
+SELECT channel_members.id
+FROM channel_members
+JOIN users ON users.id = channel_members.id
+WHERE channel_members.channel_id = $1
+    AND users.is_deleted = FALSE
+ORDER BY channel_members.id
+            ",
+            id as i64
+        )
+        .fetch_all(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch members of group {}: {}", id, err);
+            error!(SERVER, "Failed to fetch group data")
+        })?;
+        let mut members = vec![];
+        for member in member_ids {
+            members.push(User::get(member.id as u64, None, db, cache).await?);
+        }
+        Ok(Self {
+            id,
+            owner: User::get(owner_id, None, db, cache).await?,
+            name,
+            members,
+            icon,
+            topic,
+        })
+    }
+
+    pub async fn get<C: AsyncCommands>(
+        id: u64,
+        db: &mut PoolConnection<Postgres>,
+        cache: &mut C,
+    ) -> Result<Self, ErrorResponse> {
+        let group = sqlx::query!(
+            r#"
+SELECT owner_id AS "owner_id!", name AS "name!", icon, topic
+FROM channels
+WHERE id = $1
+    AND channel_type = 'GROUP'
+    AND is_deleted = FALSE
+            "#,
+            id as i64
+        )
+        .fetch_optional(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch group {}: {}", id, err);
+            error!(SERVER, "Failed to fetch group data")
+        })?
+        .ok_or_else(|| error!(NOT_FOUND))?;
+        Self::populate(
+            id,
+            group.owner_id as u64,
+            group.name,
+            group.icon.map(|i| i as u64),
+            group.topic,
+            db,
+            cache,
+        )
+        .await
+    }
+
+    /// Get all the group channels a user is a member of.
+    pub async fn get_all<C: AsyncCommands>(
+        user_id: u64,
+        db: &mut PoolConnection<Postgres>,
+        cache: &mut C,
+    ) -> Result<Vec<Self>, ErrorResponse> {
+        let groups = sqlx::query!(
+            r#"
+SELECT channels.id, owner_id AS "owner_id!", name AS "name!", icon, topic
+FROM channels
+JOIN channel_members ON channel_members.channel_id = channels.id
+WHERE channel_members.id = $1
+    AND channels.channel_type = 'GROUP'
+    AND channels.is_deleted = FALSE
+    AND NOT EXISTS (
+        SELECT 1
+        FROM users
+        WHERE users.id = channels.owner_id
+        AND users.is_deleted = TRUE
+    )
+            "#,
+            user_id as i64
+        )
+        .fetch_all(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch groups of user {}: {}", user_id, err);
+            error!(SERVER, "Failed to fetch groups")
+        })?;
+        let mut channels = vec![];
+        for group in groups {
+            channels.push(
+                Self::populate(
+                    group.id as u64,
+                    group.owner_id as u64,
+                    group.name,
+                    group.icon.map(|i| i as u64),
+                    group.topic,
+                    db,
+                    cache,
+                )
+                .await?,
+            );
+        }
+        Ok(channels)
+    }
+
+    pub async fn has_member(
+        channel_id: u64,
+        user_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<bool, ErrorResponse> {
+        Ok(sqlx::query!(
+            "
+SELECT id
+FROM channel_members
+WHERE id = $1
+    AND channel_id = $2
+            ",
+            user_id as i64,
+            channel_id as i64
+        )
+        .fetch_optional(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Couldn't check if user {} is a member of group {}: {}",
+                user_id,
+                channel_id,
+                err
+            );
+            error!(SERVER, "Failed to check group membership")
+        })?
+        .is_some())
+    }
+
+    /// Get a group's owner, making sure the user is a member of it.
+    async fn get_owner_id(
+        channel_id: u64,
+        user_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<u64, ErrorResponse> {
+        let owner_id = sqlx::query!(
+            r#"
+SELECT owner_id AS "owner_id!"
+FROM channels
+WHERE id = $1
+    AND channel_type = 'GROUP'
+    AND is_deleted = FALSE
+            "#,
+            channel_id as i64
+        )
+        .fetch_optional(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch group {}: {}", channel_id, err);
+            error!(SERVER, "Failed to fetch group data")
+        })?
+        .ok_or_else(|| error!(NOT_FOUND))?
+        .owner_id as u64;
+        if !Self::has_member(channel_id, user_id, db).await? {
+            return Err(error!(FORBIDDEN));
+        }
+        Ok(owner_id)
+    }
+
+    pub async fn create<C: AsyncCommands>(
+        mut group: GroupChannelCreate,
+        owner_id: u64,
+        id_generator: &mut IdGenerator,
+        db: &mut PoolConnection<Postgres>,
+        cache: &mut C,
+    ) -> Result<Self, ErrorResponse> {
+        group.members.retain(|m| *m != owner_id);
+        group.validate()?;
+        for member in group.members.iter() {
+            if let Err(err) = User::get_unfiltered(*member, db).await {
+                return Err(if let ErrorResponse::NotFound { .. } = err {
+                    error!(
+                        VALIDATION,
+                        "members",
+                        format!("User {} doesn't exist", member)
+                    )
+                } else {
+                    err
+                });
+            }
+        }
+        if let Some(icon) = group.icon {
+            if File::get(icon, "group-icons", &mut *db).await.is_none() {
+                return Err(error!(
+                    VALIDATION,
+                    "icon",
+                    "The group's icon must be a valid file that exists in the group-icons bucket"
+                ));
+            }
+        }
+
+        let id = id_generator.generate();
+        let mut transaction = db.begin().await.map_err(|err| {
+            log::error!("Couldn't start group create transaction: {}", err);
+            error!(SERVER, "Failed to create group")
+        })?;
+        sqlx::query!(
+            "
+INSERT INTO channels(id, owner_id, channel_type, name, icon, topic)
+VALUES($1, $2, 'GROUP', $3, $4, $5)
+            ",
+            id as i64,
+            owner_id as i64,
+            group.name,
+            group.icon.map(|i| i as i64),
+            group.topic,
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't create group for {}: {}", owner_id, err);
+            error!(SERVER, "Failed to create group")
+        })?;
+        let member_ids: Vec<i64> = group
+            .members
+            .iter()
+            .chain([owner_id].iter())
+            .map(|m| *m as i64)
+            .collect();
+        sqlx::query!(
+            "
+INSERT INTO channel_members(id, channel_id)
+SELECT member_id, $2
+FROM UNNEST($1::BIGINT[]) AS member_id
+            ",
+            &member_ids,
+            id as i64,
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't add members to group {}: {}", id, err);
+            error!(SERVER, "Failed to create group")
+        })?;
+        transaction.commit().await.map_err(|err| {
+            log::error!("Couldn't commit group create transaction: {}", err);
+            error!(SERVER, "Failed to create group")
+        })?;
+
+        Self::populate(id, owner_id, group.name, group.icon, group.topic, db, cache).await
+    }
+
+    /// Edit a group channel.
+    ///
+    /// Returns the changes that were made to it.
+    pub async fn edit(
+        mut edit: GroupChannelEdit,
+        channel_id: u64,
+        user_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<GroupChannelEdit, ErrorResponse> {
+        edit.validate()?;
+        let owner_id = Self::get_owner_id(channel_id, user_id, db).await?;
+
+        if let Some(new_owner_id) = edit.owner_id {
+            if owner_id != user_id {
+                return Err(error!(FORBIDDEN));
+            }
+            if !Self::has_member(channel_id, new_owner_id, db).await? {
+                return Err(error!(
+                    VALIDATION,
+                    "owner_id", "The group's new owner must be a member of it"
+                ));
+            }
+        }
+        if let Some(Some(icon)) = edit.icon {
+            if File::get(icon, "group-icons", &mut *db).await.is_none() {
+                return Err(error!(
+                    VALIDATION,
+                    "icon",
+                    "The group's icon must be a valid file that exists in the group-icons bucket"
+                ));
+            }
+        }
+        if let Some(ref mut topic) = edit.topic {
+            if *topic == Some("".to_string()) {
+                *topic = None;
+            }
+        }
+
+        let mut transaction = db.begin().await.map_err(|err| {
+            log::error!("Couldn't start group edit transaction: {}", err);
+            error!(SERVER, "Failed to edit group")
+        })?;
+        if let Some(ref name) = edit.name {
+            sqlx::query!(
+                "
+UPDATE channels
+SET name = $1
+WHERE id = $2
+                ",
+                name,
+                channel_id as i64,
+            )
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| {
+                log::error!("Couldn't edit group {}'s name: {}", channel_id, err);
+                error!(SERVER, "Failed to edit group")
+            })?;
+        }
+        if let Some(icon) = edit.icon {
+            sqlx::query!(
+                "
+UPDATE channels
+SET icon = $1
+WHERE id = $2
+                ",
+                icon.map(|i| i as i64),
+                channel_id as i64,
+            )
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| {
+                log::error!("Couldn't edit group {}'s icon: {}", channel_id, err);
+                error!(SERVER, "Failed to edit group")
+            })?;
+        }
+        if let Some(ref topic) = edit.topic {
+            sqlx::query!(
+                "
+UPDATE channels
+SET topic = $1
+WHERE id = $2
+                ",
+                *topic,
+                channel_id as i64,
+            )
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| {
+                log::error!("Couldn't edit group {}'s topic: {}", channel_id, err);
+                error!(SERVER, "Failed to edit group")
+            })?;
+        }
+        if let Some(new_owner_id) = edit.owner_id {
+            sqlx::query!(
+                "
+UPDATE channels
+SET owner_id = $1
+WHERE id = $2
+                ",
+                new_owner_id as i64,
+                channel_id as i64,
+            )
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| {
+                log::error!(
+                    "Couldn't transfer group {}'s ownership: {}",
+                    channel_id,
+                    err
+                );
+                error!(SERVER, "Failed to edit group")
+            })?;
+        }
+        transaction.commit().await.map_err(|err| {
+            log::error!("Couldn't commit group edit transaction: {}", err);
+            error!(SERVER, "Failed to edit group")
+        })?;
+
+        Ok(edit)
+    }
+
+    /// Add a user to a group channel that the adding user is a member of.
+    ///
+    /// Returns the added user.
+    pub async fn add_member<C: AsyncCommands>(
+        channel_id: u64,
+        user_id: u64,
+        adder_id: u64,
+        db: &mut PoolConnection<Postgres>,
+        cache: &mut C,
+    ) -> Result<User, ErrorResponse> {
+        Self::get_owner_id(channel_id, adder_id, db).await?;
+        let user = User::get(user_id, None, db, cache).await?;
+        if Self::has_member(channel_id, user_id, db).await? {
+            return Err(error!(
+                VALIDATION,
+                "user", "The user is already a member of this group"
+            ));
+        }
+        let member_count = sqlx::query!(
+            "
+SELECT COUNT(id)
+FROM channel_members
+WHERE channel_id = $1
+            ",
+            channel_id as i64
+        )
+        .fetch_one(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Couldn't fetch group {}'s member count: {}",
+                channel_id,
+                err
+            );
+            error!(SERVER, "Failed to add group member")
+        })?
+        .count
+        .unwrap_or(0);
+        if member_count as usize >= MAX_GROUP_MEMBERS {
+            return Err(error!(
+                VALIDATION,
+                "members",
+                format!("Groups can't have more than {} members", MAX_GROUP_MEMBERS)
+            ));
+        }
+        sqlx::query!(
+            "
+INSERT INTO channel_members(id, channel_id)
+VALUES($1, $2)
+ON CONFLICT DO NOTHING
+            ",
+            user_id as i64,
+            channel_id as i64,
+        )
+        .execute(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Couldn't add user {} to group {}: {}",
+                user_id,
+                channel_id,
+                err
+            );
+            error!(SERVER, "Failed to add group member")
+        })?;
+        Ok(user)
+    }
+
+    /// Remove a member from a group channel, only its owner can remove other members.
+    ///
+    /// When the owner leaves, the ownership of the group is transferred to the member with the
+    /// oldest account and its ID is returned. Groups are deleted once their last member leaves.
+    pub async fn remove_member(
+        channel_id: u64,
+        user_id: u64,
+        remover_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<Option<u64>, ErrorResponse> {
+        let owner_id = Self::get_owner_id(channel_id, remover_id, db).await?;
+        if user_id != remover_id && owner_id != remover_id {
+            return Err(error!(FORBIDDEN));
+        }
+        if !Self::has_member(channel_id, user_id, db).await? {
+            return Err(error!(NOT_FOUND));
+        }
+
+        let mut transaction = db.begin().await.map_err(|err| {
+            log::error!("Couldn't start group member remove transaction: {}", err);
+            error!(SERVER, "Failed to remove group member")
+        })?;
+        sqlx::query!(
+            "
+DELETE FROM channel_members
+WHERE id = $1
+    AND channel_id = $2
+            ",
+            user_id as i64,
+            channel_id as i64,
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Couldn't remove user {} from group {}: {}",
+                user_id,
+                channel_id,
+                err
+            );
+            error!(SERVER, "Failed to remove group member")
+        })?;
+
+        let mut new_owner_id = None;
+        if user_id == owner_id {
+            // user IDs are snowflakes, the lowest one belongs to the oldest account
+            new_owner_id = sqlx::query!(
+                "
+SELECT id
+FROM channel_members
+WHERE channel_id = $1
+ORDER BY id
+LIMIT 1
+                ",
+                channel_id as i64,
+            )
+            .fetch_optional(&mut *transaction)
+            .await
+            .map_err(|err| {
+                log::error!("Couldn't fetch group {}'s new owner: {}", channel_id, err);
+                error!(SERVER, "Failed to remove group member")
+            })?
+            .map(|r| r.id as u64);
+            sqlx::query!(
+                "
+UPDATE channels
+SET owner_id = COALESCE($1, owner_id),
+    is_deleted = $1 IS NULL
+WHERE id = $2
+                ",
+                new_owner_id.map(|id| id as i64),
+                channel_id as i64,
+            )
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| {
+                log::error!(
+                    "Couldn't transfer group {}'s ownership: {}",
+                    channel_id,
+                    err
+                );
+                error!(SERVER, "Failed to remove group member")
+            })?;
+        }
+        transaction.commit().await.map_err(|err| {
+            log::error!("Couldn't commit group member remove transaction: {}", err);
+            error!(SERVER, "Failed to remove group member")
+        })?;
+
+        Ok(new_owner_id)
+    }
+}
@@ -1,14 +1,18 @@
 mod delete;
+mod direct_messages;
 mod edit;
 mod get;
+mod groups;
+mod overwrites;
+mod permissions;
 
 use sqlx::{pool::PoolConnection, postgres::PgRow, FromRow, Postgres, Row};
 
 use crate::{
     ids::IdGenerator,
     models::{
-        ChannelType, ErrorResponse, Sphere, SphereChannel, SphereChannelCreate, SphereChannelType,
-        TextChannel, VoiceChannel,
+        ChannelType, ErrorResponse, ForumChannel, Sphere, SphereChannel, SphereChannelCreate,
+        SphereChannelType, SphereType, TextChannel, VoiceChannel,
     },
 };
 
@@ -30,6 +34,14 @@ impl FromRow<'_, PgRow> for SphereChannel {
                 position: row.get::<i32, _>("position") as u32,
                 category_id: row.get::<i64, _>("category_id") as u64,
             })),
+            ChannelType::Forum => Ok(Self::Forum(ForumChannel {
+                id: row.get::<i64, _>("id") as u64,
+                sphere_id: row.get::<i64, _>("sphere_id") as u64,
+                name: row.get("name"),
+                topic: row.get("topic"),
+                position: row.get::<i32, _>("position") as u32,
+                category_id: row.get::<i64, _>("category_id") as u64,
+            })),
             _ => unreachable!(),
         }
     }
@@ -64,7 +76,7 @@ impl SphereChannel {
     ) -> Result<SphereChannel, ErrorResponse> {
         channel.validate()?;
 
-        Sphere::get_unpopulated(sphere_id, db)
+        let sphere = Sphere::get_unpopulated(sphere_id, db)
             .await
             .map_err(|err| {
                 if let ErrorResponse::NotFound { .. } = err {
@@ -73,6 +85,23 @@ impl SphereChannel {
                     err
                 }
             })?;
+        match (&channel.channel_type, &sphere.sphere_type) {
+            (SphereChannelType::Forum, SphereType::Chat) => {
+                return Err(error!(
+                    VALIDATION,
+                    "channel_type",
+                    "Forum channels can only be created in FORUM and HYBRID spheres"
+                ));
+            }
+            (SphereChannelType::Text | SphereChannelType::Voice, SphereType::Forum) => {
+                return Err(error!(
+                    VALIDATION,
+                    "channel_type",
+                    "Text and voice channels can only be created in CHAT and HYBRID spheres"
+                ));
+            }
+            _ => {}
+        }
 
         let category_id = channel.category_id.unwrap_or(sphere_id);
         let channel_count = sqlx::query!(
@@ -131,6 +160,14 @@ VALUES($1, $2, $3, $4, $5, $6, $7)
                 position: channel_count as u32,
                 category_id,
             }),
+            SphereChannelType::Forum => Self::Forum(ForumChannel {
+                id: channel_id,
+                sphere_id,
+                name: channel.name,
+                topic: channel.topic,
+                position: channel_count as u32,
+                category_id,
+            }),
         })
     }
 
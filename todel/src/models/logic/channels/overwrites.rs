@@ -0,0 +1,164 @@
+use sqlx::{pool::PoolConnection, postgres::PgRow, FromRow, Postgres, Row};
+
+use crate::models::{
+    ErrorResponse, PermissionOverwrite, PermissionOverwriteCreate, PermissionOverwriteType, Role,
+    SphereChannel, SpherePermission,
+};
+
+impl FromRow<'_, PgRow> for PermissionOverwrite {
+    fn from_row(row: &PgRow) -> sqlx::Result<Self> {
+        Ok(Self {
+            id: row.get::<i64, _>("target_id") as u64,
+            overwrite_type: row.get("overwrite_type"),
+            allow: row.get::<i64, _>("allow") as u64,
+            deny: row.get::<i64, _>("deny") as u64,
+        })
+    }
+}
+
+impl PermissionOverwriteCreate {
+    pub fn validate(&self) -> Result<(), ErrorResponse> {
+        if self.allow & !SpherePermission::CHANNEL != 0 {
+            return Err(error!(
+                VALIDATION,
+                "allow", "Only channel permissions can be overwritten"
+            ));
+        }
+        if self.deny & !SpherePermission::CHANNEL != 0 {
+            return Err(error!(
+                VALIDATION,
+                "deny", "Only channel permissions can be overwritten"
+            ));
+        }
+        if self.allow & self.deny != 0 {
+            return Err(error!(
+                VALIDATION,
+                "body", "A permission can't be both allowed and denied"
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl PermissionOverwrite {
+    /// Create or replace the overwrite of a role or member in a channel.
+    pub async fn set(
+        overwrite: PermissionOverwriteCreate,
+        sphere_id: u64,
+        channel_id: u64,
+        target_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<Self, ErrorResponse> {
+        overwrite.validate()?;
+
+        let channel = SphereChannel::get(channel_id, db).await?;
+        if channel.get_sphere_id() != sphere_id {
+            return Err(error!(NOT_FOUND));
+        }
+
+        match overwrite.overwrite_type {
+            PermissionOverwriteType::Role if target_id == sphere_id => {}
+            PermissionOverwriteType::Role => {
+                Role::get(target_id, sphere_id, db).await.map_err(|err| {
+                    if let ErrorResponse::NotFound { .. } = err {
+                        error!(VALIDATION, "id", "Role doesn't exist")
+                    } else {
+                        err
+                    }
+                })?;
+            }
+            PermissionOverwriteType::Member => {
+                if !SphereChannel::has_member(channel_id, target_id, db).await? {
+                    return Err(error!(VALIDATION, "id", "Member doesn't exist"));
+                }
+            }
+        }
+
+        sqlx::query(
+            "
+INSERT INTO channel_overwrites(channel_id, target_id, overwrite_type, allow, deny)
+VALUES($1, $2, $3, $4, $5)
+ON CONFLICT (channel_id, target_id)
+DO UPDATE SET overwrite_type = $3, allow = $4, deny = $5
+            ",
+        )
+        .bind(channel_id as i64)
+        .bind(target_id as i64)
+        .bind(overwrite.overwrite_type)
+        .bind(overwrite.allow as i64)
+        .bind(overwrite.deny as i64)
+        .execute(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Couldn't set overwrite for {} in channel {}: {}",
+                target_id,
+                channel_id,
+                err
+            );
+            error!(SERVER, "Failed to set permission overwrite")
+        })?;
+
+        Ok(Self {
+            id: target_id,
+            overwrite_type: overwrite.overwrite_type,
+            allow: overwrite.allow,
+            deny: overwrite.deny,
+        })
+    }
+
+    pub async fn get_all(
+        channel_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<Vec<Self>, ErrorResponse> {
+        sqlx::query_as(
+            "
+SELECT *
+FROM channel_overwrites
+WHERE channel_id = $1
+            ",
+        )
+        .bind(channel_id as i64)
+        .fetch_all(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Couldn't fetch permission overwrites for channel {}: {}",
+                channel_id,
+                err
+            );
+            error!(SERVER, "Failed to get permission overwrites")
+        })
+    }
+
+    pub async fn delete(
+        channel_id: u64,
+        target_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<(), ErrorResponse> {
+        let result = sqlx::query!(
+            "
+DELETE FROM channel_overwrites
+WHERE channel_id = $1
+    AND target_id = $2
+            ",
+            channel_id as i64,
+            target_id as i64,
+        )
+        .execute(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Couldn't delete overwrite for {} in channel {}: {}",
+                target_id,
+                channel_id,
+                err
+            );
+            error!(SERVER, "Failed to delete permission overwrite")
+        })?;
+        if result.rows_affected() == 0 {
+            return Err(error!(NOT_FOUND));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+use sqlx::{pool::PoolConnection, Postgres};
+
+use crate::models::{
+    Channel, ChannelType, ErrorResponse, GroupChannel, Member, PermissionOverwrite,
+    PermissionOverwriteType, Sphere, SphereChannel, SpherePermission,
+};
+
+impl SphereChannel {
+    /// Resolve a user's permissions inside of a channel.
+    ///
+    /// This applies the channel's permission overwrites on top of the user's sphere permissions,
+    /// members with the `ADMINISTRATOR` permission bypass overwrites entirely.
+    pub async fn get_member_permissions(
+        channel_id: u64,
+        user_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<u64, ErrorResponse> {
+        let channel = Self::get(channel_id, db).await?;
+        let sphere = Sphere::get_unpopulated(channel.get_sphere_id(), db).await?;
+        let mut permissions = sphere.get_member_permissions(user_id, db).await?;
+        if SpherePermission::Administrator.is_set(permissions) {
+            return Ok(permissions);
+        }
+
+        let overwrites = PermissionOverwrite::get_all(channel_id, db).await?;
+        if overwrites.is_empty() {
+            return Ok(permissions);
+        }
+        let roles = Member::get_roles(user_id, sphere.id, db).await?;
+
+        if let Some(everyone) = overwrites
+            .iter()
+            .find(|o| o.overwrite_type == PermissionOverwriteType::Role && o.id == sphere.id)
+        {
+            permissions = (permissions & !everyone.deny) | everyone.allow;
+        }
+        let (allow, deny) = overwrites
+            .iter()
+            .filter(|o| o.overwrite_type == PermissionOverwriteType::Role && roles.contains(&o.id))
+            .fold((0, 0), |(allow, deny), o| (allow | o.allow, deny | o.deny));
+        permissions = (permissions & !deny) | allow;
+        if let Some(member) = overwrites
+            .iter()
+            .find(|o| o.overwrite_type == PermissionOverwriteType::Member && o.id == user_id)
+        {
+            permissions = (permissions & !member.deny) | member.allow;
+        }
+
+        Ok(permissions)
+    }
+
+    /// Make sure a user can view a channel and has a permission inside of it, returning a
+    /// forbidden error otherwise.
+    pub async fn require_permission(
+        channel_id: u64,
+        user_id: u64,
+        permission: SpherePermission,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<(), ErrorResponse> {
+        let permissions = Self::get_member_permissions(channel_id, user_id, db).await?;
+        if SpherePermission::ViewChannels.is_set(permissions) && permission.is_set(permissions) {
+            Ok(())
+        } else {
+            Err(error!(FORBIDDEN))
+        }
+    }
+
+    /// Check whether a user can view a channel.
+    pub async fn can_view(
+        channel_id: u64,
+        user_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<bool, ErrorResponse> {
+        match Self::require_permission(channel_id, user_id, SpherePermission::ViewChannels, db)
+            .await
+        {
+            Ok(()) => Ok(true),
+            Err(ErrorResponse::Forbidden { .. }) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+impl Channel {
+    /// Resolve a user's permissions inside of any kind of channel.
+    ///
+    /// Members of direct message and group channels get the
+    /// [`SpherePermission::PRIVATE_CHANNEL`] permissions, sphere channels are resolved using
+    /// [`SphereChannel::get_member_permissions`].
+    pub async fn get_member_permissions(
+        channel_id: u64,
+        user_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<u64, ErrorResponse> {
+        let channel = sqlx::query!(
+            r#"
+SELECT channel_type AS "channel_type: ChannelType", owner_id, recipient_id
+FROM channels
+WHERE id = $1
+    AND is_deleted = FALSE
+            "#,
+            channel_id as i64
+        )
+        .fetch_optional(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch channel data {}: {}", channel_id, err);
+            error!(SERVER, "Failed to fetch channel data")
+        })?
+        .ok_or_else(|| error!(NOT_FOUND))?;
+        match channel.channel_type {
+            ChannelType::Direct => {
+                let user_id = Some(user_id as i64);
+                if channel.owner_id == user_id || channel.recipient_id == user_id {
+                    Ok(SpherePermission::PRIVATE_CHANNEL)
+                } else {
+                    Err(error!(FORBIDDEN))
+                }
+            }
+            ChannelType::Group => {
+                if GroupChannel::has_member(channel_id, user_id, db).await? {
+                    Ok(SpherePermission::PRIVATE_CHANNEL)
+                } else {
+                    Err(error!(FORBIDDEN))
+                }
+            }
+            _ => SphereChannel::get_member_permissions(channel_id, user_id, db).await,
+        }
+    }
+
+    /// Make sure a user can view a channel and has a permission inside of it, returning a
+    /// forbidden error otherwise.
+    pub async fn require_permission(
+        channel_id: u64,
+        user_id: u64,
+        permission: SpherePermission,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<(), ErrorResponse> {
+        let permissions = Self::get_member_permissions(channel_id, user_id, db).await?;
+        if SpherePermission::ViewChannels.is_set(permissions) && permission.is_set(permissions) {
+            Ok(())
+        } else {
+            Err(error!(FORBIDDEN))
+        }
+    }
+
+    /// Check whether a user can view a channel.
+    pub async fn can_view(
+        channel_id: u64,
+        user_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<bool, ErrorResponse> {
+        match Self::require_permission(channel_id, user_id, SpherePermission::ViewChannels, db)
+            .await
+        {
+            Ok(()) => Ok(true),
+            Err(ErrorResponse::Forbidden { .. }) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+}
@@ -3,7 +3,7 @@ use std::{collections::HashMap, fs};
 use sqlx::{pool::PoolConnection, Postgres};
 
 use crate::models::{
-    Emoji, ErrorResponse, Message, Reaction, ReactionEmoji, ReactionEmojiReference,
+    Emoji, ErrorResponse, Member, Message, Reaction, ReactionEmoji, ReactionEmojiReference,
 };
 
 impl ReactionEmojiReference {
@@ -39,6 +39,9 @@ impl Message {
         user_id: u64,
         db: &mut PoolConnection<Postgres>,
     ) -> Result<ReactionEmoji, ErrorResponse> {
+        if let Some(sphere_id) = self.channel.get_sphere_id() {
+            Member::require_not_timed_out(user_id, sphere_id, db).await?;
+        }
         let reaction = self
             .reactions
             .iter_mut()
@@ -1,9 +1,20 @@
-use sqlx::{pool::PoolConnection, Postgres};
+use sqlx::{pool::PoolConnection, Acquire, Postgres};
 
-use crate::models::{Emoji, ErrorResponse};
+use crate::models::{AuditLogActor, AuditLogChanges, AuditLogEntry, Emoji, ErrorResponse};
 
 impl Emoji {
-    pub async fn delete(self, db: &mut PoolConnection<Postgres>) -> Result<(), ErrorResponse> {
+    pub async fn delete(
+        self,
+        actor: AuditLogActor<'_>,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<(), ErrorResponse> {
+        actor.validate()?;
+
+        let mut transaction = db.begin().await.map_err(|err| {
+            log::error!("Couldn't start emoji delete transaction: {}", err);
+            error!(SERVER, "Failed to delete emoji")
+        })?;
+
         sqlx::query!(
             "
             UPDATE emojis
@@ -12,7 +23,7 @@ impl Emoji {
             ",
             self.id as i64
         )
-        .execute(&mut **db)
+        .execute(&mut *transaction)
         .await
         .map_err(|err| {
             log::error!(
@@ -22,6 +33,20 @@ impl Emoji {
             );
             error!(SERVER, "Failed to delete emoji")
         })?;
+
+        AuditLogEntry::create(
+            self.sphere_id,
+            self.id,
+            AuditLogChanges::EmojiDelete { before: self },
+            actor,
+            &mut transaction,
+        )
+        .await?;
+
+        transaction.commit().await.map_err(|err| {
+            log::error!("Couldn't commit emoji delete transaction: {}", err);
+            error!(SERVER, "Failed to delete emoji")
+        })?;
         Ok(())
     }
 
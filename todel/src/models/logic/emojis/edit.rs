@@ -1,6 +1,8 @@
-use sqlx::{pool::PoolConnection, Postgres};
+use sqlx::{pool::PoolConnection, Acquire, Postgres};
 
-use crate::models::{Emoji, EmojiEdit, ErrorResponse};
+use crate::models::{
+    AuditLogActor, AuditLogChanges, AuditLogEntry, Emoji, EmojiEdit, ErrorResponse,
+};
 
 impl EmojiEdit {
     pub fn validate(&self) -> Result<(), ErrorResponse> {
@@ -18,8 +20,16 @@ impl Emoji {
     pub async fn edit(
         &mut self,
         edit: &EmojiEdit,
+        actor: AuditLogActor<'_>,
         db: &mut PoolConnection<Postgres>,
     ) -> Result<(), ErrorResponse> {
+        actor.validate()?;
+
+        let mut transaction = db.begin().await.map_err(|err| {
+            log::error!("Couldn't start emoji edit transaction: {}", err);
+            error!(SERVER, "Failed to edit emoji")
+        })?;
+
         sqlx::query!(
             "
             UPDATE emojis
@@ -30,12 +40,33 @@ impl Emoji {
             edit.name,
             self.id as i64
         )
-        .execute(&mut **db)
+        .execute(&mut *transaction)
         .await
         .map_err(|err| {
             log::error!("Failed to edit emoji {}: {}", self.id, err);
             error!(SERVER, "Failed to edit emoji")
         })?;
+
+        AuditLogEntry::create(
+            self.sphere_id,
+            self.id,
+            AuditLogChanges::EmojiUpdate {
+                before: EmojiEdit {
+                    name: self.name.clone(),
+                },
+                after: edit.clone(),
+            },
+            actor,
+            &mut transaction,
+        )
+        .await?;
+
+        transaction.commit().await.map_err(|err| {
+            log::error!("Couldn't commit emoji edit transaction: {}", err);
+            error!(SERVER, "Failed to edit emoji")
+        })?;
+
+        self.name = edit.name.clone();
         Ok(())
     }
 }
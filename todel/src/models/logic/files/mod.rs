@@ -0,0 +1,1090 @@
+#[cfg(feature = "http")]
+mod strip;
+
+#[cfg(feature = "http")]
+use std::{
+    cmp, env,
+    io::{self, Cursor},
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, SystemTime},
+};
+
+#[cfg(feature = "http")]
+use image::{
+    codecs::gif::{GifDecoder, GifEncoder, Repeat},
+    imageops::{self},
+    AnimationDecoder, DynamicImage, Frame, ImageDecoder, ImageFormat,
+};
+#[cfg(feature = "http")]
+use ravif::{Encoder, Img, RGBA8};
+#[cfg(feature = "http")]
+use rocket::{
+    fs::TempFile,
+    http::{ContentType, Header, Status},
+    response::{self, Responder},
+    FromForm, Request, Response,
+};
+use sqlx::{pool::PoolConnection, Postgres};
+#[cfg(feature = "http")]
+use tokio::fs;
+
+use crate::models::{ErrorResponse, FileData, FileMetadata};
+#[cfg(feature = "http")]
+use crate::{
+    http::{format_http_date, ByteRange, FetchHeaders},
+    ids::{IdGenerator, ELUDRIS_EPOCH},
+    models::ThumbnailFormat,
+    storage::{Storage, StorageObject},
+    Conf,
+};
+
+use crate::models::File;
+#[cfg(feature = "http")]
+use strip::{strip_image, strip_video};
+
+/// The buckets whose images can be resized and converted.
+#[cfg(feature = "http")]
+pub const RESIZABLE_BUCKETS: [&str; 9] = [
+    "attachments",
+    "avatars",
+    "banners",
+    "sphere-icons",
+    "sphere-banners",
+    "member-avatars",
+    "member-banners",
+    "emojis",
+    "group-icons",
+];
+
+#[cfg(feature = "http")]
+#[derive(Debug)]
+pub struct FetchResponse<'a> {
+    pub body: FetchBody,
+    pub disposition: Header<'a>,
+    pub content_type: ContentType,
+    /// The file's `ETag`, not present for files which aren't tracked in the database.
+    pub etag: Option<String>,
+    /// When the file was uploaded, not present for files which aren't tracked in the database.
+    pub last_modified: Option<SystemTime>,
+}
+
+/// What gets sent back for a file fetch, depending on the client's [`FetchHeaders`].
+#[cfg(feature = "http")]
+#[derive(Debug)]
+pub enum FetchBody {
+    /// The whole file.
+    Full(StorageObject),
+    /// The part of the file the client asked for with a `Range` header.
+    Partial(StorageObject),
+    /// The client's cached copy of the file is still fresh.
+    NotModified,
+    /// The range the client asked for lies outside of the file, which is `size` bytes long.
+    RangeNotSatisfiable { size: u64 },
+}
+
+#[cfg(feature = "http")]
+impl FetchBody {
+    /// Read the object at `path` from the storage, honouring the client's conditional and
+    /// `Range` headers.
+    pub async fn fetch(
+        path: &str,
+        headers: &FetchHeaders,
+        etag: Option<&str>,
+        last_modified: Option<SystemTime>,
+        storage: &Storage,
+    ) -> io::Result<Self> {
+        if headers.is_fresh(etag, last_modified) {
+            return Ok(Self::NotModified);
+        }
+        let (start, end) = match headers.byte_range(etag, last_modified) {
+            None => return Ok(Self::Full(storage.get(path).await?)),
+            Some(ByteRange::FromStart { start, end }) => (start, end),
+            Some(ByteRange::Suffix { length }) => {
+                (storage.size(path).await?.saturating_sub(length), None)
+            }
+        };
+        match storage.get_range(path, start, end).await {
+            Ok(object) => Ok(Self::Partial(object)),
+            Err(err) if err.kind() == io::ErrorKind::InvalidInput => {
+                Ok(Self::RangeNotSatisfiable {
+                    size: storage.size(path).await?,
+                })
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+#[cfg(feature = "http")]
+impl<'r, 'a> Responder<'r, 'static> for FetchResponse<'a> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let mut response = Response::build();
+        response
+            .header(self.content_type)
+            .header(Header::new(
+                self.disposition.name.to_string(),
+                self.disposition.value.to_string(),
+            ))
+            .raw_header("Accept-Ranges", "bytes");
+        if let Some(etag) = self.etag {
+            response.raw_header("ETag", etag);
+        }
+        if let Some(last_modified) = self.last_modified {
+            response.raw_header("Last-Modified", format_http_date(last_modified));
+        }
+        match self.body {
+            FetchBody::Full(object) => {
+                response.merge(object.respond_to(req)?);
+            }
+            FetchBody::Partial(object) => {
+                response
+                    .status(Status::PartialContent)
+                    .raw_header(
+                        "Content-Range",
+                        format!(
+                            "bytes {}-{}/{}",
+                            object.offset,
+                            object.offset + object.size - 1,
+                            object.total_size
+                        ),
+                    )
+                    .merge(object.respond_to(req)?);
+            }
+            FetchBody::NotModified => {
+                response.status(Status::NotModified);
+            }
+            FetchBody::RangeNotSatisfiable { size } => {
+                response
+                    .status(Status::RangeNotSatisfiable)
+                    .raw_header("Content-Range", format!("bytes */{}", size));
+            }
+        }
+        response.ok()
+    }
+}
+
+/// How a file should be transformed before it's sent.
+#[cfg(feature = "http")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FileVariant {
+    /// The size of the square the image gets resized to fit in, this has to be one of the
+    /// instance's thumbnail sizes.
+    pub size: Option<u32>,
+    /// The format the resized image gets converted to, the original format is kept if `None`.
+    pub format: Option<ThumbnailFormat>,
+}
+
+#[cfg(feature = "http")]
+impl ThumbnailFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Webp => "webp",
+            Self::Avif => "avif",
+            Self::Png => "png",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Webp => "image/webp",
+            Self::Avif => "image/avif",
+            Self::Png => "image/png",
+        }
+    }
+}
+
+/// The data format for uploading a file.
+///
+/// This is a `multipart/form-data` form.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```sh
+/// curl \
+///   -F file=@trolley.mp4 \
+///   https://cdn.eludris.gay/attachments/
+/// ```
+#[cfg(feature = "http")]
+#[autodoc(category = "Files", hidden = true)]
+#[derive(Debug, FromForm)]
+pub struct FileUpload<'a> {
+    pub file: TempFile<'a>,
+}
+
+impl File {
+    #[cfg(feature = "http")]
+    pub async fn create<'a>(
+        mut file: TempFile<'a>,
+        bucket: String,
+        storage: &Storage,
+        conf: &Conf,
+        id_generator: &mut IdGenerator,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<FileData, ErrorResponse> {
+        if file.len() == 0 {
+            return Err(error!(
+                VALIDATION,
+                "file", "You cannot upload an empty file"
+            ));
+        }
+
+        let id = id_generator.generate();
+        // Files are processed on the local disk before being handed over to the storage backend
+        let path = env::temp_dir().join(format!("eludris-upload-{}", id));
+        let name = match file.raw_name() {
+            Some(name) => PathBuf::from(name.dangerous_unsafe_unsanitized_raw().as_str())
+                .file_name()
+                .map(|n| n.to_str().unwrap_or("attachment"))
+                .unwrap_or("attachment")
+                .to_string(),
+            None => "attachment".to_string(),
+        };
+        if name.is_empty() || name.len() > 256 {
+            return Err(error!(
+                VALIDATION,
+                "name", "Invalid file name. File name must be between 1 and 256 characters long"
+            ));
+        }
+        file.move_copy_to(&path).await.map_err(|err| {
+            log::error!(
+                "Couldn't save uploaded file {} with id {}: {}",
+                name,
+                id,
+                err
+            );
+            error!(SERVER, "Failed to upload file")
+        })?;
+
+        let strip_metadata = conf.effis.strip_metadata;
+        let processing_path = path.clone();
+        let processing_name = name.clone();
+        let processed = tokio::task::spawn_blocking(move || {
+            let path = processing_path;
+            let name = processing_name;
+            let data = std::fs::read(&path).map_err(|err| {
+                log::error!(
+                    "Couldn't read uploaded file {} with id {}: {}",
+                    name,
+                    id,
+                    err
+                );
+                error!(SERVER, "Failed to upload file")
+            })?;
+            let mut mime = tree_magic::from_u8(&data);
+            if mime == "application/x-riff" && name.ends_with(".webp") {
+                // tree magic bug
+                mime = "image/webp".to_string();
+            }
+            if !strip_metadata {
+                return Ok((mime, data));
+            }
+            let data = match mime.as_str() {
+                "image/gif" | "image/jpeg" | "image/png" | "image/webp" => {
+                    let stripped = strip_image(&data, &mime).map_err(|e| {
+                        log::error!(
+                            "Failed to strip image metadata on {} with id {}: {:?}",
+                            name,
+                            id,
+                            e
+                        );
+                        error!(SERVER, "Failed to strip file metadata")
+                    })?;
+                    std::fs::write(&path, &stripped).map_err(|e| {
+                        log::error!(
+                            "Failed to strip image metadata on {} while saving with id {}: {:?}",
+                            name,
+                            id,
+                            e
+                        );
+                        error!(SERVER, "Failed to strip file metadata")
+                    })?;
+                    stripped
+                }
+                "video/mp4" | "video/webm" | "video/quicktime" => strip_video(&path, &mime)
+                    .and_then(|_| std::fs::read(&path))
+                    .map_err(|e| {
+                        log::error!(
+                            "Failed to strip video metadata on {} with id {}: {:?}",
+                            name,
+                            id,
+                            e
+                        );
+                        error!(SERVER, "Failed to strip file metadata")
+                    })?,
+                _ => data,
+            };
+            Ok((mime, data))
+        })
+        .await
+        .unwrap();
+        let (mime, data) = match processed {
+            Ok(processed) => processed,
+            Err(err) => {
+                fs::remove_file(&path).await.unwrap();
+                return Err(err);
+            }
+        };
+
+        // Hashing the stripped file lets uploads which only differ in metadata be deduplicated
+        let hash = sha256::digest(&data[..]);
+        let file = if let Ok((file_id, content_type, width, height, duration, codec)) =
+            sqlx::query!(
+                "
+SELECT file_id, content_type, width, height, duration, codec
+FROM files
+WHERE hash = $1
+AND bucket = $2
+            ",
+                hash,
+                bucket,
+            )
+            .fetch_one(&mut **db)
+            .await
+            .map(|f| {
+                (
+                    f.file_id,
+                    f.content_type,
+                    f.width,
+                    f.height,
+                    f.duration,
+                    f.codec,
+                )
+            }) {
+            fs::remove_file(path).await.unwrap();
+            sqlx::query!(
+                "
+INSERT INTO files(id, file_id, name, content_type, hash, bucket, width, height, duration, codec)
+VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                ",
+                id as i64,
+                file_id as i64,
+                name,
+                content_type,
+                hash,
+                bucket,
+                width as Option<i32>,
+                height as Option<i32>,
+                duration,
+                codec,
+            )
+            .execute(&mut **db)
+            .await
+            .unwrap();
+
+            Self {
+                id,
+                file_id: file_id as u64,
+                name,
+                content_type,
+                hash,
+                bucket,
+                width: width.map(|s| s as usize),
+                height: height.map(|s| s as usize),
+                duration,
+                codec,
+            }
+        } else {
+            let processing_path = path.clone();
+            let file = tokio::task::spawn_blocking(move || {
+                let path = processing_path;
+                let mut mime = mime;
+                let mut poster = None;
+                let mut audio_only = false;
+                let (width, height, duration, codec) = match mime.as_str() {
+                    "image/gif" | "image/jpeg" | "image/png" | "image/webp" => {
+                        imagesize::blob_size(&data)
+                            .map(|d| (Some(d.width), Some(d.height), None, None))
+                            .unwrap_or((None, None, None, None))
+                    }
+                    "video/mp4" | "video/webm" | "video/quicktime" => {
+                        if &bucket != "attachments" {
+                            return Err(error!(
+                                VALIDATION,
+                                "content_type",
+                                "Non attachment buckets can only have images and gifs"
+                            ));
+                        };
+
+                        let media = probe_media(&path).map_err(|e| {
+                            log::error!(
+                                "Failed to strip video metadata on {} with id {}: {:?}",
+                                name,
+                                id,
+                                e
+                            );
+                            error!(SERVER, "Failed to strip file metadata")
+                        })?;
+                        if media.has_video {
+                            // A missing poster only means that clients can't preview the video
+                            poster = extract_poster(&path, media.duration)
+                                .map_err(|e| {
+                                    log::warn!(
+                                        "Failed to extract poster frame from {} with id {}: {}",
+                                        name,
+                                        id,
+                                        e
+                                    );
+                                })
+                                .ok();
+                        } else {
+                            audio_only = true;
+                        }
+                        (media.width, media.height, media.duration, media.codec)
+                    }
+                    _ if mime.starts_with("audio/") => {
+                        if &bucket != "attachments" {
+                            return Err(error!(
+                                VALIDATION,
+                                "content_type",
+                                "Non attachment buckets can only have images and gifs"
+                            ));
+                        };
+
+                        let media = probe_media(&path).map_err(|e| {
+                            log::error!(
+                                "Failed to read audio metadata on {} with id {}: {:?}",
+                                name,
+                                id,
+                                e
+                            );
+                            error!(SERVER, "Failed to read file metadata")
+                        })?;
+                        (None, None, media.duration, media.codec)
+                    }
+                    _ => {
+                        if &bucket != "attachments" {
+                            return Err(error!(
+                                VALIDATION,
+                                "content_type",
+                                "Non attachment buckets can only have images and gifs"
+                            ));
+                        };
+
+                        (None, None, None, None)
+                    }
+                };
+                // Videos without a video stream are just audio in a video container
+                if audio_only {
+                    mime = mime.replacen("video/", "audio/", 1);
+                }
+                Ok((
+                    Self {
+                        id,
+                        file_id: id,
+                        name,
+                        content_type: mime,
+                        hash,
+                        bucket,
+                        width,
+                        height,
+                        duration,
+                        codec,
+                    },
+                    poster,
+                ))
+            })
+            .await
+            .unwrap();
+            let (file, poster) = match file {
+                Ok(file) => file,
+                Err(err) => {
+                    fs::remove_file(&path).await.unwrap();
+                    return Err(err);
+                }
+            };
+            let processed = fs::read(&path).await.map_err(|err| {
+                log::error!(
+                    "Couldn't read processed file {} with id {}: {}",
+                    file.name,
+                    id,
+                    err
+                );
+                error!(SERVER, "Failed to upload file")
+            })?;
+            fs::remove_file(&path).await.unwrap();
+            storage
+                .put(&file.storage_path(), processed)
+                .await
+                .map_err(|err| {
+                    log::error!("Couldn't store file {} with id {}: {}", file.name, id, err);
+                    error!(SERVER, "Failed to upload file")
+                })?;
+            if let Some(poster) = poster {
+                if let Err(err) = storage.put(&file.thumbnail_storage_path(), poster).await {
+                    log::warn!(
+                        "Couldn't store poster frame of file {} with id {}: {}",
+                        file.name,
+                        id,
+                        err
+                    );
+                }
+            }
+            sqlx::query!(
+                "
+INSERT INTO files(id, file_id, name, content_type, hash, bucket, width, height, duration, codec)
+VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                ",
+                file.id as i64,
+                file.id as i64,
+                file.name,
+                file.content_type,
+                file.hash,
+                file.bucket,
+                file.width.map(|s| s as i32),
+                file.height.map(|s| s as i32),
+                file.duration,
+                file.codec,
+            )
+            .execute(&mut **db)
+            .await
+            .unwrap();
+
+            file
+        };
+
+        Ok(file.get_file_data())
+    }
+
+    /// The path of the file's content in the [`Storage`].
+    #[cfg(feature = "http")]
+    pub fn storage_path(&self) -> String {
+        format!("{}/{}", self.bucket, self.file_id)
+    }
+
+    /// The path of the poster frame extracted from the file in the [`Storage`].
+    #[cfg(feature = "http")]
+    fn thumbnail_storage_path(&self) -> String {
+        format!("{}-thumbnail", self.storage_path())
+    }
+
+    /// When the file was uploaded.
+    #[cfg(feature = "http")]
+    fn last_modified(&self) -> SystemTime {
+        // IDs start with the amount of seconds since the Eludris epoch
+        *ELUDRIS_EPOCH + Duration::from_secs(self.file_id >> 16)
+    }
+
+    /// Get the storage path of a variant of the file, resizing the file if that variant hasn't
+    /// been requested before.
+    #[cfg(feature = "http")]
+    async fn variant_storage_path(
+        &self,
+        variant: FileVariant,
+        storage: &Storage,
+        conf: &Conf,
+    ) -> Result<String, ErrorResponse> {
+        let original_path = self.storage_path();
+        let size = match variant.size {
+            Some(size) => size,
+            None if variant.format.is_some() => {
+                return Err(error!(
+                    VALIDATION,
+                    "format", "Images can only be converted when they're resized"
+                ))
+            }
+            None => return Ok(original_path),
+        };
+        if !matches!(self.get_file_data().metadata, FileMetadata::Image { .. }) {
+            return Err(error!(VALIDATION, "bucket", "Only images support resizing"));
+        } else if !RESIZABLE_BUCKETS.contains(&self.bucket.as_str()) {
+            return Err(error!(
+                VALIDATION,
+                "bucket", "This bucket doesn't support resizing"
+            ));
+        } else if !conf.effis.thumbnail_sizes.contains(&size) {
+            return Err(error!(VALIDATION, "size", "Unsupported size"));
+        }
+        let path = match variant.format {
+            Some(format) => format!("{}-{}.{}", original_path, size, format.extension()),
+            None => format!("{}-{}", original_path, size),
+        };
+        if !storage.exists(&path).await.map_err(|e| {
+            log::error!(
+                "Could not fetch file {} with id {}: {:?}",
+                self.name,
+                self.id,
+                e
+            );
+            error!(SERVER, "Error fetching file")
+        })? {
+            let original = match storage.get(&original_path).await {
+                Ok(object) => object.into_bytes().await,
+                Err(err) => Err(err),
+            }
+            .map_err(|e| {
+                log::error!(
+                    "Failed to open file for resizing at {}: {}",
+                    original_path,
+                    e
+                );
+                error!(SERVER, "Failed to resize file")
+            })?;
+            let content_type = self.content_type.clone();
+            let resized_path = path.clone();
+            let resized = tokio::task::spawn_blocking(move || {
+                resize_image(original, &content_type, size, variant.format, &resized_path)
+            })
+            .await
+            .unwrap()?;
+            storage.put(&path, resized).await.map_err(|e| {
+                log::error!("Failed to write file at {}: {}", path, e);
+                error!(SERVER, "Failed to resize file")
+            })?;
+        }
+        Ok(path)
+    }
+
+    #[cfg(feature = "http")]
+    pub async fn open_file(
+        &self,
+        variant: FileVariant,
+        storage: &Storage,
+        conf: &Conf,
+    ) -> Result<StorageObject, ErrorResponse> {
+        let path = self.variant_storage_path(variant, storage, conf).await?;
+        storage.get(&path).await.map_err(|e| {
+            log::error!(
+                "Could not fetch file {} with id {}: {:?}",
+                self.name,
+                self.id,
+                e
+            );
+            error!(SERVER, "Error fetching file")
+        })
+    }
+
+    pub async fn get<'a>(
+        id: u64,
+        bucket: &'a str,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Option<Self> {
+        sqlx::query!(
+            "
+SELECT *
+FROM files
+WHERE id = $1
+AND bucket = $2
+            ",
+            id as i64,
+            bucket,
+        )
+        .fetch_one(&mut **db)
+        .await
+        .map(|r| Self {
+            id: r.id as u64,
+            file_id: r.file_id as u64,
+            name: r.name,
+            content_type: r.content_type,
+            hash: r.hash,
+            bucket: r.bucket,
+            width: r.width.map(|s| s as usize),
+            height: r.height.map(|s| s as usize),
+            duration: r.duration,
+            codec: r.codec,
+        })
+        .ok()
+    }
+
+    #[cfg(feature = "http")]
+    pub async fn fetch_file<'a>(
+        id: u64,
+        bucket: &'a str,
+        variant: FileVariant,
+        headers: &FetchHeaders,
+        storage: &Storage,
+        conf: &Conf,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<FetchResponse<'a>, ErrorResponse> {
+        Self::get(id, bucket, db)
+            .await
+            .ok_or_else(|| error!(NOT_FOUND))?
+            .fetch(variant, "inline", headers, storage, conf)
+            .await
+    }
+
+    #[cfg(feature = "http")]
+    pub async fn fetch_file_download<'a>(
+        id: u64,
+        bucket: &'a str,
+        variant: FileVariant,
+        headers: &FetchHeaders,
+        storage: &Storage,
+        conf: &Conf,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<FetchResponse<'a>, ErrorResponse> {
+        Self::get(id, bucket, db)
+            .await
+            .ok_or_else(|| error!(NOT_FOUND))?
+            .fetch(variant, "attachment", headers, storage, conf)
+            .await
+    }
+
+    #[cfg(feature = "http")]
+    async fn fetch<'a>(
+        &self,
+        variant: FileVariant,
+        disposition: &str,
+        headers: &FetchHeaders,
+        storage: &Storage,
+        conf: &Conf,
+    ) -> Result<FetchResponse<'a>, ErrorResponse> {
+        let path = self.variant_storage_path(variant, storage, conf).await?;
+        // Variants are different representations of the file so they need their own tag
+        let etag = match (variant.size, variant.format) {
+            (Some(size), Some(format)) => {
+                format!("\"{}-{}.{}\"", self.hash, size, format.extension())
+            }
+            (Some(size), None) => format!("\"{}-{}\"", self.hash, size),
+            _ => format!("\"{}\"", self.hash),
+        };
+        let last_modified = self.last_modified();
+        let body = FetchBody::fetch(&path, headers, Some(&etag), Some(last_modified), storage)
+            .await
+            .map_err(|e| {
+                log::error!(
+                    "Could not fetch file {} with id {}: {:?}",
+                    self.name,
+                    self.id,
+                    e
+                );
+                error!(SERVER, "Error fetching file")
+            })?;
+        let (name, content_type) = match variant.format {
+            Some(format) => (
+                PathBuf::from(&self.name)
+                    .with_extension(format.extension())
+                    .to_string_lossy()
+                    .to_string(),
+                format.content_type(),
+            ),
+            None => (self.name.clone(), self.content_type.as_str()),
+        };
+        Ok(FetchResponse {
+            body,
+            disposition: Header::new(
+                "Content-Disposition",
+                format!("{}; filename=\"{}\"", disposition, name),
+            ),
+            content_type: ContentType::parse_flexible(content_type).unwrap(),
+            etag: Some(etag),
+            last_modified: Some(last_modified),
+        })
+    }
+
+    /// Get the poster frame which was extracted from a video when it was uploaded.
+    #[cfg(feature = "http")]
+    pub async fn fetch_thumbnail<'a>(
+        id: u64,
+        bucket: &'a str,
+        headers: &FetchHeaders,
+        storage: &Storage,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<FetchResponse<'a>, ErrorResponse> {
+        let file = Self::get(id, bucket, db)
+            .await
+            .ok_or_else(|| error!(NOT_FOUND))?;
+        let etag = format!("\"{}-thumbnail\"", file.hash);
+        let last_modified = file.last_modified();
+        let body = match FetchBody::fetch(
+            &file.thumbnail_storage_path(),
+            headers,
+            Some(&etag),
+            Some(last_modified),
+            storage,
+        )
+        .await
+        {
+            Ok(body) => body,
+            // Only videos have poster frames
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Err(error!(NOT_FOUND)),
+            Err(err) => {
+                log::error!(
+                    "Could not fetch thumbnail of file {} with id {}: {:?}",
+                    file.name,
+                    file.id,
+                    err
+                );
+                return Err(error!(SERVER, "Error fetching file"));
+            }
+        };
+        Ok(FetchResponse {
+            body,
+            disposition: Header::new(
+                "Content-Disposition",
+                format!(
+                    "inline; filename=\"{}\"",
+                    PathBuf::from(&file.name).with_extension("jpg").display()
+                ),
+            ),
+            content_type: ContentType::JPEG,
+            etag: Some(etag),
+            last_modified: Some(last_modified),
+        })
+    }
+
+    pub async fn fetch_file_data<'a>(
+        id: u64,
+        bucket: &'a str,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<FileData, ErrorResponse> {
+        Self::get(id, bucket, db)
+            .await
+            .ok_or_else(|| error!(NOT_FOUND))
+            .map(|f| f.get_file_data())
+    }
+
+    pub fn get_file_data(&self) -> FileData {
+        let metadata = match self.content_type.as_ref() {
+            "image/gif" | "image/jpeg" | "image/png" | "image/webp" => {
+                if self.width.is_some() && self.height.is_some() {
+                    FileMetadata::Image {
+                        width: self.width,
+                        height: self.height,
+                    }
+                } else {
+                    FileMetadata::Other
+                }
+            }
+            "video/mp4" | "video/webm" | "video/quicktime" => {
+                if self.width.is_some() && self.height.is_some() {
+                    FileMetadata::Video {
+                        width: self.width,
+                        height: self.height,
+                        duration: self.duration,
+                        codec: self.codec.clone(),
+                    }
+                } else {
+                    FileMetadata::Other
+                }
+            }
+            _ if self.content_type.starts_with("audio/") => FileMetadata::Audio {
+                duration: self.duration,
+                codec: self.codec.clone(),
+            },
+            _ if self.content_type.starts_with("text") => FileMetadata::Text,
+            _ => FileMetadata::Other,
+        };
+
+        FileData {
+            id: self.id,
+            name: self.name.clone(),
+            bucket: self.bucket.clone(),
+            metadata,
+        }
+    }
+}
+
+/// What ffprobe found out about an audio or video file.
+#[cfg(feature = "http")]
+#[derive(Debug, Default)]
+struct MediaInfo {
+    width: Option<usize>,
+    height: Option<usize>,
+    duration: Option<f64>,
+    codec: Option<String>,
+    /// Whether the file has a video stream, cover art doesn't count.
+    has_video: bool,
+}
+
+#[cfg(feature = "http")]
+fn probe_media(path: &Path) -> Result<MediaInfo, ffprobe::FfProbeError> {
+    let probe = ffprobe::ffprobe(path)?;
+    // Audio files store their cover art as a single frame video stream
+    let video = probe
+        .streams
+        .iter()
+        .find(|s| s.codec_type.as_deref() == Some("video") && s.disposition.attached_pic == 0);
+    let audio = probe
+        .streams
+        .iter()
+        .find(|s| s.codec_type.as_deref() == Some("audio"));
+    let stream = video.or(audio);
+    Ok(MediaInfo {
+        width: video.and_then(|s| s.width).map(|w| w as usize),
+        height: video.and_then(|s| s.height).map(|h| h as usize),
+        duration: probe
+            .format
+            .duration
+            .as_deref()
+            .or_else(|| stream.and_then(|s| s.duration.as_deref()))
+            .and_then(|d| d.parse().ok()),
+        codec: stream
+            .and_then(|s| s.codec_name.clone())
+            .filter(|codec| codec.len() <= 32),
+        has_video: video.is_some(),
+    })
+}
+
+/// Extract a frame from a video as a JPEG, the frame is taken from a second in or from the
+/// middle of shorter videos so that it isn't a black intro frame.
+#[cfg(feature = "http")]
+fn extract_poster(path: &Path, duration: Option<f64>) -> io::Result<Vec<u8>> {
+    let timestamp = duration.map(|d| (d / 2.).min(1.)).unwrap_or(0.);
+    let output = Command::new("ffmpeg")
+        .args(["-v", "error", "-ss", &timestamp.to_string(), "-i"])
+        .arg(path)
+        .args([
+            "-frames:v",
+            "1",
+            "-f",
+            "image2pipe",
+            "-c:v",
+            "mjpeg",
+            "pipe:1",
+        ])
+        .output()?;
+    if !output.status.success() || output.stdout.is_empty() {
+        return Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(output.stdout)
+}
+
+/// Resize an image so that it fits within a `size` by `size` square and convert it to `format`,
+/// `path` is only used for logs.
+#[cfg(feature = "http")]
+fn resize_image(
+    original: Vec<u8>,
+    content_type: &str,
+    size: u32,
+    format: Option<ThumbnailFormat>,
+    path: &str,
+) -> Result<Vec<u8>, ErrorResponse> {
+    // Converted gifs lose their animation since only their first frame gets decoded
+    if content_type == "image/gif" && format.is_none() {
+        let decoder = GifDecoder::new(Cursor::new(&original)).map_err(|e| {
+            log::error!("Failed to open file for resizing at {}: {}", path, e);
+            error!(SERVER, "Failed to resize file")
+        })?;
+        let (width, height) = decoder.dimensions();
+        if width <= size && height <= size {
+            return Ok(original);
+        }
+        let mut target_width = size;
+        let mut target_height = size;
+        match width.cmp(&height) {
+            cmp::Ordering::Greater => {
+                target_height = (height as f32 * (size as f32 / width as f32)).round() as u32;
+            }
+            cmp::Ordering::Less => {
+                target_width = (width as f32 * (size as f32 / height as f32)).round() as u32;
+            }
+            cmp::Ordering::Equal => {}
+        }
+        let mut frames = vec![];
+        for frame in decoder.into_frames() {
+            let frame = frame.map_err(|e| {
+                log::error!("Failed to open frame for resizing at {}: {}", path, e);
+                error!(SERVER, "Failed to resize file")
+            })?;
+            let buffer = frame.buffer();
+            let resized = imageops::thumbnail(buffer, target_width, target_height);
+            frames.push(Frame::from_parts(
+                resized,
+                frame.left(),
+                frame.top(),
+                frame.delay(),
+            ));
+        }
+        let mut out = Cursor::new(vec![]);
+        {
+            let mut encoder = GifEncoder::new(&mut out);
+            encoder.set_repeat(Repeat::Infinite).unwrap();
+            encoder.encode_frames(frames).map_err(|e| {
+                log::error!(
+                    "Failed to build gif from frames for resizing at {}: {}",
+                    path,
+                    e
+                );
+                error!(SERVER, "Failed to resize file")
+            })?;
+        }
+        Ok(out.into_inner())
+    } else {
+        let source_format = ImageFormat::from_mime_type(content_type).unwrap();
+        let thumbnail = image::load_from_memory_with_format(&original, source_format)
+            .map_err(|e| {
+                log::error!("Failed to strip open file for resizing at {}: {}", path, e);
+                error!(SERVER, "Failed to resize file")
+            })?
+            .thumbnail(size, size);
+        let mut out = Cursor::new(vec![]);
+        match format {
+            None => thumbnail.write_to(&mut out, source_format),
+            Some(ThumbnailFormat::Png) => thumbnail.write_to(&mut out, ImageFormat::Png),
+            // The WebP encoder only supports 8 bit colours
+            Some(ThumbnailFormat::Webp) => {
+                DynamicImage::ImageRgba8(thumbnail.to_rgba8()).write_to(&mut out, ImageFormat::WebP)
+            }
+            Some(ThumbnailFormat::Avif) => return encode_avif(&thumbnail, path),
+        }
+        .map_err(|e| {
+            log::error!("Failed to write file at {}: {}", path, e);
+            error!(SERVER, "Failed to resize file")
+        })?;
+        Ok(out.into_inner())
+    }
+}
+
+#[cfg(feature = "http")]
+fn encode_avif(image: &DynamicImage, path: &str) -> Result<Vec<u8>, ErrorResponse> {
+    let image = image.to_rgba8();
+    let pixels: Vec<RGBA8> = image
+        .pixels()
+        .map(|pixel| RGBA8::new(pixel[0], pixel[1], pixel[2], pixel[3]))
+        .collect();
+    Encoder::new()
+        .with_quality(70.)
+        .with_speed(8)
+        .encode_rgba(Img::new(
+            &pixels[..],
+            image.width() as usize,
+            image.height() as usize,
+        ))
+        .map(|encoded| encoded.avif_file)
+        .map_err(|e| {
+            log::error!("Failed to encode avif at {}: {}", path, e);
+            error!(SERVER, "Failed to resize file")
+        })
+}
+
+#[cfg(feature = "http")]
+#[cfg(test)]
+mod tests {
+    use image::{ImageBuffer, Rgb};
+
+    use super::*;
+
+    #[test]
+    fn resize_and_convert() {
+        let mut original = Cursor::new(vec![]);
+        DynamicImage::ImageRgb8(ImageBuffer::from_pixel(600, 400, Rgb([255, 0, 0])))
+            .write_to(&mut original, ImageFormat::Png)
+            .unwrap();
+        let original = original.into_inner();
+
+        for (format, image_format) in [
+            (None, ImageFormat::Png),
+            (Some(ThumbnailFormat::Png), ImageFormat::Png),
+            (Some(ThumbnailFormat::Webp), ImageFormat::WebP),
+        ] {
+            let resized = resize_image(original.clone(), "image/png", 64, format, "test").unwrap();
+            let resized = image::load_from_memory_with_format(&resized, image_format).unwrap();
+            assert_eq!((resized.width(), resized.height()), (64, 43));
+        }
+
+        let avif = resize_image(
+            original,
+            "image/png",
+            64,
+            Some(ThumbnailFormat::Avif),
+            "test",
+        )
+        .unwrap();
+        assert_eq!(&avif[4..12], b"ftypavif");
+    }
+}
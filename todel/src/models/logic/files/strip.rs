@@ -0,0 +1,515 @@
+//! Lossless removal of EXIF, XMP, IPTC and container metadata from uploaded files.
+//!
+//! Images are stripped by dropping their metadata segments, chunks or blocks while copying the
+//! rest of the file byte for byte. The only time an image is re-encoded is when its EXIF
+//! orientation isn't the default one, since the orientation is lost along with the EXIF data it
+//! has to be applied to the pixels first.
+use std::{
+    io::{self, Cursor},
+    path::Path,
+    process::Command,
+};
+
+use image::{codecs::jpeg::JpegEncoder, DynamicImage, ImageFormat};
+
+/// Strip the metadata from an image of type `mime`.
+pub(super) fn strip_image(data: &[u8], mime: &str) -> io::Result<Vec<u8>> {
+    let (stripped, orientation) = match mime {
+        "image/jpeg" => strip_jpeg(data)?,
+        "image/png" => strip_png(data)?,
+        "image/webp" => strip_webp(data)?,
+        "image/gif" => (strip_gif(data)?, None),
+        _ => return Ok(data.to_vec()),
+    };
+    match orientation {
+        Some(orientation) if (2..=8).contains(&orientation) => rotate(&stripped, mime, orientation),
+        _ => Ok(stripped),
+    }
+}
+
+/// Strip the metadata from a video of type `mime` by remuxing it with ffmpeg, replacing the file
+/// at `path`.
+///
+/// Data streams are dropped along with the metadata since cameras use them for GPS tracks.
+pub(super) fn strip_video(path: &Path, mime: &str) -> io::Result<()> {
+    let format = match mime {
+        "video/mp4" => "mp4",
+        "video/quicktime" => "mov",
+        "video/webm" => "webm",
+        _ => return Ok(()),
+    };
+    let mut stripped = path.as_os_str().to_owned();
+    stripped.push("-stripped");
+    let output = Command::new("ffmpeg")
+        .args(["-v", "error", "-y", "-i"])
+        .arg(path)
+        .args([
+            "-map",
+            "0:v?",
+            "-map",
+            "0:a?",
+            "-map",
+            "0:s?",
+            "-map_metadata",
+            "-1",
+            "-map_chapters",
+            "-1",
+            "-fflags",
+            "+bitexact",
+            "-c",
+            "copy",
+            "-f",
+            format,
+        ])
+        .arg(&stripped)
+        .output()?;
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&stripped);
+        return Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    std::fs::rename(stripped, path)
+}
+
+/// Apply an EXIF orientation to an image, re-encoding it in its original format.
+fn rotate(data: &[u8], mime: &str, orientation: u16) -> io::Result<Vec<u8>> {
+    let format = ImageFormat::from_mime_type(mime).ok_or_else(|| invalid("Unknown image type"))?;
+    let image = image::load_from_memory_with_format(data, format).map_err(io::Error::other)?;
+    let image = match orientation {
+        2 => image.fliph(),
+        3 => image.rotate180(),
+        4 => image.flipv(),
+        5 => image.rotate90().fliph(),
+        6 => image.rotate90(),
+        7 => image.rotate270().fliph(),
+        8 => image.rotate270(),
+        _ => image,
+    };
+    let mut out = Cursor::new(vec![]);
+    match format {
+        ImageFormat::Jpeg => JpegEncoder::new_with_quality(&mut out, 95).encode_image(&image),
+        // The WebP encoder only supports 8 bit colours
+        ImageFormat::WebP => {
+            DynamicImage::ImageRgba8(image.to_rgba8()).write_to(&mut out, ImageFormat::WebP)
+        }
+        format => image.write_to(&mut out, format),
+    }
+    .map_err(io::Error::other)?;
+    Ok(out.into_inner())
+}
+
+/// Drop every JPEG segment except the ones needed to display the image and its ICC profile,
+/// along with anything after the end of the image.
+fn strip_jpeg(data: &[u8]) -> io::Result<(Vec<u8>, Option<u16>)> {
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return Err(invalid("Missing JPEG start of image"));
+    }
+    let mut out = Vec::with_capacity(data.len());
+    out.extend_from_slice(&data[..2]);
+    let mut orientation = None;
+    let mut pos = 2;
+    loop {
+        if data.get(pos) != Some(&0xFF) {
+            return Err(invalid("Expected a JPEG marker"));
+        }
+        // Markers can be padded with any amount of 0xFF bytes
+        while data.get(pos + 1) == Some(&0xFF) {
+            pos += 1;
+        }
+        let marker = *data.get(pos + 1).ok_or_else(|| invalid("Truncated JPEG"))?;
+        match marker {
+            // End of image
+            0xD9 => {
+                out.extend_from_slice(&[0xFF, 0xD9]);
+                return Ok((out, orientation));
+            }
+            // Restart markers and TEM don't have a length
+            0x01 | 0xD0..=0xD7 => {
+                out.extend_from_slice(&data[pos..pos + 2]);
+                pos += 2;
+                continue;
+            }
+            _ => {}
+        }
+        let length = data
+            .get(pos + 2..pos + 4)
+            .map(|l| u16::from_be_bytes([l[0], l[1]]) as usize)
+            .filter(|length| *length >= 2)
+            .ok_or_else(|| invalid("Truncated JPEG"))?;
+        let end = pos + 2 + length;
+        let segment = data
+            .get(pos..end)
+            .ok_or_else(|| invalid("Truncated JPEG segment"))?;
+        let payload = &segment[4..];
+        let keep = match marker {
+            // JFIF and Adobe segments affect how the image is decoded
+            0xE0 | 0xEE => true,
+            0xE2 => payload.starts_with(b"ICC_PROFILE\0"),
+            0xE1 => {
+                if let Some(tiff) = payload.strip_prefix(b"Exif\0\0") {
+                    orientation = orientation.or_else(|| exif_orientation(tiff));
+                }
+                false
+            }
+            // Other application segments and comments
+            0xE3..=0xEF | 0xFE => false,
+            _ => true,
+        };
+        if keep {
+            out.extend_from_slice(segment);
+        }
+        pos = end;
+        if marker == 0xDA {
+            // Copy the entropy coded data up to the next marker, 0xFF bytes in it are either
+            // followed by a 0x00 stuffing byte or are restart markers
+            let is_marker =
+                |pos: usize| data[pos] == 0xFF && !matches!(data[pos + 1], 0x00 | 0xD0..=0xD7);
+            let start = pos;
+            while pos + 1 < data.len() && !is_marker(pos) {
+                pos += 1;
+            }
+            if pos + 1 >= data.len() {
+                return Err(invalid("Truncated JPEG scan"));
+            }
+            out.extend_from_slice(&data[start..pos]);
+        }
+    }
+}
+
+/// Drop textual, EXIF and timestamp chunks from a PNG along with anything after its end.
+fn strip_png(data: &[u8]) -> io::Result<(Vec<u8>, Option<u16>)> {
+    const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+    if !data.starts_with(SIGNATURE) {
+        return Err(invalid("Missing PNG signature"));
+    }
+    let mut out = Vec::with_capacity(data.len());
+    out.extend_from_slice(SIGNATURE);
+    let mut orientation = None;
+    let mut animated = false;
+    let mut pos = SIGNATURE.len();
+    loop {
+        let length = data
+            .get(pos..pos + 4)
+            .map(|l| u32::from_be_bytes([l[0], l[1], l[2], l[3]]) as usize)
+            .ok_or_else(|| invalid("Truncated PNG"))?;
+        // Length, type, data and CRC
+        let end = pos + 12 + length;
+        let chunk = data
+            .get(pos..end)
+            .ok_or_else(|| invalid("Truncated PNG chunk"))?;
+        let kind = &chunk[4..8];
+        match kind {
+            b"eXIf" => orientation = exif_orientation(&chunk[8..8 + length]),
+            b"tEXt" | b"zTXt" | b"iTXt" | b"tIME" => {}
+            kind => {
+                animated |= kind == b"acTL";
+                out.extend_from_slice(chunk);
+            }
+        }
+        pos = end;
+        if kind == b"IEND" {
+            // Rotating an APNG would drop all of its frames but the first one
+            return Ok((out, orientation.filter(|_| !animated)));
+        }
+    }
+}
+
+/// Drop the EXIF and XMP chunks from a WebP.
+fn strip_webp(data: &[u8]) -> io::Result<(Vec<u8>, Option<u16>)> {
+    if data.len() < 12 || &data[..4] != b"RIFF" || &data[8..12] != b"WEBP" {
+        return Err(invalid("Missing WebP header"));
+    }
+    let riff_end =
+        (u32::from_le_bytes([data[4], data[5], data[6], data[7]]) as usize + 8).min(data.len());
+    let mut out = Vec::with_capacity(data.len());
+    out.extend_from_slice(&data[..12]);
+    let mut orientation = None;
+    let mut animated = false;
+    let mut pos = 12;
+    while pos + 8 <= riff_end {
+        let kind = &data[pos..pos + 4];
+        let length =
+            u32::from_le_bytes([data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]])
+                as usize;
+        // Chunks are padded to an even length
+        let end = (pos + 8 + length + length % 2).min(riff_end);
+        let chunk = data
+            .get(pos..end)
+            .filter(|chunk| chunk.len() >= 8 + length)
+            .ok_or_else(|| invalid("Truncated WebP chunk"))?;
+        match kind {
+            b"EXIF" => {
+                let exif = &chunk[8..8 + length];
+                orientation = exif_orientation(exif.strip_prefix(b"Exif\0\0").unwrap_or(exif));
+            }
+            b"XMP " => {}
+            b"VP8X" if length >= 1 => {
+                animated = chunk[8] & 0x02 != 0;
+                let chunk_start = out.len();
+                out.extend_from_slice(chunk);
+                // Unset the EXIF and XMP flags
+                out[chunk_start + 8] &= !0x0C;
+            }
+            _ => out.extend_from_slice(chunk),
+        }
+        pos = end;
+    }
+    let riff_size = (out.len() - 8) as u32;
+    out[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    // Rotating an animated WebP would drop all of its frames but the first one
+    Ok((out, orientation.filter(|_| !animated)))
+}
+
+/// Drop comments and application extensions other than the looping ones from a GIF.
+fn strip_gif(data: &[u8]) -> io::Result<Vec<u8>> {
+    if data.len() < 13 || !(data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a")) {
+        return Err(invalid("Missing GIF header"));
+    }
+    let mut pos = 13;
+    if data[10] & 0x80 != 0 {
+        // Global colour table
+        pos += 3 << ((data[10] & 0x07) + 1);
+    }
+    let mut out = Vec::with_capacity(data.len());
+    out.extend_from_slice(data.get(..pos).ok_or_else(|| invalid("Truncated GIF"))?);
+    loop {
+        let start = pos;
+        match data.get(pos) {
+            // Image descriptor
+            Some(0x2C) => {
+                let flags = *data.get(pos + 9).ok_or_else(|| invalid("Truncated GIF"))?;
+                pos += 10;
+                if flags & 0x80 != 0 {
+                    // Local colour table
+                    pos += 3 << ((flags & 0x07) + 1);
+                }
+                // LZW minimum code size
+                pos = skip_sub_blocks(data, pos + 1)?;
+                out.extend_from_slice(&data[start..pos]);
+            }
+            // Extension
+            Some(0x21) => {
+                let label = *data.get(pos + 1).ok_or_else(|| invalid("Truncated GIF"))?;
+                pos = skip_sub_blocks(data, pos + 2)?;
+                let keep = match label {
+                    0xFE => false,
+                    0xFF => {
+                        let identifier = data.get(start + 3..start + 14);
+                        identifier == Some(b"NETSCAPE2.0") || identifier == Some(b"ANIMEXTS1.0")
+                    }
+                    _ => true,
+                };
+                if keep {
+                    out.extend_from_slice(&data[start..pos]);
+                }
+            }
+            // Trailer
+            Some(0x3B) => {
+                out.push(0x3B);
+                return Ok(out);
+            }
+            _ => return Err(invalid("Unknown GIF block")),
+        }
+    }
+}
+
+/// Get the position right after a GIF sub-block chain starting at `pos`.
+fn skip_sub_blocks(data: &[u8], mut pos: usize) -> io::Result<usize> {
+    loop {
+        let length = *data.get(pos).ok_or_else(|| invalid("Truncated GIF"))? as usize;
+        pos += 1 + length;
+        if length == 0 {
+            return Ok(pos);
+        }
+    }
+}
+
+/// Read the orientation tag from the first IFD of a TIFF structured EXIF block.
+fn exif_orientation(tiff: &[u8]) -> Option<u16> {
+    let big_endian = match tiff.get(..2)? {
+        b"MM" => true,
+        b"II" => false,
+        _ => return None,
+    };
+    let read_u16 = |pos: usize| {
+        let bytes = [*tiff.get(pos)?, *tiff.get(pos + 1)?];
+        Some(if big_endian {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        })
+    };
+    let ifd = tiff.get(4..8).map(|b| {
+        let bytes = [b[0], b[1], b[2], b[3]];
+        if big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        }
+    })? as usize;
+    (0..read_u16(ifd)? as usize)
+        .map(|entry| ifd + 2 + entry * 12)
+        .find(|&entry| read_u16(entry) == Some(0x0112))
+        // Short values are stored at the start of the entry's 4 byte value field
+        .and_then(|entry| read_u16(entry + 8))
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{ImageBuffer, Rgb};
+
+    use super::*;
+
+    /// A little endian EXIF block with only an orientation tag.
+    fn exif(orientation: u16) -> Vec<u8> {
+        let mut tiff = b"II*\0\x08\0\0\0\x01\0\x12\x01\x03\0\x01\0\0\0".to_vec();
+        tiff.extend_from_slice(&orientation.to_le_bytes());
+        tiff.extend_from_slice(&[0; 6]);
+        tiff
+    }
+
+    /// Replace the orientation of the first [`exif`] block in a file.
+    fn with_orientation(data: &[u8], orientation: u16) -> Vec<u8> {
+        let original = exif(1);
+        let start = data
+            .windows(original.len())
+            .position(|w| w == original)
+            .unwrap();
+        let mut out = data.to_vec();
+        out[start..start + original.len()].copy_from_slice(&exif(orientation));
+        out
+    }
+
+    fn encode(format: ImageFormat) -> Vec<u8> {
+        let mut out = Cursor::new(vec![]);
+        DynamicImage::ImageRgb8(ImageBuffer::from_pixel(6, 4, Rgb([255, 0, 0])))
+            .write_to(&mut out, format)
+            .unwrap();
+        out.into_inner()
+    }
+
+    fn dimensions(data: &[u8], format: ImageFormat) -> (u32, u32) {
+        let image = image::load_from_memory_with_format(data, format).unwrap();
+        (image.width(), image.height())
+    }
+
+    #[test]
+    fn orientation() {
+        assert_eq!(exif_orientation(&exif(6)), Some(6));
+        let mut big_endian = b"MM\0*\0\0\0\x08\0\x01\x01\x12\0\x03\0\0\0\x01\0\x03".to_vec();
+        big_endian.extend_from_slice(&[0; 6]);
+        assert_eq!(exif_orientation(&big_endian), Some(3));
+        assert_eq!(exif_orientation(b"II*\0\xff\0\0\0"), None);
+    }
+
+    #[test]
+    fn jpeg() {
+        let original = encode(ImageFormat::Jpeg);
+        let mut tagged = original[..2].to_vec();
+        let mut app1 = b"Exif\0\0".to_vec();
+        app1.extend_from_slice(&exif(1));
+        tagged.extend_from_slice(&[0xFF, 0xE1]);
+        tagged.extend_from_slice(&(app1.len() as u16 + 2).to_be_bytes());
+        tagged.extend_from_slice(&app1);
+        tagged.extend_from_slice(&[0xFF, 0xFE, 0x00, 0x06, b'h', b'e', b'y', b'!']);
+        tagged.extend_from_slice(&original[2..]);
+        tagged.extend_from_slice(b"trailing data");
+
+        assert_eq!(strip_image(&tagged, "image/jpeg").unwrap(), original);
+
+        let rotated = strip_image(&with_orientation(&tagged, 6), "image/jpeg").unwrap();
+        assert_eq!(dimensions(&rotated, ImageFormat::Jpeg), (4, 6));
+    }
+
+    #[test]
+    fn png() {
+        let original = encode(ImageFormat::Png);
+        let chunk = |kind: &[u8], data: &[u8]| {
+            let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+            chunk.extend_from_slice(kind);
+            chunk.extend_from_slice(data);
+            // The CRC isn't checked when stripping
+            chunk.extend_from_slice(&[0; 4]);
+            chunk
+        };
+        let with_chunks = |chunks: &[Vec<u8>]| {
+            // Right after the IHDR chunk
+            let mut tagged = original[..33].to_vec();
+            for c in chunks {
+                tagged.extend_from_slice(c);
+            }
+            tagged.extend_from_slice(&original[33..]);
+            tagged
+        };
+
+        let tagged = with_chunks(&[chunk(b"tEXt", b"Comment\0hey!"), chunk(b"eXIf", &exif(1))]);
+        assert_eq!(strip_image(&tagged, "image/png").unwrap(), original);
+
+        let rotated = strip_image(&with_chunks(&[chunk(b"eXIf", &exif(8))]), "image/png").unwrap();
+        assert_eq!(dimensions(&rotated, ImageFormat::Png), (4, 6));
+    }
+
+    #[test]
+    fn webp() {
+        let original = encode(ImageFormat::WebP);
+        let image_chunk = original[12..].to_vec();
+        let webp = |chunks: &[(&[u8], Vec<u8>)]| {
+            let mut body = b"WEBP".to_vec();
+            for (kind, data) in chunks {
+                body.extend_from_slice(kind);
+                body.extend_from_slice(&(data.len() as u32).to_le_bytes());
+                body.extend_from_slice(data);
+                if data.len() % 2 == 1 {
+                    body.push(0);
+                }
+            }
+            let mut out = b"RIFF".to_vec();
+            out.extend_from_slice(&(body.len() as u32).to_le_bytes());
+            out.extend_from_slice(&body);
+            out
+        };
+        // 6x4 canvas with the EXIF and XMP flags set
+        let vp8x = |flags: u8| vec![flags, 0, 0, 0, 5, 0, 0, 3, 0, 0];
+
+        let mut expected = webp(&[(b"VP8X", vp8x(0))]);
+        expected.extend_from_slice(&image_chunk);
+        let expected_size = (expected.len() as u32 - 8).to_le_bytes();
+        expected[4..8].copy_from_slice(&expected_size);
+
+        let mut tagged = webp(&[
+            (b"VP8X", vp8x(0x0C)),
+            (b"EXIF", exif(1)),
+            (b"XMP ", b"<x:xmpmeta/>".to_vec()),
+        ]);
+        tagged.extend_from_slice(&image_chunk);
+        let tagged_size = (tagged.len() as u32 - 8).to_le_bytes();
+        tagged[4..8].copy_from_slice(&tagged_size);
+        assert_eq!(strip_image(&tagged, "image/webp").unwrap(), expected);
+
+        let rotated = strip_image(&with_orientation(&tagged, 6), "image/webp").unwrap();
+        assert_eq!(dimensions(&rotated, ImageFormat::WebP), (4, 6));
+    }
+
+    #[test]
+    fn gif() {
+        let original = encode(ImageFormat::Gif);
+        // Right after the global colour table
+        let mut split = 13;
+        if original[10] & 0x80 != 0 {
+            split += 3 << ((original[10] & 0x07) + 1);
+        }
+        let mut tagged = original[..split].to_vec();
+        tagged.extend_from_slice(&[0x21, 0xFE, 0x04, b'h', b'e', b'y', b'!', 0x00]);
+        tagged.extend_from_slice(&[0x21, 0xFF, 0x0B]);
+        tagged.extend_from_slice(b"XMP DataXMP");
+        tagged.extend_from_slice(&[0x02, b'<', b'>', 0x00]);
+        tagged.extend_from_slice(&original[split..]);
+
+        assert_eq!(strip_image(&tagged, "image/gif").unwrap(), original);
+    }
+}
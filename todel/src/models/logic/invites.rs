@@ -0,0 +1,219 @@
+use rand::{distributions::Alphanumeric, Rng};
+use sqlx::{pool::PoolConnection, postgres::PgRow, FromRow, Postgres, Row};
+
+use crate::models::{logic::get_unix_timestamp, ErrorResponse, Invite, InviteCreate};
+
+/// The length of generated invite codes.
+const INVITE_CODE_LENGTH: usize = 8;
+
+impl FromRow<'_, PgRow> for Invite {
+    fn from_row(row: &PgRow) -> sqlx::Result<Self> {
+        Ok(Self {
+            code: row.get("code"),
+            sphere_id: row.get::<i64, _>("sphere_id") as u64,
+            creator_id: row.get::<Option<i64>, _>("creator_id").map(|c| c as u64),
+            uses: row.get::<i32, _>("uses") as u32,
+            max_uses: row.get::<Option<i32>, _>("max_uses").map(|m| m as u32),
+            expires_at: row.get::<Option<i64>, _>("expires_at").map(|e| e as u64),
+        })
+    }
+}
+
+impl InviteCreate {
+    pub fn validate(&self) -> Result<(), ErrorResponse> {
+        if let Some(max_uses) = self.max_uses {
+            if max_uses == 0 || max_uses > 1000 {
+                return Err(error!(
+                    VALIDATION,
+                    "max_uses", "The invite's max uses must be between 1 and 1000"
+                ));
+            }
+        }
+        if self.duration == Some(0) {
+            return Err(error!(
+                VALIDATION,
+                "duration", "The invite's duration must be greater than 0"
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Invite {
+    pub async fn create<R: Rng>(
+        invite: InviteCreate,
+        sphere_id: u64,
+        creator_id: u64,
+        rng: &mut R,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<Self, ErrorResponse> {
+        invite.validate()?;
+        let code: String = rng
+            .sample_iter(&Alphanumeric)
+            .take(INVITE_CODE_LENGTH)
+            .map(char::from)
+            .collect();
+        let expires_at = invite.duration.map(|d| get_unix_timestamp() + d);
+        sqlx::query!(
+            "
+INSERT INTO invites(code, sphere_id, creator_id, max_uses, expires_at)
+VALUES($1, $2, $3, $4, $5)
+            ",
+            code,
+            sphere_id as i64,
+            creator_id as i64,
+            invite.max_uses.map(|m| m as i32),
+            expires_at.map(|e| e as i64),
+        )
+        .execute(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Couldn't create invite for sphere {} by {}: {}",
+                sphere_id,
+                creator_id,
+                err
+            );
+            error!(SERVER, "Failed to create invite")
+        })?;
+        Ok(Self {
+            code,
+            sphere_id,
+            creator_id: Some(creator_id),
+            uses: 0,
+            max_uses: invite.max_uses,
+            expires_at,
+        })
+    }
+
+    /// Get an invite which can still be used.
+    pub async fn get(code: &str, db: &mut PoolConnection<Postgres>) -> Result<Self, ErrorResponse> {
+        sqlx::query_as(
+            "
+SELECT *
+FROM invites
+WHERE code = $1
+    AND (max_uses IS NULL OR uses < max_uses)
+    AND (expires_at IS NULL OR expires_at > $2)
+            ",
+        )
+        .bind(code)
+        .bind(get_unix_timestamp() as i64)
+        .fetch_optional(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch invite {}: {}", code, err);
+            error!(SERVER, "Failed to get invite")
+        })?
+        .ok_or_else(|| error!(NOT_FOUND))
+    }
+
+    /// Get all of a sphere's invites that haven't expired yet, including used up ones.
+    pub async fn get_all(
+        sphere_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<Vec<Self>, ErrorResponse> {
+        sqlx::query_as(
+            "
+SELECT *
+FROM invites
+WHERE sphere_id = $1
+    AND (expires_at IS NULL OR expires_at > $2)
+            ",
+        )
+        .bind(sphere_id as i64)
+        .bind(get_unix_timestamp() as i64)
+        .fetch_all(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch invites for sphere {}: {}", sphere_id, err);
+            error!(SERVER, "Failed to get invites")
+        })
+    }
+
+    /// Get one of a sphere's invites regardless of whether it can still be used.
+    pub async fn get_unchecked(
+        code: &str,
+        sphere_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<Self, ErrorResponse> {
+        sqlx::query_as(
+            "
+SELECT *
+FROM invites
+WHERE code = $1
+    AND sphere_id = $2
+            ",
+        )
+        .bind(code)
+        .bind(sphere_id as i64)
+        .fetch_optional(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch invite {}: {}", code, err);
+            error!(SERVER, "Failed to get invite")
+        })?
+        .ok_or_else(|| error!(NOT_FOUND))
+    }
+
+    /// Get the IDs of a sphere's current members who joined through an invite.
+    ///
+    /// This also works for invites which were revoked or have expired.
+    pub async fn get_members(
+        code: &str,
+        sphere_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<Vec<u64>, ErrorResponse> {
+        Ok(sqlx::query!(
+            "
+SELECT id
+FROM members
+WHERE sphere_id = $1
+    AND invite_code = $2
+    AND is_deleted = FALSE
+            ",
+            sphere_id as i64,
+            code,
+        )
+        .fetch_all(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch members of invite {}: {}", code, err);
+            error!(SERVER, "Failed to get invite members")
+        })?
+        .into_iter()
+        .map(|r| r.id as u64)
+        .collect())
+    }
+
+    pub async fn delete(&self, db: &mut PoolConnection<Postgres>) -> Result<(), ErrorResponse> {
+        sqlx::query!(
+            "
+DELETE FROM invites
+WHERE code = $1
+            ",
+            self.code,
+        )
+        .execute(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't delete invite {}: {}", self.code, err);
+            error!(SERVER, "Failed to delete invite")
+        })?;
+        Ok(())
+    }
+
+    /// Delete every invite that has expired.
+    pub async fn clean_up_expired(db: &mut PoolConnection<Postgres>) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "
+DELETE FROM invites
+WHERE expires_at <= $1
+            ",
+            get_unix_timestamp() as i64
+        )
+        .execute(&mut **db)
+        .await?;
+        Ok(())
+    }
+}
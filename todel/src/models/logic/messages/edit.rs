@@ -1,6 +1,6 @@
 use sqlx::{pool::PoolConnection, Acquire, Postgres};
 
-use crate::models::{Attachment, Embed, ErrorResponse, File, Message, MessageEdit};
+use crate::models::{Attachment, Embed, ErrorResponse, Message, MessageEdit};
 
 impl MessageEdit {
     pub fn validate(&mut self) -> Result<(), ErrorResponse> {
@@ -72,26 +72,10 @@ impl Message {
         // store here to avoid relying on side effects later
         let had_attachments = edit.attachments.is_some();
 
-        let mut attachments = vec![];
-        if let Some(attachment_creates) = edit.attachments {
-            for (i, attachment_create) in attachment_creates.into_iter().enumerate() {
-                let file = match File::get(attachment_create.file_id, "attachments", db).await {
-                    Some(file) => file,
-                    None => {
-                        return Err(error!(
-                            VALIDATION,
-                            format!("attachments-{}", i),
-                            "Attachment's file must be a valid file that exists in the attachments bucket"
-                        ))
-                    }
-                };
-                attachments.push(Attachment {
-                    file: file.get_file_data(),
-                    description: attachment_create.description,
-                    spoiler: attachment_create.spoiler,
-                });
-            }
-        }
+        let attachments = match edit.attachments {
+            Some(attachment_creates) => Attachment::gather(attachment_creates, db).await?,
+            None => vec![],
+        };
 
         let mut transaction = db.begin().await.map_err(|err| {
             log::error!(
@@ -5,11 +5,37 @@ use redis::AsyncCommands;
 use sqlx::{pool::PoolConnection, types::Json, Postgres, QueryBuilder, Row};
 
 use crate::models::{
-    Attachment, Embed, Emoji, ErrorResponse, File, Message, MessageDisguise, Reaction,
-    ReactionEmoji, SphereChannel, Status, StatusType, User,
+    Attachment, Channel, Embed, Emoji, ErrorResponse, File, Message, MessageDisguise,
+    MessageWebhook, Reaction, ReactionEmoji, User,
 };
 
 impl Message {
+    /// Get the webhook a message was sent through, if any.
+    async fn get_webhook(
+        id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<Option<MessageWebhook>, ErrorResponse> {
+        Ok(sqlx::query!(
+            "
+            SELECT *
+            FROM message_webhooks
+            WHERE message_id = $1
+            ",
+            id as i64
+        )
+        .fetch_optional(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch webhook for message {}: {}", id, err);
+            error!(SERVER, "Failed to fetch message data")
+        })?
+        .map(|row| MessageWebhook {
+            id: row.webhook_id as u64,
+            name: row.name,
+            avatar: row.avatar.map(|a| a as u64),
+        }))
+    }
+
     #[allow(clippy::multiple_bound_locations)] // happens thanks to the `async_recursion` macro
     #[async_recursion]
     pub async fn get<C: AsyncCommands>(
@@ -32,25 +58,11 @@ impl Message {
             error!(SERVER, "Failed to fetch message data")
         })?
         .ok_or_else(|| error!(NOT_FOUND))?;
-        let author = match row.author_id {
-            Some(id) => User::get(id as u64, None, db, cache).await?,
-            None => User {
-                id: 0,
-                username: "deleted-user".to_string(),
-                display_name: Some("Deleted User".to_string()),
-                social_credit: 0,
-                status: Status {
-                    status_type: StatusType::Offline,
-                    text: None,
-                },
-                bio: None,
-                avatar: None,
-                banner: None,
-                badges: 0,
-                permissions: 0,
-                email: None,
-                verified: None,
-            },
+        let webhook = Self::get_webhook(id, db).await?;
+        let author = match (row.author_id, &webhook) {
+            (Some(id), _) => User::get(id as u64, None, db, cache).await?,
+            (None, Some(webhook)) => User::webhook(webhook),
+            (None, None) => User::deleted(),
         };
         let reference = match row.reference {
             Some(reference) => match Self::get(reference as u64, db, cache).await {
@@ -164,8 +176,9 @@ impl Message {
             author,
             content: row.content,
             reference,
+            webhook,
             disguise,
-            channel: SphereChannel::get(row.channel_id as u64, db).await?,
+            channel: Channel::get(row.channel_id as u64, db, cache).await?,
             attachments,
             embeds,
             reactions: reactions
@@ -215,28 +228,15 @@ impl Message {
             log::error!("Couldn't fetch channel history {}: {}", channel_id, err);
             error!(SERVER, "Failed to fetch channel history")
         })?;
+        let channel = Channel::get(channel_id, db, cache).await?;
         let mut messages = vec![];
         for row in rows {
             let id = row.get::<i64, _>("id") as u64;
-            let author = match row.get::<Option<i64>, _>("author_id") {
-                Some(id) => User::get(id as u64, None, db, cache).await?,
-                None => User {
-                    id: 0,
-                    username: "deleted-user".to_string(),
-                    display_name: Some("Deleted User".to_string()),
-                    social_credit: 0,
-                    status: Status {
-                        status_type: StatusType::Offline,
-                        text: None,
-                    },
-                    bio: None,
-                    avatar: None,
-                    banner: None,
-                    badges: 0,
-                    permissions: 0,
-                    email: None,
-                    verified: None,
-                },
+            let webhook = Self::get_webhook(id, db).await?;
+            let author = match (row.get::<Option<i64>, _>("author_id"), &webhook) {
+                (Some(id), _) => User::get(id as u64, None, db, cache).await?,
+                (None, Some(webhook)) => User::webhook(webhook),
+                (None, None) => User::deleted(),
             };
             let reference = match row.get::<Option<i64>, _>("reference") {
                 Some(reference) => match Self::get(reference as u64, db, cache).await {
@@ -351,8 +351,9 @@ impl Message {
                 author,
                 content: row.get("content"),
                 reference,
+                webhook,
                 disguise,
-                channel: SphereChannel::get(row.get::<i64, _>("channel_id") as u64, db).await?,
+                channel: channel.clone(),
                 attachments,
                 embeds,
                 reactions: reactions
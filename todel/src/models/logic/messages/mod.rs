@@ -9,7 +9,10 @@ use sqlx::{pool::PoolConnection, Acquire, Postgres};
 
 use crate::{
     ids::IdGenerator,
-    models::{Attachment, Embed, ErrorResponse, File, Message, MessageCreate, SphereChannel, User},
+    models::{
+        Attachment, Channel, Embed, ErrorResponse, Member, Message, MessageCreate, MessageWebhook,
+        ReadState, User, Webhook,
+    },
 };
 
 impl MessageCreate {
@@ -69,21 +72,76 @@ impl MessageCreate {
 
 impl Message {
     pub async fn create<C: AsyncCommands>(
-        mut message: MessageCreate,
+        message: MessageCreate,
         channel_id: u64,
         author_id: u64,
         id_generator: &mut IdGenerator,
         db: &mut PoolConnection<Postgres>,
         cache: &mut C,
+    ) -> Result<Self, ErrorResponse> {
+        let author = User::get(author_id, None, db, cache).await?;
+        Self::insert(message, channel_id, author, None, id_generator, db, cache).await
+    }
+
+    /// Send a message through a webhook, the webhook takes the place of the message's author.
+    pub async fn create_from_webhook<C: AsyncCommands>(
+        message: MessageCreate,
+        webhook: &Webhook,
+        id_generator: &mut IdGenerator,
+        db: &mut PoolConnection<Postgres>,
+        cache: &mut C,
+    ) -> Result<Self, ErrorResponse> {
+        if message.disguise.is_some() {
+            return Err(error!(
+                VALIDATION,
+                "_disguise", "Webhook messages can't be disguised"
+            ));
+        }
+        let webhook_data = webhook.to_message_webhook();
+        let author = User::webhook(&webhook_data);
+        Self::insert(
+            message,
+            webhook.channel_id,
+            author,
+            Some(webhook_data),
+            id_generator,
+            db,
+            cache,
+        )
+        .await
+    }
+
+    async fn insert<C: AsyncCommands>(
+        mut message: MessageCreate,
+        channel_id: u64,
+        author: User,
+        webhook: Option<MessageWebhook>,
+        id_generator: &mut IdGenerator,
+        db: &mut PoolConnection<Postgres>,
+        cache: &mut C,
     ) -> Result<Self, ErrorResponse> {
         message.validate()?;
-        let channel = SphereChannel::get(channel_id, db).await.map_err(|err| {
+        // webhook messages don't have an actual author
+        let author_id = match webhook {
+            Some(_) => None,
+            None => Some(author.id),
+        };
+        let channel = Channel::get(channel_id, db, cache).await.map_err(|err| {
             if let ErrorResponse::NotFound { .. } = err {
                 error!(VALIDATION, "channel", "Channel doesn't exist")
             } else {
                 err
             }
         })?;
+        if let Channel::Forum(_) = channel {
+            return Err(error!(
+                VALIDATION,
+                "channel", "Messages can't be sent in forum channels, create a post instead"
+            ));
+        }
+        if let (Some(author_id), Some(sphere_id)) = (author_id, channel.get_sphere_id()) {
+            Member::require_not_timed_out(author_id, sphere_id, db).await?;
+        }
         let id = id_generator.generate();
         let reference = match message.reference {
             Some(reference) => match Self::get(reference, db, cache).await {
@@ -101,32 +159,16 @@ impl Message {
             },
             None => None,
         };
-        let author = User::get(author_id, None, db, cache).await?;
 
         // gather attachment files pre-transaction
-        // (consider if this should be in Attachment::create)
-        let mut attachments = vec![];
-        for (i, mut attachment_create) in message.attachments.into_iter().enumerate() {
-            attachment_create.validate()?;
-
-            let attachment_file =
-                match File::get(attachment_create.file_id, "attachments", db).await {
-                    Some(file) => file,
-                    None => {
-                        return Err(error!(
-                    VALIDATION,
-                    format!("attachments-{}", i),
-                    "Attachment's file must be a valid file that exists in the attachments bucket"
-                ))
-                    }
-                };
-
-            attachments.push(Attachment {
-                file: attachment_file.get_file_data(),
-                description: attachment_create.description,
-                spoiler: attachment_create.spoiler,
-            });
-        }
+        let attachments = Attachment::gather(message.attachments, db).await?;
+        // along with the mentioned users who can see the message
+        let mentions = match (&message.content, channel.get_sphere_id()) {
+            (Some(content), Some(_)) => {
+                ReadState::get_visible_mentions(channel_id, author.id, content, db).await?
+            }
+            _ => vec![],
+        };
 
         let mut transaction = db.begin().await.map_err(|err| {
             log::error!("Couldn't start message create transaction: {}", err);
@@ -139,7 +181,7 @@ VALUES($1, $2, $3, $4, $5)
             ",
             id as i64,
             channel_id as i64,
-            author_id as i64,
+            author_id.map(|a| a as i64),
             message.content,
             message.reference.map(|r| r as i64),
         )
@@ -148,7 +190,7 @@ VALUES($1, $2, $3, $4, $5)
         .map_err(|err| {
             log::error!(
                 "Couldn't create message by {} on {}: {}",
-                author_id,
+                author.id,
                 channel_id,
                 err
             );
@@ -195,6 +237,29 @@ VALUES($1, $2, $3, $4, $5)
             })?;
         }
 
+        if let Some(sphere_id) = channel.get_sphere_id() {
+            ReadState::add_mentions(channel_id, sphere_id, &mentions, &mut transaction).await?;
+        }
+
+        if let Some(webhook) = &webhook {
+            sqlx::query!(
+                "
+                INSERT INTO message_webhooks(message_id, webhook_id, name, avatar)
+                VALUES($1, $2, $3, $4)
+                ",
+                id as i64,
+                webhook.id as i64,
+                webhook.name,
+                webhook.avatar.map(|a| a as i64),
+            )
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| {
+                log::error!("Couldn't add message webhook to {}: {}", id, err);
+                error!(SERVER, "Failed to create message")
+            })?;
+        }
+
         // bodge
         if let Some(disguise) = &message.disguise {
             sqlx::query!(
@@ -224,6 +289,7 @@ VALUES($1, $2, $3, $4, $5)
             author,
             content: message.content,
             reference,
+            webhook,
             disguise: message.disguise,
             channel,
             attachments,
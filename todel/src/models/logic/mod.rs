@@ -1,15 +1,26 @@
+mod applications;
 mod attachments;
+mod audit_log;
 mod categories;
 mod channels;
 mod email;
 mod emojis;
 mod files;
+mod invites;
 mod messages;
 mod meta;
+mod oauth;
+mod posts;
+mod read_states;
+mod roles;
 mod sessions;
 mod spheres;
 mod users;
+mod webhooks;
 
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub use audit_log::*;
 pub use email::*;
 pub use meta::*;
 pub use sessions::*;
@@ -17,3 +28,11 @@ pub use users::*;
 
 #[cfg(feature = "http")]
 pub use files::*;
+
+/// Get the current time in seconds since the Unix epoch.
+pub(crate) fn get_unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Couldn't get current timestamp")
+        .as_secs()
+}
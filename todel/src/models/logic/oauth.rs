@@ -0,0 +1,365 @@
+use std::net::IpAddr;
+
+use data_encoding::BASE64URL_NOPAD;
+use jwt::SignWithKey;
+use rand::{distributions::Alphanumeric, Rng};
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{pool::PoolConnection, types::ipnetwork::IpNetwork, Acquire, Postgres};
+use url::Url;
+
+use crate::{
+    ids::IdGenerator,
+    models::{
+        Application, ErrorResponse, OAuthAuthorization, OAuthAuthorize, OAuthAuthorized,
+        OAuthScope, OAuthToken, OAuthTokenCreate, Session,
+    },
+};
+
+use super::{get_unix_timestamp, Secret, SessionTokenClaims};
+
+/// How many seconds authorization codes are valid for.
+const AUTHORIZATION_CODE_LIFETIME: usize = 600;
+/// How many seconds access tokens are valid for.
+const ACCESS_TOKEN_LIFETIME: u64 = 86400;
+
+/// The data an authorization code stands for while it waits to be exchanged for a token.
+#[derive(Debug, Serialize, Deserialize)]
+struct AuthorizationCodeData {
+    application_id: u64,
+    user_id: u64,
+    redirect_uri: String,
+    scopes: u64,
+    code_challenge: String,
+}
+
+impl OAuthScope {
+    /// Every scope that is currently defined.
+    pub const ALL: [Self; 6] = [
+        Self::Identify,
+        Self::SpheresRead,
+        Self::SpheresWrite,
+        Self::MessagesRead,
+        Self::MessagesWrite,
+        Self::Gateway,
+    ];
+
+    /// The name the scope is requested with.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Identify => "identify",
+            Self::SpheresRead => "spheres.read",
+            Self::SpheresWrite => "spheres.write",
+            Self::MessagesRead => "messages.read",
+            Self::MessagesWrite => "messages.write",
+            Self::Gateway => "gateway",
+        }
+    }
+
+    /// Check whether this scope is set in a scope bitfield.
+    pub fn is_set(self, scopes: u64) -> bool {
+        scopes & self as u64 != 0
+    }
+
+    /// Parse a space separated list of scope names into a scope bitfield.
+    pub fn parse(scope: &str) -> Result<u64, ErrorResponse> {
+        let mut scopes = 0;
+        for name in scope.split_whitespace() {
+            let scope = Self::ALL
+                .into_iter()
+                .find(|s| s.name() == name)
+                .ok_or_else(|| error!(VALIDATION, "scope", format!("Unknown scope {}", name)))?;
+            scopes |= scope as u64;
+        }
+        if scopes == 0 {
+            return Err(error!(
+                VALIDATION,
+                "scope", "At least one scope is required"
+            ));
+        }
+        Ok(scopes)
+    }
+
+    /// Get the scopes which are set in a scope bitfield.
+    pub fn from_bitfield(scopes: u64) -> Vec<Self> {
+        Self::ALL.into_iter().filter(|s| s.is_set(scopes)).collect()
+    }
+
+    /// Format a scope bitfield as a space separated list of scope names.
+    pub fn to_scope_string(scopes: u64) -> String {
+        Self::from_bitfield(scopes)
+            .into_iter()
+            .map(Self::name)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl OAuthAuthorize {
+    /// Validate an authorization request, returning the application it's for and the requested
+    /// scopes.
+    pub async fn validate(
+        &self,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<(Application, u64), ErrorResponse> {
+        if self.response_type != "code" {
+            return Err(error!(
+                VALIDATION,
+                "response_type", "Only the code response type is supported"
+            ));
+        }
+        if self.code_challenge_method != "S256" {
+            return Err(error!(
+                VALIDATION,
+                "code_challenge_method", "Only the S256 code challenge method is supported"
+            ));
+        }
+        if self.code_challenge.len() != 43
+            || BASE64URL_NOPAD
+                .decode(self.code_challenge.as_bytes())
+                .is_err()
+        {
+            return Err(error!(
+                VALIDATION,
+                "code_challenge", "The code challenge must be a base64url encoded SHA-256 hash"
+            ));
+        }
+        if let Some(state) = &self.state {
+            if state.len() > 1024 {
+                return Err(error!(
+                    VALIDATION,
+                    "state", "The state can't be longer than 1024 characters"
+                ));
+            }
+        }
+        let scopes = OAuthScope::parse(&self.scope)?;
+        let application = Application::get_unchecked(self.client_id, db)
+            .await
+            .map_err(|err| match err {
+                ErrorResponse::NotFound { .. } => {
+                    error!(VALIDATION, "client_id", "Application doesn't exist")
+                }
+                err => err,
+            })?;
+        if !application.redirect_uris.contains(&self.redirect_uri) {
+            return Err(error!(
+                VALIDATION,
+                "redirect_uri", "The redirect URI isn't registered for this application"
+            ));
+        }
+        Ok((application, scopes))
+    }
+
+    /// Describe an authorization request so that the user can be asked for their consent.
+    pub async fn get_authorization(
+        &self,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<OAuthAuthorization, ErrorResponse> {
+        let (application, scopes) = self.validate(db).await?;
+        Ok(OAuthAuthorization {
+            application,
+            scopes: OAuthScope::from_bitfield(scopes),
+        })
+    }
+
+    /// Approve an authorization request on behalf of a user, issuing an authorization code.
+    pub async fn approve<R: Rng, C: AsyncCommands>(
+        self,
+        user_id: u64,
+        rng: &mut R,
+        db: &mut PoolConnection<Postgres>,
+        cache: &mut C,
+    ) -> Result<OAuthAuthorized, ErrorResponse> {
+        let (application, scopes) = self.validate(db).await?;
+        let code: String = rng
+            .sample_iter(&Alphanumeric)
+            .take(32)
+            .map(char::from)
+            .collect();
+        let data = AuthorizationCodeData {
+            application_id: application.id,
+            user_id,
+            redirect_uri: self.redirect_uri.clone(),
+            scopes,
+            code_challenge: self.code_challenge,
+        };
+        cache
+            .set_ex::<_, _, ()>(
+                format!("oauth2-code:{}", code),
+                serde_json::to_string(&data).expect("Couldn't serialize authorization code"),
+                AUTHORIZATION_CODE_LIFETIME,
+            )
+            .await
+            .map_err(|err| {
+                log::error!("Failed to store authorization code in cache: {}", err);
+                error!(SERVER, "Couldn't authorize the application")
+            })?;
+        let mut redirect_uri = Url::parse(&self.redirect_uri).map_err(|err| {
+            log::error!("Couldn't parse stored redirect URI: {}", err);
+            error!(SERVER, "Couldn't authorize the application")
+        })?;
+        {
+            let mut query = redirect_uri.query_pairs_mut();
+            query.append_pair("code", &code);
+            if let Some(state) = &self.state {
+                query.append_pair("state", state);
+            }
+        }
+        Ok(OAuthAuthorized {
+            redirect_uri: redirect_uri.to_string(),
+        })
+    }
+}
+
+impl Session {
+    /// Exchange an authorization code or a refresh token for an OAuth2 access token.
+    ///
+    /// Every token is backed by a session of the user, refreshing a token replaces its session.
+    pub async fn create_oauth<R: Rng, C: AsyncCommands>(
+        token: OAuthTokenCreate,
+        ip: IpAddr,
+        secret: &Secret,
+        rng: &mut R,
+        id_generator: &mut IdGenerator,
+        db: &mut PoolConnection<Postgres>,
+        cache: &mut C,
+    ) -> Result<OAuthToken, ErrorResponse> {
+        let application = Application::get_unchecked(token.client_id, db).await?;
+        // a refreshed token's old session is only removed once its new session is stored
+        let mut transaction = db.begin().await.map_err(|err| {
+            log::error!("Couldn't start OAuth2 token transaction: {}", err);
+            error!(SERVER, "Couldn't issue an access token")
+        })?;
+        let (user_id, scopes) = match token.grant_type.as_str() {
+            "authorization_code" => {
+                let (code, redirect_uri, code_verifier) =
+                    match (token.code, token.redirect_uri, token.code_verifier) {
+                        (Some(code), Some(redirect_uri), Some(code_verifier)) => {
+                            (code, redirect_uri, code_verifier)
+                        }
+                        _ => {
+                            return Err(error!(
+                                VALIDATION,
+                                "body",
+                                "The code, redirect_uri and code_verifier fields are required"
+                            ))
+                        }
+                    };
+                // Codes are deleted as they're read so that they can only be used once.
+                let (data,): (Option<String>,) = redis::pipe()
+                    .atomic()
+                    .get(format!("oauth2-code:{}", code))
+                    .del(format!("oauth2-code:{}", code))
+                    .ignore()
+                    .query_async(cache)
+                    .await
+                    .map_err(|err| {
+                        log::error!("Failed to get authorization code from cache: {}", err);
+                        error!(SERVER, "Couldn't issue an access token")
+                    })?;
+                let data: AuthorizationCodeData = data
+                    .and_then(|data| serde_json::from_str(&data).ok())
+                    .ok_or_else(|| error!(VALIDATION, "code", "Invalid authorization code"))?;
+                if data.application_id != token.client_id || data.redirect_uri != redirect_uri {
+                    return Err(error!(VALIDATION, "code", "Invalid authorization code"));
+                }
+                let challenge = BASE64URL_NOPAD.encode(&Sha256::digest(code_verifier.as_bytes()));
+                if code_verifier.len() < 43
+                    || code_verifier.len() > 128
+                    || challenge != data.code_challenge
+                {
+                    return Err(error!(
+                        VALIDATION,
+                        "code_verifier", "The code verifier doesn't match the code challenge"
+                    ));
+                }
+                (data.user_id, data.scopes)
+            }
+            "refresh_token" => {
+                let refresh_token = token.refresh_token.ok_or_else(|| {
+                    error!(
+                        VALIDATION,
+                        "refresh_token", "The refresh_token field is required"
+                    )
+                })?;
+                let session = sqlx::query!(
+                    "
+DELETE FROM sessions
+WHERE refresh_token_hash = $1
+AND application_id = $2
+RETURNING user_id, scopes
+                    ",
+                    sha256::digest(refresh_token.as_str()),
+                    token.client_id as i64,
+                )
+                .fetch_optional(&mut *transaction)
+                .await
+                .map_err(|err| {
+                    log::error!("Couldn't consume refresh token: {}", err);
+                    error!(SERVER, "Couldn't issue an access token")
+                })?
+                .ok_or_else(|| error!(VALIDATION, "refresh_token", "Invalid refresh token"))?;
+                (
+                    session.user_id as u64,
+                    session.scopes.unwrap_or_default() as u64,
+                )
+            }
+            _ => {
+                return Err(error!(
+                    VALIDATION,
+                    "grant_type",
+                    "Only the authorization_code and refresh_token grant types are supported"
+                ))
+            }
+        };
+        let id = id_generator.generate();
+        let now = get_unix_timestamp();
+        let refresh_token: String = rng
+            .sample_iter(&Alphanumeric)
+            .take(48)
+            .map(char::from)
+            .collect();
+        sqlx::query!(
+            "
+INSERT INTO sessions(id, user_id, platform, client, ip, application_id, scopes, refresh_token_hash, expires_at, created_at, last_used_at, last_ip)
+VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $10, $5)
+            ",
+            id as i64,
+            user_id as i64,
+            "oauth2",
+            application.bot.username,
+            IpNetwork::from(ip),
+            application.id as i64,
+            scopes as i64,
+            sha256::digest(refresh_token.as_str()),
+            (now + ACCESS_TOKEN_LIFETIME) as i64,
+            now as i64,
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| {
+            log::error!("Failed to store OAuth2 session in database: {}", err);
+            error!(SERVER, "Could not save session data")
+        })?;
+        let claims = SessionTokenClaims {
+            user_id,
+            session_id: id,
+        };
+        let access_token = claims.sign_with_key(&secret.0).map_err(|err| {
+            log::error!("Couldn't sign JWT: {}", err);
+            error!(SERVER, "Failed to generate a token for the user")
+        })?;
+        transaction.commit().await.map_err(|err| {
+            log::error!("Couldn't commit OAuth2 token transaction: {}", err);
+            error!(SERVER, "Couldn't issue an access token")
+        })?;
+        Ok(OAuthToken {
+            access_token,
+            token_type: "Bearer".to_string(),
+            expires_in: ACCESS_TOKEN_LIFETIME,
+            refresh_token,
+            scope: OAuthScope::to_scope_string(scopes),
+        })
+    }
+}
@@ -0,0 +1,25 @@
+use sqlx::{pool::PoolConnection, Postgres};
+
+use crate::models::{ErrorResponse, Post};
+
+impl Post {
+    /// Delete a post along with all of its comments.
+    pub async fn delete(&self, db: &mut PoolConnection<Postgres>) -> Result<(), ErrorResponse> {
+        sqlx::query!(
+            "
+DELETE FROM posts
+WHERE id = $1
+AND channel_id = $2
+            ",
+            self.id as i64,
+            self.channel_id as i64
+        )
+        .execute(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Failed to delete post {}: {}", self.id, err);
+            error!(SERVER, "Failed to delete post")
+        })?;
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+use sqlx::{pool::PoolConnection, Acquire, Postgres};
+
+use super::{validate_flair, validate_title};
+use crate::models::{Attachment, Embed, ErrorResponse, Post, PostEdit};
+
+impl PostEdit {
+    pub fn validate(&mut self) -> Result<(), ErrorResponse> {
+        if let Some(ref mut title) = self.title {
+            validate_title(title)?;
+        }
+        if let Some(Some(ref mut flair)) = self.flair {
+            validate_flair(flair)?;
+        }
+        if self.message.content.is_some()
+            || self.message.attachments.is_some()
+            || self.message.embeds.is_some()
+        {
+            self.message.validate()?;
+        } else if self.title.is_none() && self.flair.is_none() {
+            return Err(error!(
+                VALIDATION,
+                "body", "Post edit must change at least one field"
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Post {
+    pub async fn edit(
+        &mut self,
+        mut edit: PostEdit,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<(), ErrorResponse> {
+        edit.validate()?;
+
+        if self.parent_id.is_some() {
+            if edit.title.is_some() {
+                return Err(error!(VALIDATION, "title", "Comments can't have a title"));
+            }
+            if edit.flair.is_some() {
+                return Err(error!(VALIDATION, "flair", "Comments can't have a flair"));
+            }
+        }
+        let message = edit.message;
+        if (message.content.as_ref().is_some_and(|c| c.is_none()) || self.content.is_none())
+            && (message.attachments.as_ref().is_some_and(|a| a.is_empty())
+                || self.attachments.is_empty())
+            && (message.embeds.as_ref().is_some_and(|e| e.is_empty()) || self.embeds.is_empty())
+        {
+            return Err(error!(
+                VALIDATION,
+                "body", "Final post must contain either content, an attachment or an embed"
+            ));
+        }
+
+        let attachments = match message.attachments {
+            Some(attachment_creates) => Some(Attachment::gather(attachment_creates, db).await?),
+            None => None,
+        };
+
+        let mut transaction = db.begin().await.map_err(|err| {
+            log::error!("Couldn't start post edit transaction {}: {}", self.id, err);
+            error!(SERVER, "Failed to edit post")
+        })?;
+
+        sqlx::query!(
+            "
+UPDATE posts
+SET title = COALESCE($1, title),
+    flair = CASE WHEN $2 THEN $3 ELSE flair END,
+    content = CASE WHEN $4 THEN $5 ELSE content END
+WHERE id = $6
+            ",
+            edit.title,
+            edit.flair.is_some(),
+            edit.flair.clone().flatten(),
+            message.content.is_some(),
+            message.content.clone().flatten(),
+            self.id as i64,
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't edit post {}: {}", self.id, err);
+            error!(SERVER, "Failed to edit post")
+        })?;
+        if let Some(title) = edit.title {
+            self.title = Some(title);
+        }
+        if let Some(flair) = edit.flair {
+            self.flair = flair;
+        }
+        if let Some(content) = message.content {
+            self.content = content;
+        }
+
+        if let Some(attachments) = attachments {
+            sqlx::query!(
+                "
+DELETE FROM post_attachments
+WHERE post_id = $1
+                ",
+                self.id as i64,
+            )
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| {
+                log::error!("Couldn't remove old post attachments {}: {}", self.id, err);
+                error!(SERVER, "Failed to edit post")
+            })?;
+            for attachment in attachments.iter() {
+                sqlx::query!(
+                    "
+INSERT INTO post_attachments(post_id, file_id, description, spoiler)
+VALUES($1, $2, $3, $4)
+                    ",
+                    self.id as i64,
+                    attachment.file.id as i64,
+                    attachment.description,
+                    attachment.spoiler
+                )
+                .execute(&mut *transaction)
+                .await
+                .map_err(|err| {
+                    log::error!(
+                        "Couldn't edit post attachment file {} to {}: {}",
+                        attachment.file.id,
+                        self.id,
+                        err
+                    );
+                    error!(SERVER, "Failed to edit post")
+                })?;
+            }
+            self.attachments = attachments;
+        }
+
+        if let Some(embeds) = message.embeds {
+            sqlx::query!(
+                "
+DELETE FROM post_embeds
+WHERE post_id = $1
+                ",
+                self.id as i64,
+            )
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| {
+                log::error!("Couldn't remove old post embeds {}: {}", self.id, err);
+                error!(SERVER, "Failed to edit post")
+            })?;
+            for embed in embeds.iter() {
+                sqlx::query!(
+                    "
+INSERT INTO post_embeds(post_id, embed)
+VALUES($1, $2)
+                    ",
+                    self.id as i64,
+                    serde_json::to_value(Embed::Custom(embed.clone())).unwrap(),
+                )
+                .execute(&mut *transaction)
+                .await
+                .map_err(|err| {
+                    log::error!(
+                        "Couldn't edit post embed {:?} to {}: {}",
+                        embed,
+                        self.id,
+                        err
+                    );
+                    error!(SERVER, "Failed to edit post")
+                })?;
+            }
+            self.embeds = embeds.into_iter().map(Embed::Custom).collect();
+        }
+
+        transaction.commit().await.map_err(|err| {
+            log::error!("Couldn't commit post edit transaction {}: {}", self.id, err);
+            error!(SERVER, "Failed to edit post")
+        })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+use redis::AsyncCommands;
+use sqlx::{pool::PoolConnection, Postgres, QueryBuilder};
+
+use crate::models::{ErrorResponse, Post, PostSort};
+
+impl Post {
+    /// Get a post without its comments.
+    pub async fn get_unpopulated<C: AsyncCommands>(
+        id: u64,
+        channel_id: u64,
+        db: &mut PoolConnection<Postgres>,
+        cache: &mut C,
+    ) -> Result<Self, ErrorResponse> {
+        let row = sqlx::query(
+            "
+SELECT posts.*, (
+    SELECT COUNT(*)
+    FROM posts AS comments
+    WHERE comments.parent_id = posts.id
+) AS comment_count
+FROM posts
+WHERE id = $1
+    AND channel_id = $2
+            ",
+        )
+        .bind(id as i64)
+        .bind(channel_id as i64)
+        .fetch_optional(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch post data {}: {}", id, err);
+            error!(SERVER, "Failed to fetch post data")
+        })?
+        .ok_or_else(|| error!(NOT_FOUND))?;
+        Self::populate(&row, db, cache).await
+    }
+
+    /// Get a post along with its whole comment tree.
+    pub async fn get<C: AsyncCommands>(
+        id: u64,
+        channel_id: u64,
+        db: &mut PoolConnection<Postgres>,
+        cache: &mut C,
+    ) -> Result<Self, ErrorResponse> {
+        let mut post = Self::get_unpopulated(id, channel_id, db, cache).await?;
+        let rows = sqlx::query(
+            "
+WITH RECURSIVE comments AS (
+    SELECT *
+    FROM posts
+    WHERE parent_id = $1
+    UNION ALL
+    SELECT posts.*
+    FROM posts
+    JOIN comments ON posts.parent_id = comments.id
+)
+SELECT comments.*, (
+    SELECT COUNT(*)
+    FROM posts
+    WHERE posts.parent_id = comments.id
+) AS comment_count
+FROM comments
+ORDER BY id DESC
+            ",
+        )
+        .bind(id as i64)
+        .fetch_all(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch comments of post {}: {}", id, err);
+            error!(SERVER, "Failed to fetch post data")
+        })?;
+
+        // comments are always newer than what they reply to, so going from the newest one means
+        // that each comment's replies are complete by the time it's reached
+        let mut replies: HashMap<u64, Vec<Post>> = HashMap::new();
+        for row in rows {
+            let mut comment = Self::populate(&row, db, cache).await?;
+            if let Some(mut comments) = replies.remove(&comment.id) {
+                comments.reverse();
+                comment.comments = comments;
+            }
+            replies
+                .entry(comment.parent_id.unwrap_or(id))
+                .or_default()
+                .push(comment);
+        }
+        if let Some(mut comments) = replies.remove(&id) {
+            comments.reverse();
+            post.comments = comments;
+        }
+        Ok(post)
+    }
+
+    /// Get a forum channel's posts without their comments.
+    pub async fn get_all<C: AsyncCommands>(
+        channel_id: u64,
+        sort: PostSort,
+        limit: u32,
+        before: Option<u64>,
+        offset: Option<u32>,
+        db: &mut PoolConnection<Postgres>,
+        cache: &mut C,
+    ) -> Result<Vec<Self>, ErrorResponse> {
+        if !(1..=100).contains(&limit) {
+            return Err(error!(
+                VALIDATION,
+                "limit", "Limit must be between 1 and 100, inclusive."
+            ));
+        }
+
+        let mut query: QueryBuilder<Postgres> = QueryBuilder::new(
+            "
+SELECT posts.*, (
+    SELECT COUNT(*)
+    FROM posts AS comments
+    WHERE comments.parent_id = posts.id
+) AS comment_count
+FROM posts
+WHERE parent_id IS NULL
+    AND channel_id =
+            ",
+        );
+        query.push_bind(channel_id as i64);
+        if let Some(id) = before {
+            query.push(" AND id < ").push_bind(id as i64);
+        }
+        query.push(match sort {
+            // the upper 48 bits of an ID are its creation time in seconds since the Eludris epoch,
+            // so every 45000 seconds (12.5 hours) are worth as much as a tenfold score
+            PostSort::Hot => {
+                " ORDER BY SIGN(score) * LOG(GREATEST(ABS(score), 1)) + (id >> 16) / 45000.0 DESC, id DESC "
+            }
+            PostSort::Top => " ORDER BY score DESC, id DESC ",
+            PostSort::New => " ORDER BY id DESC ",
+        });
+        query.push(" LIMIT ").push_bind(limit as i32);
+        if let Some(offset) = offset {
+            query.push(" OFFSET ").push_bind(offset as i64);
+        }
+
+        let rows = query.build().fetch_all(&mut **db).await.map_err(|err| {
+            log::error!("Couldn't fetch posts of channel {}: {}", channel_id, err);
+            error!(SERVER, "Failed to fetch posts")
+        })?;
+        let mut posts = vec![];
+        for row in rows {
+            posts.push(Self::populate(&row, db, cache).await?);
+        }
+        Ok(posts)
+    }
+}
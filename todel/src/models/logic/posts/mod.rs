@@ -0,0 +1,277 @@
+mod delete;
+mod edit;
+mod get;
+mod votes;
+
+use redis::AsyncCommands;
+use sqlx::{pool::PoolConnection, postgres::PgRow, types::Json, Acquire, Postgres, Row};
+
+use crate::{
+    ids::IdGenerator,
+    models::{Attachment, Channel, Embed, ErrorResponse, File, Member, Post, PostCreate, User},
+};
+
+fn validate_title(title: &mut String) -> Result<(), ErrorResponse> {
+    *title = title.trim().to_string();
+    if title.is_empty() || title.len() > 256 {
+        return Err(error!(
+            VALIDATION,
+            "title", "The post's title must be between 1 and 256 characters long"
+        ));
+    }
+    Ok(())
+}
+
+fn validate_flair(flair: &mut String) -> Result<(), ErrorResponse> {
+    *flair = flair.trim().to_string();
+    if flair.is_empty() || flair.len() > 32 {
+        return Err(error!(
+            VALIDATION,
+            "flair", "The post's flair must be between 1 and 32 characters long"
+        ));
+    }
+    Ok(())
+}
+
+impl PostCreate {
+    pub fn validate(&mut self) -> Result<(), ErrorResponse> {
+        self.message.validate()?;
+        if self.message.reference.is_some() {
+            return Err(error!(
+                VALIDATION,
+                "reference", "Posts can't reference messages, use parent_id instead"
+            ));
+        }
+        if self.message.disguise.is_some() {
+            return Err(error!(VALIDATION, "_disguise", "Posts can't be disguised"));
+        }
+        match self.parent_id {
+            Some(_) => {
+                if self.title.is_some() {
+                    return Err(error!(VALIDATION, "title", "Comments can't have a title"));
+                }
+                if self.flair.is_some() {
+                    return Err(error!(VALIDATION, "flair", "Comments can't have a flair"));
+                }
+            }
+            None => match self.title {
+                Some(ref mut title) => validate_title(title)?,
+                None => {
+                    return Err(error!(VALIDATION, "title", "Posts must have a title"));
+                }
+            },
+        }
+        if let Some(ref mut flair) = self.flair {
+            validate_flair(flair)?;
+        }
+        Ok(())
+    }
+}
+
+impl Post {
+    /// Build a post from a `posts` row which also has a `comment_count` column.
+    pub(crate) async fn populate<C: AsyncCommands>(
+        row: &PgRow,
+        db: &mut PoolConnection<Postgres>,
+        cache: &mut C,
+    ) -> Result<Self, ErrorResponse> {
+        let id = row.get::<i64, _>("id") as u64;
+        let author = match row.get::<Option<i64>, _>("author_id") {
+            Some(id) => User::get(id as u64, None, db, cache).await?,
+            None => User::deleted(),
+        };
+        let attachment_rows = sqlx::query!(
+            "
+SELECT *
+FROM post_attachments
+WHERE post_id = $1
+            ",
+            id as i64
+        )
+        .fetch_all(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch post attachments {}: {}", id, err);
+            error!(SERVER, "Failed to fetch post data")
+        })?;
+        let mut attachments = vec![];
+        for attachment_row in attachment_rows {
+            let file = File::get(attachment_row.file_id as u64, "attachments", db)
+                .await
+                .ok_or_else(|| {
+                    error!(
+                        VALIDATION,
+                        "attachment-file", "Attachment file has vanished..."
+                    )
+                })?;
+            attachments.push(Attachment {
+                file: file.get_file_data(),
+                description: attachment_row.description,
+                spoiler: attachment_row.spoiler,
+            });
+        }
+        let embeds = sqlx::query!(
+            r#"
+SELECT embed as "embed: Json<Embed>"
+FROM post_embeds
+WHERE post_id = $1
+            "#,
+            id as i64
+        )
+        .fetch_all(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch post embeds {}: {}", id, err);
+            error!(SERVER, "Failed to fetch post data")
+        })?
+        .into_iter()
+        .map(|r| r.embed.0)
+        .collect();
+
+        Ok(Self {
+            id,
+            channel_id: row.get::<i64, _>("channel_id") as u64,
+            author,
+            title: row.get("title"),
+            flair: row.get("flair"),
+            parent_id: row.get::<Option<i64>, _>("parent_id").map(|p| p as u64),
+            content: row.get("content"),
+            attachments,
+            embeds,
+            score: row.get("score"),
+            comment_count: row.get::<i64, _>("comment_count") as u32,
+            comments: vec![],
+        })
+    }
+
+    pub async fn create<C: AsyncCommands>(
+        mut post: PostCreate,
+        channel_id: u64,
+        author_id: u64,
+        id_generator: &mut IdGenerator,
+        db: &mut PoolConnection<Postgres>,
+        cache: &mut C,
+    ) -> Result<Self, ErrorResponse> {
+        post.validate()?;
+        let channel = Channel::get(channel_id, db, cache).await.map_err(|err| {
+            if let ErrorResponse::NotFound { .. } = err {
+                error!(VALIDATION, "channel", "Channel doesn't exist")
+            } else {
+                err
+            }
+        })?;
+        let sphere_id = match channel {
+            Channel::Forum(channel) => channel.sphere_id,
+            _ => {
+                return Err(error!(
+                    VALIDATION,
+                    "channel", "Posts can only be created in forum channels"
+                ))
+            }
+        };
+        Member::require_not_timed_out(author_id, sphere_id, db).await?;
+        if let Some(parent_id) = post.parent_id {
+            Self::get_unpopulated(parent_id, channel_id, db, cache)
+                .await
+                .map_err(|err| {
+                    if let ErrorResponse::NotFound { .. } = err {
+                        error!(VALIDATION, "parent_id", "Parent post doesn't exist")
+                    } else {
+                        err
+                    }
+                })?;
+        }
+        let author = User::get(author_id, None, db, cache).await?;
+
+        // gather attachment files pre-transaction
+        let attachments = Attachment::gather(post.message.attachments, db).await?;
+
+        let id = id_generator.generate();
+        let mut transaction = db.begin().await.map_err(|err| {
+            log::error!("Couldn't start post create transaction: {}", err);
+            error!(SERVER, "Failed to create post")
+        })?;
+        sqlx::query!(
+            "
+INSERT INTO posts(id, channel_id, author_id, parent_id, title, flair, content)
+VALUES($1, $2, $3, $4, $5, $6, $7)
+            ",
+            id as i64,
+            channel_id as i64,
+            author_id as i64,
+            post.parent_id.map(|p| p as i64),
+            post.title,
+            post.flair,
+            post.message.content,
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Couldn't create post by {} on {}: {}",
+                author_id,
+                channel_id,
+                err
+            );
+            error!(SERVER, "Failed to create post")
+        })?;
+        for attachment in attachments.iter() {
+            sqlx::query!(
+                "
+INSERT INTO post_attachments(post_id, file_id, description, spoiler)
+VALUES($1, $2, $3, $4)
+                ",
+                id as i64,
+                attachment.file.id as i64,
+                attachment.description,
+                attachment.spoiler
+            )
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| {
+                log::error!(
+                    "Couldn't add post attachment for file_id {} to {}: {}",
+                    attachment.file.id,
+                    id,
+                    err
+                );
+                error!(SERVER, "Failed to create post")
+            })?;
+        }
+        for embed in post.message.embeds.iter() {
+            sqlx::query!(
+                "
+INSERT INTO post_embeds(post_id, embed)
+VALUES($1, $2)
+                ",
+                id as i64,
+                serde_json::to_value(Embed::Custom(embed.clone())).unwrap(),
+            )
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| {
+                log::error!("Couldn't add post embed {:?} to {}: {}", embed, id, err);
+                error!(SERVER, "Failed to create post")
+            })?;
+        }
+        transaction.commit().await.map_err(|err| {
+            log::error!("Couldn't commit post create transaction: {}", err);
+            error!(SERVER, "Failed to create post")
+        })?;
+
+        Ok(Self {
+            id,
+            channel_id,
+            author,
+            title: post.title,
+            flair: post.flair,
+            parent_id: post.parent_id,
+            content: post.message.content,
+            attachments,
+            embeds: post.message.embeds.into_iter().map(Embed::Custom).collect(),
+            score: 0,
+            comment_count: 0,
+            comments: vec![],
+        })
+    }
+}
@@ -0,0 +1,174 @@
+use sqlx::{pool::PoolConnection, Acquire, Postgres};
+
+use crate::models::{ErrorResponse, Post, PostVote};
+
+impl PostVote {
+    pub fn validate(&self) -> Result<(), ErrorResponse> {
+        if self.vote != 1 && self.vote != -1 {
+            return Err(error!(
+                VALIDATION,
+                "vote", "The vote must be either 1 or -1"
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Post {
+    /// Set a user's vote on this post, removing it if `vote` is `None`.
+    ///
+    /// The difference from the user's previous vote is applied to both the post's score and its
+    /// author's social credit. Returns whether the author's social credit changed.
+    pub async fn vote(
+        &mut self,
+        user_id: u64,
+        vote: Option<PostVote>,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<bool, ErrorResponse> {
+        if let Some(vote) = &vote {
+            vote.validate()?;
+        }
+        if self.author.id == user_id {
+            return Err(error!(
+                VALIDATION,
+                "post", "You can't vote on your own posts"
+            ));
+        }
+
+        let mut transaction = db.begin().await.map_err(|err| {
+            log::error!("Couldn't start post vote transaction {}: {}", self.id, err);
+            error!(SERVER, "Failed to vote on post")
+        })?;
+
+        // locking the post serialises concurrent votes on it, including a user's first vote which
+        // has no existing row to lock
+        sqlx::query!(
+            "
+SELECT id
+FROM posts
+WHERE id = $1
+FOR UPDATE
+            ",
+            self.id as i64,
+        )
+        .fetch_optional(&mut *transaction)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't lock post {} for voting: {}", self.id, err);
+            error!(SERVER, "Failed to vote on post")
+        })?
+        .ok_or_else(|| error!(NOT_FOUND))?;
+
+        let previous = sqlx::query!(
+            "
+SELECT vote
+FROM post_votes
+WHERE post_id = $1
+    AND user_id = $2
+            ",
+            self.id as i64,
+            user_id as i64,
+        )
+        .fetch_optional(&mut *transaction)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Couldn't fetch {}'s previous vote on post {}: {}",
+                user_id,
+                self.id,
+                err
+            );
+            error!(SERVER, "Failed to vote on post")
+        })?
+        .map(|r| r.vote)
+        .unwrap_or(0);
+        match &vote {
+            Some(vote) => {
+                sqlx::query!(
+                    "
+INSERT INTO post_votes(post_id, user_id, vote)
+VALUES($1, $2, $3)
+ON CONFLICT (post_id, user_id) DO UPDATE SET vote = $3
+                    ",
+                    self.id as i64,
+                    user_id as i64,
+                    vote.vote as i16,
+                )
+                .execute(&mut *transaction)
+                .await
+            }
+            None => {
+                sqlx::query!(
+                    "
+DELETE FROM post_votes
+WHERE post_id = $1
+    AND user_id = $2
+                    ",
+                    self.id as i64,
+                    user_id as i64,
+                )
+                .execute(&mut *transaction)
+                .await
+            }
+        }
+        .map_err(|err| {
+            log::error!(
+                "Couldn't store {}'s vote on post {}: {}",
+                user_id,
+                self.id,
+                err
+            );
+            error!(SERVER, "Failed to vote on post")
+        })?;
+
+        let delta = vote.map(|v| v.vote as i32).unwrap_or(0) - previous as i32;
+        if delta == 0 {
+            return Ok(false);
+        }
+        let post = sqlx::query!(
+            "
+UPDATE posts
+SET score = score + $1
+WHERE id = $2
+RETURNING score, author_id
+            ",
+            delta,
+            self.id as i64,
+        )
+        .fetch_one(&mut *transaction)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't update score of post {}: {}", self.id, err);
+            error!(SERVER, "Failed to vote on post")
+        })?;
+        self.score = post.score;
+        if let Some(author_id) = post.author_id {
+            sqlx::query!(
+                "
+UPDATE users
+SET social_credit = social_credit + $1
+WHERE id = $2
+                ",
+                delta,
+                author_id,
+            )
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| {
+                log::error!(
+                    "Couldn't update social credit of user {}: {}",
+                    author_id,
+                    err
+                );
+                error!(SERVER, "Failed to vote on post")
+            })?;
+        }
+
+        transaction.commit().await.map_err(|err| {
+            log::error!("Couldn't commit post vote transaction {}: {}", self.id, err);
+            error!(SERVER, "Failed to vote on post")
+        })?;
+
+        Ok(post.author_id.is_some())
+    }
+}
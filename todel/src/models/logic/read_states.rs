@@ -0,0 +1,200 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use sqlx::{pool::PoolConnection, PgConnection, Postgres};
+
+use crate::models::{ErrorResponse, ReadState, SphereChannel};
+
+/// Get the IDs of the users mentioned in a message's content, mentions being formatted as
+/// `<@user_id>`.
+pub(crate) fn get_mentions(content: &str) -> Vec<u64> {
+    lazy_static! {
+        static ref MENTION_REGEX: Regex =
+            Regex::new(r"<@(\d+)>").expect("Could not compile mention regex");
+    }
+    let mut mentions: Vec<u64> = MENTION_REGEX
+        .captures_iter(content)
+        .filter_map(|c| c[1].parse().ok())
+        .collect();
+    mentions.sort_unstable();
+    mentions.dedup();
+    mentions
+}
+
+impl ReadState {
+    /// Get a user's read states for every text channel they can view in the spheres they're in
+    /// and every direct message and group channel they're a part of.
+    pub async fn get_all(
+        user_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<Vec<Self>, ErrorResponse> {
+        let rows = sqlx::query!(
+            r#"
+SELECT
+    channels.id AS channel_id,
+    channels.sphere_id,
+    read_states.last_acked_id AS "last_acked_id?",
+    COALESCE(read_states.mention_count, 0) AS "mention_count!",
+    (
+        SELECT MAX(messages.id)
+        FROM messages
+        WHERE messages.channel_id = channels.id
+        AND messages.is_deleted = FALSE
+    ) AS last_message_id
+FROM channels
+LEFT JOIN read_states ON read_states.channel_id = channels.id
+    AND read_states.user_id = $1
+WHERE channels.is_deleted = FALSE
+    AND (
+        (
+            channels.channel_type = 'TEXT'
+            AND EXISTS (
+                SELECT 1
+                FROM members
+                WHERE members.id = $1
+                AND members.sphere_id = channels.sphere_id
+                AND members.is_deleted = FALSE
+            )
+        )
+        OR (
+            channels.channel_type = 'DIRECT'
+            AND $1 IN (channels.owner_id, channels.recipient_id)
+        )
+        OR (
+            channels.channel_type = 'GROUP'
+            AND EXISTS (
+                SELECT 1
+                FROM channel_members
+                WHERE channel_members.id = $1
+                AND channel_members.channel_id = channels.id
+            )
+        )
+    )
+            "#,
+            user_id as i64
+        )
+        .fetch_all(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch read states of user {}: {}", user_id, err);
+            error!(SERVER, "Failed to fetch read states")
+        })?;
+        let mut read_states = Vec::with_capacity(rows.len());
+        for r in rows {
+            if r.sphere_id.is_some()
+                && !SphereChannel::can_view(r.channel_id as u64, user_id, db).await?
+            {
+                continue;
+            }
+            read_states.push(Self {
+                channel_id: r.channel_id as u64,
+                last_acked_id: r.last_acked_id.map(|id| id as u64),
+                last_message_id: r.last_message_id.map(|id| id as u64),
+                mention_count: r.mention_count as u32,
+            });
+        }
+        Ok(read_states)
+    }
+
+    /// Acknowledge every message of a channel up to a message, resetting the user's mention
+    /// count in it.
+    ///
+    /// Returns the ID of the last message the user has acknowledged in the channel, which is
+    /// never moved back by acknowledging older messages.
+    pub async fn ack(
+        user_id: u64,
+        channel_id: u64,
+        message_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<u64, ErrorResponse> {
+        sqlx::query!(
+            r#"
+INSERT INTO read_states(user_id, channel_id, last_acked_id, mention_count)
+SELECT $1, messages.channel_id, messages.id, 0
+FROM messages
+WHERE messages.id = $3
+    AND messages.channel_id = $2
+    AND messages.is_deleted = FALSE
+ON CONFLICT (user_id, channel_id)
+DO UPDATE SET
+    last_acked_id = GREATEST(read_states.last_acked_id, EXCLUDED.last_acked_id),
+    mention_count = 0
+RETURNING last_acked_id AS "last_acked_id!"
+            "#,
+            user_id as i64,
+            channel_id as i64,
+            message_id as i64
+        )
+        .fetch_optional(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Couldn't ack message {} in channel {} for user {}: {}",
+                message_id,
+                channel_id,
+                user_id,
+                err
+            );
+            error!(SERVER, "Failed to acknowledge message")
+        })?
+        .map(|r| r.last_acked_id as u64)
+        .ok_or_else(|| error!(NOT_FOUND))
+    }
+
+    /// Get the users mentioned in a new message's content who can view the channel it was sent
+    /// in, leaving out its author.
+    pub(crate) async fn get_visible_mentions(
+        channel_id: u64,
+        author_id: u64,
+        content: &str,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<Vec<u64>, ErrorResponse> {
+        let mut mentions = vec![];
+        for id in get_mentions(content) {
+            if id != author_id && SphereChannel::can_view(channel_id, id, db).await? {
+                mentions.push(id);
+            }
+        }
+        Ok(mentions)
+    }
+
+    /// Count the mentions of a new message towards the mentioned members' read states.
+    ///
+    /// This takes a plain connection so that it can run inside of the message's transaction.
+    pub(crate) async fn add_mentions(
+        channel_id: u64,
+        sphere_id: u64,
+        mentions: &[u64],
+        db: &mut PgConnection,
+    ) -> Result<(), ErrorResponse> {
+        if mentions.is_empty() {
+            return Ok(());
+        }
+        let mentions: Vec<i64> = mentions.iter().map(|id| *id as i64).collect();
+        sqlx::query!(
+            "
+INSERT INTO read_states(user_id, channel_id, mention_count)
+SELECT members.id, $1, 1
+FROM members
+WHERE members.sphere_id = $2
+    AND members.id = ANY($3)
+    AND members.is_deleted = FALSE
+ON CONFLICT (user_id, channel_id)
+DO UPDATE SET mention_count = read_states.mention_count + 1
+            ",
+            channel_id as i64,
+            sphere_id as i64,
+            &mentions
+        )
+        .execute(db)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Couldn't add mentions to read states in channel {}: {}",
+                channel_id,
+                err
+            );
+            error!(SERVER, "Failed to create message")
+        })?;
+        Ok(())
+    }
+}
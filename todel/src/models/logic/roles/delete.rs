@@ -0,0 +1,89 @@
+use sqlx::{pool::PoolConnection, Acquire, Postgres};
+
+use crate::models::{ErrorResponse, Role};
+
+impl Role {
+    pub async fn delete(
+        sphere_id: u64,
+        role_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<(), ErrorResponse> {
+        let current_position = Self::get(role_id, sphere_id, db)
+            .await
+            .map_err(|err| {
+                if let ErrorResponse::NotFound { .. } = err {
+                    error!(VALIDATION, "role", "Role doesn't exist")
+                } else {
+                    err
+                }
+            })?
+            .position;
+
+        let mut transaction = db.begin().await.map_err(|err| {
+            log::error!("Couldn't start role delete transaction: {}", err);
+            error!(SERVER, "Failed to delete role")
+        })?;
+
+        sqlx::query!(
+            "
+UPDATE roles
+SET
+    position = CASE
+        WHEN (position = $2) THEN -1
+        ELSE position - 1
+        END,
+    is_deleted = (position = $2)
+WHERE sphere_id = $1
+    AND position >= $2
+    AND is_deleted = FALSE
+            ",
+            sphere_id as i64,
+            current_position as i32,
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't update role positions after deletion: {}", err);
+            error!(SERVER, "Failed to delete role")
+        })?;
+
+        sqlx::query!(
+            "
+DELETE FROM member_roles
+WHERE role_id = $1
+            ",
+            role_id as i64,
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't unassign deleted role {}: {}", role_id, err);
+            error!(SERVER, "Failed to delete role")
+        })?;
+
+        sqlx::query!(
+            "
+DELETE FROM channel_overwrites
+WHERE target_id = $1
+            ",
+            role_id as i64,
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Couldn't delete deleted role {}'s overwrites: {}",
+                role_id,
+                err
+            );
+            error!(SERVER, "Failed to delete role")
+        })?;
+
+        transaction.commit().await.map_err(|err| {
+            log::error!("Couldn't commit role delete transaction: {}", err);
+            error!(SERVER, "Failed to delete role")
+        })?;
+
+        Ok(())
+    }
+}
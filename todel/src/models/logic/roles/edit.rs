@@ -0,0 +1,183 @@
+use sqlx::{pool::PoolConnection, Acquire, Postgres};
+
+use crate::models::{ErrorResponse, Role, RoleEdit};
+
+use super::{validate_role_colour, validate_role_permissions};
+
+impl RoleEdit {
+    pub fn validate(&self) -> Result<(), ErrorResponse> {
+        if self.name.is_none()
+            && self.colour.is_none()
+            && self.position.is_none()
+            && self.permissions.is_none()
+        {
+            return Err(error!(
+                VALIDATION,
+                "body",
+                "At least one of 'name', 'colour', 'position' or 'permissions' must be provided."
+            ));
+        }
+        if let Some(name) = &self.name {
+            if name.is_empty() || name.len() > 32 {
+                return Err(error!(
+                    VALIDATION,
+                    "name", "The role's name must be between 1 and 32 characters long"
+                ));
+            }
+        }
+        if let Some(Some(colour)) = self.colour {
+            validate_role_colour(colour)?;
+        }
+        if let Some(permissions) = self.permissions {
+            validate_role_permissions(permissions)?;
+        }
+        Ok(())
+    }
+}
+
+impl Role {
+    pub async fn edit(
+        mut role: RoleEdit,
+        sphere_id: u64,
+        role_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<(Self, RoleEdit), ErrorResponse> {
+        role.validate()?;
+
+        let current_role = Self::get(role_id, sphere_id, db).await.map_err(|err| {
+            if let ErrorResponse::NotFound { .. } = err {
+                error!(VALIDATION, "role", "Role doesn't exist")
+            } else {
+                err
+            }
+        })?;
+
+        let mut transaction = db.begin().await.map_err(|err| {
+            log::error!("Couldn't start role edit transaction: {}", err);
+            error!(SERVER, "Failed to edit role")
+        })?;
+
+        if let Some(ref name) = role.name {
+            sqlx::query!(
+                "
+UPDATE roles
+SET name = $1
+WHERE id = $2
+                ",
+                name,
+                role_id as i64,
+            )
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| {
+                log::error!("Couldn't update role name: {}", err);
+                error!(SERVER, "Failed to edit role")
+            })?;
+        }
+
+        if let Some(colour) = role.colour {
+            sqlx::query!(
+                "
+UPDATE roles
+SET colour = $1
+WHERE id = $2
+                ",
+                colour.map(|c| c as i32),
+                role_id as i64,
+            )
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| {
+                log::error!("Couldn't update role colour: {}", err);
+                error!(SERVER, "Failed to edit role")
+            })?;
+        }
+
+        if let Some(permissions) = role.permissions {
+            sqlx::query!(
+                "
+UPDATE roles
+SET permissions = $1
+WHERE id = $2
+                ",
+                permissions as i64,
+                role_id as i64,
+            )
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| {
+                log::error!("Couldn't update role permissions: {}", err);
+                error!(SERVER, "Failed to edit role")
+            })?;
+        }
+
+        if let Some(mut position) = role.position {
+            let role_count = sqlx::query!(
+                "
+SELECT COUNT(id)
+FROM roles
+WHERE sphere_id = $1
+    AND is_deleted = FALSE
+                ",
+                sphere_id as i64
+            )
+            .fetch_one(&mut *transaction)
+            .await
+            .map_err(|err| {
+                log::error!("Couldn't fetch sphere's role count: {}", err);
+                error!(SERVER, "Failed to edit role")
+            })?
+            .count
+            .ok_or_else(|| {
+                log::error!("Couldn't fetch sphere's role count");
+                error!(SERVER, "Failed to edit role")
+            })? as u32;
+
+            if position >= role_count {
+                position = role_count - 1;
+                role.position = Some(position);
+            }
+
+            sqlx::query!(
+                "
+UPDATE roles
+SET position = CASE
+    WHEN (position = $1) THEN $2
+    WHEN ($1 > $2)       THEN position + (position BETWEEN $2 AND $1)::int
+    ELSE                      position - (position BETWEEN $1 AND $2)::int
+    END
+WHERE sphere_id = $3
+    AND is_deleted = FALSE
+                ",
+                current_role.position as i64,
+                position as i64,
+                sphere_id as i64,
+            )
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| {
+                log::error!("Couldn't update role position: {}", err);
+                error!(SERVER, "Failed to edit role")
+            })?;
+        }
+
+        transaction.commit().await.map_err(|err| {
+            log::error!("Couldn't commit role edit transaction: {}", err);
+            error!(SERVER, "Failed to edit role")
+        })?;
+
+        let result = {
+            let role = role.clone();
+            Self {
+                id: role_id,
+                sphere_id,
+                name: role.name.unwrap_or(current_role.name),
+                colour: role.colour.unwrap_or(current_role.colour),
+                position: role.position.unwrap_or(current_role.position),
+                permissions: role.permissions.unwrap_or(current_role.permissions),
+            }
+        };
+
+        Ok((result, role))
+    }
+}
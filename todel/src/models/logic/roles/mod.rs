@@ -0,0 +1,180 @@
+mod delete;
+mod edit;
+
+use sqlx::{pool::PoolConnection, postgres::PgRow, FromRow, Postgres, Row};
+
+use crate::{
+    ids::IdGenerator,
+    models::{ErrorResponse, Role, RoleCreate, Sphere, SpherePermission},
+};
+
+impl FromRow<'_, PgRow> for Role {
+    fn from_row(row: &PgRow) -> sqlx::Result<Self> {
+        Ok(Self {
+            id: row.get::<i64, _>("id") as u64,
+            sphere_id: row.get::<i64, _>("sphere_id") as u64,
+            name: row.get("name"),
+            colour: row.get::<Option<i32>, _>("colour").map(|c| c as u32),
+            position: row.get::<i32, _>("position") as u32,
+            permissions: row.get::<i64, _>("permissions") as u64,
+        })
+    }
+}
+
+pub(crate) fn validate_role_colour(colour: u32) -> Result<(), ErrorResponse> {
+    if colour > 0xFFFFFF {
+        return Err(error!(
+            VALIDATION,
+            "colour", "The role's colour must be a valid 0xRRGGBB integer"
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn validate_role_permissions(permissions: u64) -> Result<(), ErrorResponse> {
+    if permissions & !SpherePermission::ALL != 0 {
+        return Err(error!(
+            VALIDATION,
+            "permissions", "The role's permissions contain unknown bits"
+        ));
+    }
+    Ok(())
+}
+
+impl RoleCreate {
+    pub fn validate(&self) -> Result<(), ErrorResponse> {
+        if self.name.is_empty() || self.name.len() > 32 {
+            return Err(error!(
+                VALIDATION,
+                "name", "The role's name must be between 1 and 32 characters long"
+            ));
+        }
+        if let Some(colour) = self.colour {
+            validate_role_colour(colour)?;
+        }
+        validate_role_permissions(self.permissions)?;
+        Ok(())
+    }
+}
+
+impl Role {
+    pub async fn create(
+        role: RoleCreate,
+        sphere_id: u64,
+        id_generator: &mut IdGenerator,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<Self, ErrorResponse> {
+        role.validate()?;
+
+        Sphere::get_unpopulated(sphere_id, db)
+            .await
+            .map_err(|err| {
+                if let ErrorResponse::NotFound { .. } = err {
+                    error!(VALIDATION, "sphere", "Sphere doesn't exist")
+                } else {
+                    err
+                }
+            })?;
+
+        let role_count = sqlx::query!(
+            "
+SELECT COUNT(id)
+FROM roles
+WHERE sphere_id = $1
+    AND is_deleted = FALSE
+            ",
+            sphere_id as i64,
+        )
+        .fetch_one(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch sphere's role count: {}", err);
+            error!(SERVER, "Failed to create role")
+        })?
+        .count
+        .ok_or_else(|| {
+            log::error!("Couldn't fetch sphere's role count");
+            error!(SERVER, "Failed to create role")
+        })?;
+        if role_count >= 250 {
+            return Err(error!(VALIDATION, "roles", "Sphere exceeded role limit"));
+        }
+
+        let role_id = id_generator.generate();
+        // New roles are placed at the bottom of the hierarchy.
+        sqlx::query!(
+            "
+INSERT INTO roles(id, sphere_id, name, colour, position, permissions)
+VALUES($1, $2, $3, $4, $5, $6)
+            ",
+            role_id as i64,
+            sphere_id as i64,
+            role.name,
+            role.colour.map(|c| c as i32),
+            role_count as i32,
+            role.permissions as i64,
+        )
+        .execute(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't create role in sphere {}: {}", sphere_id, err);
+            error!(SERVER, "Failed to create role")
+        })?;
+
+        Ok(Self {
+            id: role_id,
+            sphere_id,
+            name: role.name,
+            colour: role.colour,
+            position: role_count as u32,
+            permissions: role.permissions,
+        })
+    }
+
+    pub async fn get(
+        id: u64,
+        sphere_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<Self, ErrorResponse> {
+        sqlx::query_as(
+            "
+SELECT *
+FROM roles
+WHERE id = $1
+    AND sphere_id = $2
+    AND is_deleted = FALSE
+            ",
+        )
+        .bind(id as i64)
+        .bind(sphere_id as i64)
+        .fetch_optional(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch role {}: {}", id, err);
+            error!(SERVER, "Failed to get role")
+        })?
+        .ok_or_else(|| error!(NOT_FOUND))
+    }
+
+    pub async fn get_all(
+        sphere_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<Vec<Self>, ErrorResponse> {
+        sqlx::query_as(
+            "
+SELECT *
+FROM roles
+WHERE sphere_id = $1
+    AND is_deleted = FALSE
+ORDER BY position
+            ",
+        )
+        .bind(sphere_id as i64)
+        .fetch_all(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch roles for sphere {}: {}", sphere_id, err);
+            error!(SERVER, "Failed to get roles")
+        })
+    }
+}
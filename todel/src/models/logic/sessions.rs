@@ -9,15 +9,20 @@ use crate::{
     ids::IdGenerator,
     models::{
         ErrorResponse, PasswordDeleteCredentials, Session, SessionCreate, SessionCreated, User,
+        UserBadge,
     },
+    Conf,
 };
 
-use super::Secret;
+use super::{get_unix_timestamp, Secret};
+
+/// How many seconds have to pass before a session's last use is recorded again.
+const LAST_USED_PRECISION: u64 = 60;
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct SessionTokenClaims {
-    user_id: u64,
-    session_id: u64,
+    pub(crate) user_id: u64,
+    pub(crate) session_id: u64,
 }
 
 impl SessionCreate {
@@ -44,6 +49,7 @@ FROM users
 WHERE (username = $1
 OR email = $1)
 AND is_deleted = FALSE
+AND password IS NOT NULL -- bots can't log in with a password
             ",
             session.identifier
         )
@@ -53,27 +59,38 @@ AND is_deleted = FALSE
             log::error!("Could not fetch the user's password: {}", err);
             error!(SERVER, "Failed to fetch the user's password")
         })?
-        .ok_or_else(|| error!(NOT_FOUND))?;
+        // unknown accounts get the same error as a wrong password to not reveal which exist
+        .ok_or_else(|| error!(UNAUTHORIZED))?;
+        let password = user.password.ok_or_else(|| error!(UNAUTHORIZED))?;
         verifier
             .verify_password(
                 session.password.as_bytes(),
-                &PasswordHash::new(&user.password).map_err(|err| {
+                &PasswordHash::new(&password).map_err(|err| {
                     log::error!("Couldn't parse password hash: {}", err);
                     error!(SERVER, "Failed to validate the user's password")
                 })?,
             )
             .map_err(|_| error!(UNAUTHORIZED))?;
+        User::validate_two_factor(
+            user.id as u64,
+            session.two_factor_code.as_deref(),
+            verifier,
+            db,
+        )
+        .await?;
         let id = id_generator.generate();
+        let now = get_unix_timestamp();
         sqlx::query!(
             "
-INSERT INTO sessions(id, user_id, platform, client, ip)
-VALUES($1, $2, $3, $4, $5)
+INSERT INTO sessions(id, user_id, platform, client, ip, created_at, last_used_at, last_ip)
+VALUES($1, $2, $3, $4, $5, $6, $6, $5)
             ",
             id as i64,
             user.id as i64,
             session.platform,
             session.client,
             IpNetwork::from(ip),
+            now as i64,
         )
         .execute(&mut **db)
         .await
@@ -97,30 +114,112 @@ VALUES($1, $2, $3, $4, $5)
                 platform: session.platform,
                 client: session.client,
                 ip,
+                bot: false,
+                application_id: None,
+                scopes: None,
+                created_at: now,
+                last_used_at: now,
+                last_ip: ip,
+            },
+        })
+    }
+
+    /// Issue a new token for a bot, used by its [`Application`](crate::models::Application).
+    pub async fn create_bot(
+        bot_id: u64,
+        ip: IpAddr,
+        secret: &Secret,
+        id_generator: &mut IdGenerator,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<SessionCreated, ErrorResponse> {
+        let id = id_generator.generate();
+        let platform = "bot".to_string();
+        let client = "application".to_string();
+        let now = get_unix_timestamp();
+        sqlx::query!(
+            "
+INSERT INTO sessions(id, user_id, platform, client, ip, created_at, last_used_at, last_ip)
+VALUES($1, $2, $3, $4, $5, $6, $6, $5)
+            ",
+            id as i64,
+            bot_id as i64,
+            platform,
+            client,
+            IpNetwork::from(ip),
+            now as i64,
+        )
+        .execute(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Failed to store bot session in database: {}", err);
+            error!(SERVER, "Could not save session data")
+        })?;
+        let claims = SessionTokenClaims {
+            user_id: bot_id,
+            session_id: id,
+        };
+        let token = claims.sign_with_key(&secret.0).map_err(|err| {
+            log::error!("Couldn't sign JWT: {}", err);
+            error!(SERVER, "Failed to generate a token for the bot")
+        })?;
+        Ok(SessionCreated {
+            token,
+            session: Self {
+                id,
+                user_id: bot_id,
+                platform,
+                client,
+                ip,
+                bot: true,
+                application_id: None,
+                scopes: None,
+                created_at: now,
+                last_used_at: now,
+                last_ip: ip,
             },
         })
     }
 
+    /// The identifier rate limits of this session's user are tracked under, bots are tracked
+    /// separately from regular users.
+    pub fn rate_limit_identifier(&self) -> String {
+        if self.bot {
+            format!("bot:{}", self.user_id)
+        } else {
+            self.user_id.to_string()
+        }
+    }
+
+    /// Validate a token and get the session it belongs to, recording that it was used.
+    ///
+    /// Sessions of users other than bots expire once they go unused for longer than the
+    /// instance's `session_idle_timeout` or outlive its `session_max_lifetime`, OAuth2 sessions
+    /// instead expire along with their access token and live on through their refresh token.
     pub async fn validate_token(
         token: &str,
+        ip: IpAddr,
         secret: &Secret,
+        conf: &Conf,
         db: &mut PoolConnection<Postgres>,
     ) -> Result<Self, ErrorResponse> {
         let claims: SessionTokenClaims = token
             .verify_with_key(&secret.0)
             .map_err(|_| error!(UNAUTHORIZED))?;
-        let session = sqlx::query!(
+        let now = get_unix_timestamp();
+        let (oauth, mut session) = sqlx::query!(
             "
-SELECT s.id, s.user_id, s.platform, s.client, s.ip
+SELECT s.id, s.user_id, s.platform, s.client, s.ip, s.application_id, s.scopes, s.created_at, s.last_used_at, s.last_ip, s.refresh_token_hash, u.badges
 FROM sessions s
 LEFT JOIN users u
 ON s.user_id = u.id
 WHERE s.id = $1
 AND s.user_id = $2
+AND (s.expires_at IS NULL OR s.expires_at > $3)
 AND u.is_deleted = FALSE
             ",
             claims.session_id as i64,
-            claims.user_id as i64
+            claims.user_id as i64,
+            now as i64,
         )
         .fetch_optional(&mut **db)
         .await
@@ -128,26 +227,100 @@ AND u.is_deleted = FALSE
             log::error!("Could not fetch the user's session: {}", err);
             error!(SERVER, "Failed to fetch the user's session")
         })?
-        .map(|s| Self {
-            id: s.id as u64,
-            user_id: s.user_id as u64,
-            platform: s.platform,
-            client: s.client,
-            ip: s.ip.ip(),
+        .map(|s| {
+            (
+                s.refresh_token_hash.is_some(),
+                Self {
+                    id: s.id as u64,
+                    user_id: s.user_id as u64,
+                    platform: s.platform,
+                    client: s.client,
+                    ip: s.ip.ip(),
+                    bot: UserBadge::Bot.is_set(s.badges as u64),
+                    application_id: s.application_id.map(|a| a as u64),
+                    scopes: s.scopes.map(|s| s as u64),
+                    created_at: s.created_at as u64,
+                    last_used_at: s.last_used_at as u64,
+                    last_ip: s.last_ip.ip(),
+                },
+            )
         })
         .ok_or_else(|| error!(UNAUTHORIZED))?; // no such session exists
+        if !oauth && session.is_expired(now, conf) {
+            return Err(error!(UNAUTHORIZED));
+        }
+        if now - session.last_used_at >= LAST_USED_PRECISION || session.last_ip != ip {
+            sqlx::query!(
+                "
+UPDATE sessions
+SET last_used_at = $1, last_ip = $2
+WHERE id = $3
+                ",
+                now as i64,
+                IpNetwork::from(ip),
+                session.id as i64,
+            )
+            .execute(&mut **db)
+            .await
+            .map_err(|err| {
+                log::error!("Couldn't update the last use of a session: {}", err);
+                error!(SERVER, "Failed to fetch the user's session")
+            })?;
+            session.last_used_at = now;
+            session.last_ip = ip;
+        }
         Ok(session)
     }
 
+    /// Check whether a session has gone unused for too long or outlived the instance's maximum
+    /// session lifetime, bot sessions never expire.
+    fn is_expired(&self, now: u64, conf: &Conf) -> bool {
+        if self.bot {
+            return false;
+        }
+        let idle_timeout = conf.oprish.session_idle_timeout;
+        let max_lifetime = conf.oprish.session_max_lifetime;
+        (idle_timeout != 0 && now.saturating_sub(self.last_used_at) > idle_timeout)
+            || (max_lifetime != 0 && now.saturating_sub(self.created_at) > max_lifetime)
+    }
+
+    /// Delete every session that has expired, bot and OAuth2 sessions are kept.
+    pub async fn clean_up_expired(
+        conf: &Conf,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<(), sqlx::Error> {
+        let now = get_unix_timestamp() as i64;
+        sqlx::query!(
+            "
+DELETE FROM sessions s
+USING users u
+WHERE s.user_id = u.id
+AND u.badges & $1 = 0
+AND s.refresh_token_hash IS NULL
+AND (($2::BIGINT > 0 AND s.last_used_at < $4::BIGINT - $2)
+    OR ($3::BIGINT > 0 AND s.created_at < $4 - $3))
+            ",
+            UserBadge::Bot as i64,
+            conf.oprish.session_idle_timeout as i64,
+            conf.oprish.session_max_lifetime as i64,
+            now,
+        )
+        .execute(&mut **db)
+        .await?;
+        Ok(())
+    }
+
     pub async fn get_sessions(
         user: u64,
         db: &mut PoolConnection<Postgres>,
     ) -> Result<Vec<Session>, ErrorResponse> {
         Ok(sqlx::query!(
             "
-        SELECT *
-        FROM sessions
-        WHERE user_id = $1
+        SELECT s.*, u.badges
+        FROM sessions s
+        JOIN users u
+        ON s.user_id = u.id
+        WHERE s.user_id = $1
                     ",
             user as i64
         )
@@ -164,6 +337,12 @@ AND u.is_deleted = FALSE
             platform: s.platform,
             client: s.client,
             ip: s.ip.ip(),
+            bot: UserBadge::Bot.is_set(s.badges as u64),
+            application_id: s.application_id.map(|a| a as u64),
+            scopes: s.scopes.map(|s| s as u64),
+            created_at: s.created_at as u64,
+            last_used_at: s.last_used_at as u64,
+            last_ip: s.last_ip.ip(),
         })
         .collect())
     }
@@ -176,6 +355,7 @@ AND u.is_deleted = FALSE
         db: &mut PoolConnection<Postgres>,
     ) -> Result<(), ErrorResponse> {
         User::validate_password(user_id, &delete.password, verifier, db).await?;
+        User::validate_two_factor(user_id, delete.two_factor_code.as_deref(), verifier, db).await?;
         sqlx::query!(
             "
 DELETE FROM sessions
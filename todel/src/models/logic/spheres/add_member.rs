@@ -1,13 +1,21 @@
-use sqlx::{pool::PoolConnection, Postgres};
+use sqlx::{pool::PoolConnection, Acquire, Postgres};
 
-use crate::models::{ErrorResponse, Member, Sphere, User};
+use crate::models::{logic::get_unix_timestamp, ErrorResponse, Member, Sphere, User};
 
 impl Sphere {
+    /// Add a user to this sphere, recording the invite they joined through if any.
+    ///
+    /// Using an invite counts towards its uses, invites which have expired or ran out of uses are
+    /// rejected.
     pub async fn add_member(
         &self,
         user_id: u64,
+        invite_code: Option<&str>,
         db: &mut PoolConnection<Postgres>,
     ) -> Result<Member, ErrorResponse> {
+        if self.is_banned(user_id, db).await? {
+            return Err(error!(FORBIDDEN));
+        }
         if sqlx::query!(
             "
             SELECT id
@@ -36,20 +44,53 @@ impl Sphere {
                 "sphere", "User is already in this sphere"
             ));
         }
+        let mut transaction = db.begin().await.map_err(|err| {
+            log::error!("Couldn't start sphere join transaction: {}", err);
+            error!(SERVER, "Failed to join sphere")
+        })?;
+        if let Some(code) = invite_code {
+            let result = sqlx::query!(
+                "
+UPDATE invites
+SET uses = uses + 1
+WHERE code = $1
+    AND sphere_id = $2
+    AND (max_uses IS NULL OR uses < max_uses)
+    AND (expires_at IS NULL OR expires_at > $3)
+                ",
+                code,
+                self.id as i64,
+                get_unix_timestamp() as i64,
+            )
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| {
+                log::error!("Couldn't use invite {}: {}", code, err);
+                error!(SERVER, "Failed to join sphere")
+            })?;
+            if result.rows_affected() == 0 {
+                return Err(error!(NOT_FOUND));
+            }
+        }
         sqlx::query!(
             "
-            INSERT INTO members(id, sphere_id)
-            VALUES($1, $2)
+            INSERT INTO members(id, sphere_id, invite_code)
+            VALUES($1, $2, $3)
             ",
             user_id as i64,
-            self.id as i64
+            self.id as i64,
+            invite_code,
         )
-        .execute(&mut **db)
+        .execute(&mut *transaction)
         .await
         .map_err(|err| {
             log::error!("Couldn't insert member into sphere {}: {}", self.id, err);
             error!(SERVER, "Failed to join sphere")
         })?;
+        transaction.commit().await.map_err(|err| {
+            log::error!("Couldn't commit sphere join transaction: {}", err);
+            error!(SERVER, "Failed to join sphere")
+        })?;
         Ok(Member {
             sphere_id: self.id,
             user: User::get_unfiltered(user_id, db).await?,
@@ -58,6 +99,8 @@ impl Sphere {
             sphere_banner: None,
             sphere_bio: None,
             sphere_status: None,
+            roles: vec![],
+            timed_out_until: None,
         })
     }
 }
@@ -0,0 +1,218 @@
+use sqlx::{pool::PoolConnection, postgres::PgRow, Acquire, FromRow, Postgres, Row};
+
+use crate::models::{
+    logic::get_unix_timestamp, AuditLogActor, AuditLogChanges, AuditLogEntry, ErrorResponse,
+    Sphere, SphereBan, SphereBanCreate,
+};
+
+impl FromRow<'_, PgRow> for SphereBan {
+    fn from_row(row: &PgRow) -> sqlx::Result<Self> {
+        Ok(Self {
+            user_id: row.get::<i64, _>("user_id") as u64,
+            sphere_id: row.get::<i64, _>("sphere_id") as u64,
+            moderator_id: row.get::<i64, _>("moderator_id") as u64,
+            reason: row.get("reason"),
+            expires_at: row.get::<Option<i64>, _>("expires_at").map(|e| e as u64),
+        })
+    }
+}
+
+impl SphereBanCreate {
+    pub fn validate(&self) -> Result<(), ErrorResponse> {
+        if let Some(reason) = &self.reason {
+            if reason.is_empty() || reason.len() > 512 {
+                return Err(error!(
+                    VALIDATION,
+                    "reason", "The ban's reason must be between 1 and 512 characters long"
+                ));
+            }
+        }
+        if self.duration == Some(0) {
+            return Err(error!(
+                VALIDATION,
+                "duration", "The ban's duration must be greater than 0"
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Sphere {
+    /// Ban a user from this sphere, removing them from it if they're a member.
+    ///
+    /// The ban's reason is recorded in the audit log if the actor didn't give one.
+    pub async fn ban_member(
+        &self,
+        user_id: u64,
+        ban: SphereBanCreate,
+        mut actor: AuditLogActor<'_>,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<SphereBan, ErrorResponse> {
+        ban.validate()?;
+        actor.validate()?;
+        let moderator_id = actor.user_id;
+        if user_id == self.owner_id {
+            return Err(error!(
+                VALIDATION,
+                "user", "The sphere's owner can't be banned"
+            ));
+        }
+        let mut transaction = db.begin().await.map_err(|err| {
+            log::error!("Couldn't start sphere ban transaction: {}", err);
+            error!(SERVER, "Failed to ban member")
+        })?;
+        self.delete_member(user_id, &mut transaction).await?;
+        let expires_at = ban.duration.map(|d| get_unix_timestamp() + d);
+        sqlx::query!(
+            "
+            INSERT INTO sphere_bans(sphere_id, user_id, moderator_id, reason, expires_at)
+            VALUES($1, $2, $3, $4, $5)
+            ON CONFLICT (sphere_id, user_id)
+            DO UPDATE SET moderator_id = $3, reason = $4, expires_at = $5
+            ",
+            self.id as i64,
+            user_id as i64,
+            moderator_id as i64,
+            ban.reason,
+            expires_at.map(|e| e as i64),
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Couldn't ban user {} from sphere {}: {}",
+                user_id,
+                self.id,
+                err
+            );
+            error!(SERVER, "Failed to ban member")
+        })?;
+        let ban = SphereBan {
+            user_id,
+            sphere_id: self.id,
+            moderator_id,
+            reason: ban.reason,
+            expires_at,
+        };
+        if actor.reason.is_none() {
+            actor.reason = ban.reason.clone();
+        }
+        AuditLogEntry::create(
+            self.id,
+            user_id,
+            AuditLogChanges::MemberBan { after: ban.clone() },
+            actor,
+            &mut transaction,
+        )
+        .await?;
+        transaction.commit().await.map_err(|err| {
+            log::error!("Couldn't commit sphere ban transaction: {}", err);
+            error!(SERVER, "Failed to ban member")
+        })?;
+        Ok(ban)
+    }
+
+    pub async fn unban_member(
+        &self,
+        user_id: u64,
+        actor: AuditLogActor<'_>,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<(), ErrorResponse> {
+        actor.validate()?;
+        let result = sqlx::query!(
+            "
+            DELETE FROM sphere_bans
+            WHERE sphere_id = $1
+            AND user_id = $2
+            ",
+            self.id as i64,
+            user_id as i64,
+        )
+        .execute(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Couldn't unban user {} from sphere {}: {}",
+                user_id,
+                self.id,
+                err
+            );
+            error!(SERVER, "Failed to unban member")
+        })?;
+        if result.rows_affected() == 0 {
+            return Err(error!(NOT_FOUND));
+        }
+        AuditLogEntry::create(self.id, user_id, AuditLogChanges::MemberUnban, actor, db).await?;
+        Ok(())
+    }
+
+    /// Get all of this sphere's bans that haven't expired yet.
+    pub async fn get_bans(
+        &self,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<Vec<SphereBan>, ErrorResponse> {
+        sqlx::query_as(
+            "
+            SELECT *
+            FROM sphere_bans
+            WHERE sphere_id = $1
+            AND (expires_at IS NULL OR expires_at > $2)
+            ",
+        )
+        .bind(self.id as i64)
+        .bind(get_unix_timestamp() as i64)
+        .fetch_all(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch bans for sphere {}: {}", self.id, err);
+            error!(SERVER, "Failed to get bans")
+        })
+    }
+
+    pub async fn is_banned(
+        &self,
+        user_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<bool, ErrorResponse> {
+        Ok(sqlx::query!(
+            "
+            SELECT user_id
+            FROM sphere_bans
+            WHERE sphere_id = $1
+            AND user_id = $2
+            AND (expires_at IS NULL OR expires_at > $3)
+            ",
+            self.id as i64,
+            user_id as i64,
+            get_unix_timestamp() as i64,
+        )
+        .fetch_optional(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Couldn't check if user {} is banned from sphere {}: {}",
+                user_id,
+                self.id,
+                err
+            );
+            error!(SERVER, "Failed to check user ban")
+        })?
+        .is_some())
+    }
+
+    /// Delete every sphere ban that has expired.
+    pub async fn clean_up_expired_bans(
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "
+DELETE FROM sphere_bans
+WHERE expires_at <= $1
+            ",
+            get_unix_timestamp() as i64
+        )
+        .execute(&mut **db)
+        .await?;
+        Ok(())
+    }
+}
@@ -1,6 +1,9 @@
 use sqlx::{pool::PoolConnection, Acquire, Postgres};
 
-use crate::models::{ErrorResponse, File, Sphere, SphereEdit, SphereType};
+use crate::models::{
+    AuditLogActor, AuditLogChanges, AuditLogEntry, ErrorResponse, File, Sphere, SphereEdit,
+    SpherePermission, SphereType,
+};
 
 impl SphereEdit {
     pub fn validate(&self) -> Result<(), ErrorResponse> {
@@ -9,6 +12,9 @@ impl SphereEdit {
             && self.description.is_none()
             && self.icon.is_none()
             && self.banner.is_none()
+            && self.default_permissions.is_none()
+            && self.discoverable.is_none()
+            && self.invite_only.is_none()
         {
             return Err(error!(
                 VALIDATION,
@@ -16,6 +22,14 @@ impl SphereEdit {
                 "At least one of 'name', 'topic', 'position' or 'category_id' must be provided."
             ));
         }
+        if let Some(default_permissions) = self.default_permissions {
+            if default_permissions & !SpherePermission::ALL != 0 {
+                return Err(error!(
+                    VALIDATION,
+                    "default_permissions", "The sphere's default permissions contain unknown bits"
+                ));
+            }
+        }
         if let Some(Some(name)) = &self.name {
             if name.is_empty() || name.len() > 32 {
                 return Err(error!(
@@ -50,9 +64,11 @@ impl Sphere {
     pub async fn edit(
         edit: SphereEdit,
         sphere_id: u64,
+        actor: AuditLogActor<'_>,
         db: &mut PoolConnection<Postgres>,
     ) -> Result<Self, ErrorResponse> {
         edit.validate()?;
+        actor.validate()?;
 
         let sphere = Self::get_unpopulated(sphere_id, db).await.map_err(|err| {
             if let ErrorResponse::NotFound { .. } = err {
@@ -205,6 +221,104 @@ WHERE id = $2
             })?;
         }
 
+        if let Some(default_permissions) = edit.default_permissions {
+            sqlx::query!(
+                "
+UPDATE spheres
+SET default_permissions = $1
+WHERE id = $2
+                ",
+                default_permissions as i64,
+                sphere_id as i64
+            )
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| {
+                log::error!(
+                    "Couldn't update {} sphere's default permissions to {}: {}",
+                    sphere_id,
+                    default_permissions,
+                    err
+                );
+                error!(SERVER, "Failed to edit sphere")
+            })?;
+        }
+
+        if let Some(discoverable) = edit.discoverable {
+            sqlx::query!(
+                "
+UPDATE spheres
+SET discoverable = $1
+WHERE id = $2
+                ",
+                discoverable,
+                sphere_id as i64
+            )
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| {
+                log::error!(
+                    "Couldn't update {} sphere's discoverability to {}: {}",
+                    sphere_id,
+                    discoverable,
+                    err
+                );
+                error!(SERVER, "Failed to edit sphere")
+            })?;
+        }
+
+        if let Some(invite_only) = edit.invite_only {
+            sqlx::query!(
+                "
+UPDATE spheres
+SET invite_only = $1
+WHERE id = $2
+                ",
+                invite_only,
+                sphere_id as i64
+            )
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| {
+                log::error!(
+                    "Couldn't update {} sphere's invite-only flag to {}: {}",
+                    sphere_id,
+                    invite_only,
+                    err
+                );
+                error!(SERVER, "Failed to edit sphere")
+            })?;
+        }
+
+        AuditLogEntry::create(
+            sphere_id,
+            sphere_id,
+            AuditLogChanges::SphereUpdate {
+                before: SphereEdit {
+                    name: edit.name.as_ref().map(|_| sphere.name.clone()),
+                    sphere_type: edit
+                        .sphere_type
+                        .as_ref()
+                        .map(|_| sphere.sphere_type.clone()),
+                    description: edit
+                        .description
+                        .as_ref()
+                        .map(|_| sphere.description.clone()),
+                    icon: edit.icon.map(|_| sphere.icon),
+                    banner: edit.banner.map(|_| sphere.banner),
+                    default_permissions: edit
+                        .default_permissions
+                        .map(|_| sphere.default_permissions),
+                    discoverable: edit.discoverable.map(|_| sphere.discoverable),
+                    invite_only: edit.invite_only.map(|_| sphere.invite_only),
+                },
+                after: edit.clone(),
+            },
+            actor,
+            &mut transaction,
+        )
+        .await?;
+
         transaction.commit().await.map_err(|err| {
             log::error!("Couldn't commit sphere edit transaction: {}", err);
             error!(SERVER, "Failed to edit sphere")
@@ -220,9 +334,15 @@ WHERE id = $2
             icon: edit.icon.unwrap_or(sphere.icon),
             banner: edit.banner.unwrap_or(sphere.banner),
             badges: sphere.badges,
+            default_permissions: edit
+                .default_permissions
+                .unwrap_or(sphere.default_permissions),
+            discoverable: edit.discoverable.unwrap_or(sphere.discoverable),
+            invite_only: edit.invite_only.unwrap_or(sphere.invite_only),
             categories: vec![],
             members: vec![],
             emojis: vec![],
+            roles: vec![],
         })
     }
 }
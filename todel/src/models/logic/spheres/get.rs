@@ -4,9 +4,11 @@ use redis::AsyncCommands;
 use sqlx::{pool::PoolConnection, FromRow, Postgres, Row};
 
 use crate::models::{
-    Category, Emoji, ErrorResponse, Member, Sphere, SphereChannel, Status, StatusType, User,
+    Category, Emoji, ErrorResponse, Member, Role, Sphere, SphereChannel, Status, StatusType, User,
 };
 
+use super::members::get_active_timeout;
+
 impl Sphere {
     pub async fn populate_channels(
         &mut self,
@@ -101,6 +103,33 @@ WHERE sphere_id = $1
             log::error!("Couldn't fetch members for {} sphere: {}", self.slug, err);
             error!(SERVER, "Failed to get sphere")
         })?;
+        let mut member_roles: HashMap<u64, Vec<u64>> = HashMap::new();
+        for row in sqlx::query!(
+            "
+            SELECT member_roles.member_id, member_roles.role_id
+            FROM member_roles
+            JOIN roles ON roles.id = member_roles.role_id
+            WHERE member_roles.sphere_id = $1
+              AND roles.is_deleted = FALSE
+            ORDER BY roles.position
+            ",
+            self.id as i64
+        )
+        .fetch_all(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Couldn't fetch member roles for {} sphere: {}",
+                self.slug,
+                err
+            );
+            error!(SERVER, "Failed to get sphere")
+        })? {
+            member_roles
+                .entry(row.member_id as u64)
+                .or_default()
+                .push(row.role_id as u64);
+        }
         let mut members = vec![];
         for row in rows {
             let mut user = User::from_row(&row).map_err(|err| {
@@ -123,6 +152,7 @@ WHERE sphere_id = $1
                 }
             }
             members.push(Member {
+                roles: member_roles.remove(&user.id).unwrap_or_default(),
                 user,
                 sphere_id: self.id,
                 nickname: row.get("nickname"),
@@ -130,6 +160,7 @@ WHERE sphere_id = $1
                 sphere_banner: row.get::<Option<i64>, _>("sphere_banner").map(|a| a as u64),
                 sphere_bio: row.get("sphere_bio"),
                 sphere_status: row.get("sphere_status"),
+                timed_out_until: get_active_timeout(row.get("timed_out_until")),
             })
         }
         self.members = members;
@@ -168,6 +199,14 @@ WHERE sphere_id = $1
         Ok(())
     }
 
+    pub async fn populate_roles(
+        &mut self,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<(), ErrorResponse> {
+        self.roles = Role::get_all(self.id, db).await?;
+        Ok(())
+    }
+
     pub async fn get<C: AsyncCommands>(
         id: u64,
         db: &mut PoolConnection<Postgres>,
@@ -191,6 +230,7 @@ WHERE id = $1
         .ok_or_else(|| error!(NOT_FOUND))?;
         sphere.populate_channels(db).await?;
         sphere.populate_members(db, cache).await?;
+        sphere.populate_roles(db).await?;
         Ok(sphere)
     }
 
@@ -218,6 +258,7 @@ WHERE slug = $1
         sphere.populate_channels(db).await?;
         sphere.populate_members(db, cache).await?;
         sphere.populate_emojis(db).await?;
+        sphere.populate_roles(db).await?;
         Ok(sphere)
     }
 
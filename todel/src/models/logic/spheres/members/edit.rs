@@ -1,7 +1,9 @@
 use redis::AsyncCommands;
 use sqlx::{pool::PoolConnection, query, Acquire, Postgres};
 
-use crate::models::{ErrorResponse, File, Member, MemberEdit};
+use crate::models::{
+    AuditLogActor, AuditLogChanges, AuditLogEntry, ErrorResponse, File, Member, MemberEdit, Role,
+};
 
 impl MemberEdit {
     pub fn validate(&self) -> Result<(), ErrorResponse> {
@@ -10,6 +12,7 @@ impl MemberEdit {
             && self.sphere_banner.is_none()
             && self.sphere_bio.is_none()
             && self.sphere_status.is_none()
+            && self.roles.is_none()
         {
             return Err(error!(VALIDATION, "body", "At least one field must exist"));
         }
@@ -42,17 +45,21 @@ impl MemberEdit {
 }
 
 impl Member {
+    /// Edit a member, recording changes to other members' nicknames and roles in the sphere's
+    /// audit log.
     pub async fn edit<C: AsyncCommands>(
         id: u64,
         sphere_id: u64,
         edit: MemberEdit,
+        actor: AuditLogActor<'_>,
         db: &mut PoolConnection<Postgres>,
-        requester_id: Option<u64>,
         cache: &mut C,
     ) -> Result<Self, ErrorResponse> {
         edit.validate()?;
+        actor.validate()?;
+        let requester_id = actor.user_id;
 
-        if Some(id) != requester_id
+        if id != requester_id
             && (edit.sphere_avatar.flatten().is_some()
                 && edit.sphere_banner.flatten().is_some()
                 && edit.sphere_bio.clone().flatten().is_some()
@@ -64,7 +71,7 @@ impl Member {
             ));
         }
 
-        query!(
+        let current_nickname = query!(
             "
             SELECT nickname
             FROM members
@@ -80,7 +87,8 @@ impl Member {
             log::error!("Couldn't fetch member {}'s sphere_id: {}", id, err);
             error!(SERVER, "Failed to fetch member")
         })?
-        .ok_or_else(|| error!(NOT_FOUND))?;
+        .ok_or_else(|| error!(NOT_FOUND))?
+        .nickname;
 
         if let Some(Some(avatar)) = edit.sphere_avatar {
             if File::get(avatar, "member-avatars", &mut *db)
@@ -108,11 +116,52 @@ impl Member {
             }
         }
 
+        if let Some(ref roles) = edit.roles {
+            for role in roles {
+                Role::get(*role, sphere_id, db).await.map_err(|err| {
+                    if let ErrorResponse::NotFound { .. } = err {
+                        error!(
+                            VALIDATION,
+                            "roles",
+                            format!("Role {} doesn't exist in this sphere", role)
+                        )
+                    } else {
+                        err
+                    }
+                })?;
+            }
+        }
+
         let mut transaction = db.begin().await.map_err(|err| {
             log::error!("Couldn't start sphere edit transaction: {}", err);
             error!(SERVER, "Failed to edit member")
         })?;
 
+        let current_roles = match edit.roles {
+            Some(_) if id != requester_id => Some(
+                sqlx::query!(
+                    "
+         SELECT role_id
+         FROM member_roles
+         WHERE member_id = $1
+         AND sphere_id = $2
+                         ",
+                    id as i64,
+                    sphere_id as i64,
+                )
+                .fetch_all(&mut *transaction)
+                .await
+                .map_err(|err| {
+                    log::error!("Couldn't fetch {} member's roles: {}", id, err);
+                    error!(SERVER, "Failed to edit member")
+                })?
+                .into_iter()
+                .map(|r| r.role_id as u64)
+                .collect(),
+            ),
+            _ => None,
+        };
+
         if let Some(ref nickname) = edit.nickname {
             sqlx::query!(
                 "
@@ -233,11 +282,75 @@ impl Member {
             })?;
         }
 
+        if let Some(ref roles) = edit.roles {
+            sqlx::query!(
+                "
+         DELETE FROM member_roles
+         WHERE member_id = $1
+         AND sphere_id = $2
+                         ",
+                id as i64,
+                sphere_id as i64,
+            )
+            .execute(&mut *transaction)
+            .await
+            .map_err(|err| {
+                log::error!("Couldn't clear {} member's roles: {}", id, err);
+                error!(SERVER, "Failed to edit member")
+            })?;
+            for role in roles {
+                sqlx::query!(
+                    "
+         INSERT INTO member_roles(member_id, sphere_id, role_id)
+         VALUES($1, $2, $3)
+         ON CONFLICT DO NOTHING
+                         ",
+                    id as i64,
+                    sphere_id as i64,
+                    *role as i64,
+                )
+                .execute(&mut *transaction)
+                .await
+                .map_err(|err| {
+                    log::error!("Couldn't give {} member role {}: {}", id, role, err);
+                    error!(SERVER, "Failed to edit member")
+                })?;
+            }
+        }
+
+        if id != requester_id && (edit.nickname.is_some() || edit.roles.is_some()) {
+            AuditLogEntry::create(
+                sphere_id,
+                id,
+                AuditLogChanges::MemberUpdate {
+                    before: MemberEdit {
+                        nickname: edit.nickname.as_ref().map(|_| current_nickname),
+                        sphere_avatar: None,
+                        sphere_banner: None,
+                        sphere_bio: None,
+                        sphere_status: None,
+                        roles: current_roles,
+                    },
+                    after: MemberEdit {
+                        nickname: edit.nickname.clone(),
+                        sphere_avatar: None,
+                        sphere_banner: None,
+                        sphere_bio: None,
+                        sphere_status: None,
+                        roles: edit.roles.clone(),
+                    },
+                },
+                actor,
+                &mut transaction,
+            )
+            .await?;
+        }
+
         transaction.commit().await.map_err(|err| {
             log::error!("Couldn't commit member edit transaction: {}", err);
             error!(SERVER, "Failed to edit member")
         })?;
 
-        Self::get(id, sphere_id, requester_id, db, cache).await
+        Self::get(id, sphere_id, Some(requester_id), db, cache).await
     }
 }
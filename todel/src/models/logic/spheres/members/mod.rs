@@ -1,4 +1,7 @@
 mod edit;
+mod timeout;
+
+pub(crate) use timeout::get_active_timeout;
 
 use redis::AsyncCommands;
 use sqlx::{pool::PoolConnection, Postgres, Row};
@@ -14,6 +17,7 @@ impl Member {
         cache: &mut C,
     ) -> Result<Self, ErrorResponse> {
         let user = User::get(id, requester_id, db, cache).await?;
+        let roles = Self::get_roles(id, sphere_id, db).await?;
         sqlx::query(
             "
             SELECT * 
@@ -38,6 +42,8 @@ impl Member {
             sphere_banner: r.get::<Option<i64>, _>("sphere_banner").map(|i| i as u64),
             sphere_bio: r.get("sphere_bio"),
             sphere_status: r.get("sphere_status"),
+            roles,
+            timed_out_until: get_active_timeout(r.get("timed_out_until")),
         })
         .ok_or_else(|| error!(NOT_FOUND))
     }
@@ -50,6 +56,7 @@ impl Member {
         cache: &mut C,
     ) -> Result<Self, ErrorResponse> {
         let user = User::get_username(username, requester_id, db, cache).await?;
+        let roles = Self::get_roles(user.id, sphere_id, db).await?;
         sqlx::query(
             "
             SELECT * 
@@ -74,7 +81,38 @@ impl Member {
             sphere_banner: r.get::<Option<i64>, _>("sphere_banner").map(|i| i as u64),
             sphere_bio: r.get("sphere_bio"),
             sphere_status: r.get("sphere_status"),
+            roles,
+            timed_out_until: get_active_timeout(r.get("timed_out_until")),
         })
         .ok_or_else(|| error!(NOT_FOUND))
     }
+
+    pub async fn get_roles(
+        id: u64,
+        sphere_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<Vec<u64>, ErrorResponse> {
+        Ok(sqlx::query!(
+            "
+            SELECT member_roles.role_id
+            FROM member_roles
+            JOIN roles ON roles.id = member_roles.role_id
+            WHERE member_roles.member_id = $1
+              AND member_roles.sphere_id = $2
+              AND roles.is_deleted = FALSE
+            ORDER BY roles.position
+            ",
+            id as i64,
+            sphere_id as i64
+        )
+        .fetch_all(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch member {}'s roles: {}", id, err);
+            error!(SERVER, "Failed to fetch member")
+        })?
+        .into_iter()
+        .map(|r| r.role_id as u64)
+        .collect())
+    }
 }
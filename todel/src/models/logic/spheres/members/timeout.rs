@@ -0,0 +1,137 @@
+use sqlx::{pool::PoolConnection, Acquire, Postgres};
+
+use crate::models::{
+    logic::get_unix_timestamp, AuditLogActor, AuditLogChanges, AuditLogEntry, ErrorResponse,
+    Member, MemberTimeout,
+};
+
+/// The longest a member can be timed out for, 28 days.
+const MAX_TIMEOUT_DURATION: u64 = 2_419_200;
+
+/// Filter out a stored timeout if it has already expired.
+pub(crate) fn get_active_timeout(timed_out_until: Option<i64>) -> Option<u64> {
+    timed_out_until
+        .map(|t| t as u64)
+        .filter(|t| *t > get_unix_timestamp())
+}
+
+impl MemberTimeout {
+    pub fn validate(&self) -> Result<(), ErrorResponse> {
+        if self.duration > MAX_TIMEOUT_DURATION {
+            return Err(error!(
+                VALIDATION,
+                "duration", "Members can't be timed out for more than 28 days"
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Member {
+    /// Time a member out, returning the time until which they are timed out.
+    pub async fn timeout(
+        id: u64,
+        sphere_id: u64,
+        timeout: MemberTimeout,
+        actor: AuditLogActor<'_>,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<Option<u64>, ErrorResponse> {
+        timeout.validate()?;
+        actor.validate()?;
+        let timed_out_until = match timeout.duration {
+            0 => None,
+            duration => Some(get_unix_timestamp() + duration),
+        };
+
+        let mut transaction = db.begin().await.map_err(|err| {
+            log::error!("Couldn't start member timeout transaction: {}", err);
+            error!(SERVER, "Failed to time out member")
+        })?;
+
+        let previous_timeout = sqlx::query!(
+            "
+            SELECT timed_out_until
+            FROM members
+            WHERE id = $1
+            AND sphere_id = $2
+            FOR UPDATE
+            ",
+            id as i64,
+            sphere_id as i64,
+        )
+        .fetch_optional(&mut *transaction)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch member {}'s timeout: {}", id, err);
+            error!(SERVER, "Failed to time out member")
+        })?
+        .ok_or_else(|| error!(NOT_FOUND))?
+        .timed_out_until;
+
+        sqlx::query!(
+            "
+            UPDATE members
+            SET timed_out_until = $1
+            WHERE id = $2
+            AND sphere_id = $3
+            ",
+            timed_out_until.map(|t| t as i64),
+            id as i64,
+            sphere_id as i64,
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't update member {}'s timeout: {}", id, err);
+            error!(SERVER, "Failed to time out member")
+        })?;
+
+        AuditLogEntry::create(
+            sphere_id,
+            id,
+            AuditLogChanges::MemberTimeout {
+                before: get_active_timeout(previous_timeout),
+                after: timed_out_until,
+            },
+            actor,
+            &mut transaction,
+        )
+        .await?;
+
+        transaction.commit().await.map_err(|err| {
+            log::error!("Couldn't commit member timeout transaction: {}", err);
+            error!(SERVER, "Failed to time out member")
+        })?;
+
+        Ok(timed_out_until)
+    }
+
+    /// Make sure a member isn't currently timed out, returning a forbidden error otherwise.
+    pub async fn require_not_timed_out(
+        id: u64,
+        sphere_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<(), ErrorResponse> {
+        let timed_out_until = sqlx::query!(
+            "
+            SELECT timed_out_until
+            FROM members
+            WHERE id = $1
+            AND sphere_id = $2
+            ",
+            id as i64,
+            sphere_id as i64,
+        )
+        .fetch_optional(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch member {}'s timeout: {}", id, err);
+            error!(SERVER, "Failed to fetch member")
+        })?
+        .and_then(|r| r.timed_out_until);
+        if get_active_timeout(timed_out_until).is_some() {
+            return Err(error!(FORBIDDEN));
+        }
+        Ok(())
+    }
+}
@@ -1,7 +1,9 @@
 mod add_member;
+mod bans;
 mod edit;
 mod get;
 mod members;
+mod permissions;
 mod remove_member;
 
 use regex::Regex;
@@ -14,8 +16,8 @@ use sqlx::{
 use crate::{
     ids::IdGenerator,
     models::{
-        Category, ChannelType, ErrorResponse, File, Sphere, SphereChannel, SphereCreate,
-        TextChannel,
+        Category, ChannelType, ErrorResponse, File, ForumChannel, Sphere, SphereChannel,
+        SphereCreate, SpherePermission, SphereType, TextChannel,
     },
 };
 
@@ -31,9 +33,13 @@ impl FromRow<'_, PgRow> for Sphere {
             icon: row.get::<Option<i64>, _>("icon").map(|a| a as u64),
             banner: row.get::<Option<i64>, _>("banner").map(|a| a as u64),
             badges: row.get::<i64, _>("badges") as u64,
+            default_permissions: row.get::<i64, _>("default_permissions") as u64,
+            discoverable: row.get("discoverable"),
+            invite_only: row.get("invite_only"),
             categories: vec![],
             members: vec![],
             emojis: vec![],
+            roles: vec![],
         })
     }
 }
@@ -162,8 +168,8 @@ WHERE owner_id = $1
         })?;
         sqlx::query(
             "
-INSERT INTO spheres(id, owner_id, sphere_type, slug, name, description, icon, banner)
-VALUES($1, $2, $3, $4, $5, $6, $7, $8)
+INSERT INTO spheres(id, owner_id, sphere_type, slug, name, description, icon, banner, discoverable, invite_only)
+VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
             ",
         )
         .bind(sphere_id as i64)
@@ -174,6 +180,8 @@ VALUES($1, $2, $3, $4, $5, $6, $7, $8)
         .bind(&sphere.description)
         .bind(sphere.icon.map(|i| i as i64))
         .bind(sphere.banner.map(|b| b as i64))
+        .bind(sphere.discoverable.unwrap_or(true))
+        .bind(sphere.invite_only.unwrap_or(false))
         .execute(&mut **db)
         .await
         .map_err(|err| {
@@ -194,6 +202,10 @@ VALUES($1, $1, 'uncategorised', 0)
             error!(SERVER, "Failed to create sphere")
         })?;
         let channel_id = id_generator.generate();
+        let channel_type = match sphere.sphere_type {
+            SphereType::Forum => ChannelType::Forum,
+            SphereType::Chat | SphereType::Hybrid => ChannelType::Text,
+        };
         sqlx::query(
             "
 INSERT INTO channels(id, sphere_id, category_id, channel_type, name, position)
@@ -202,7 +214,7 @@ VALUES($1, $2, $2, $3, $4, 0)
         )
         .bind(channel_id as i64)
         .bind(sphere_id as i64)
-        .bind(ChannelType::Text)
+        .bind(&channel_type)
         .bind("general")
         .execute(&mut **db)
         .await
@@ -224,24 +236,38 @@ VALUES($1, $2, $2, $3, $4, 0)
             icon: sphere.icon,
             banner: sphere.banner,
             badges: 0,
+            default_permissions: SpherePermission::DEFAULT,
+            discoverable: sphere.discoverable.unwrap_or(true),
+            invite_only: sphere.invite_only.unwrap_or(false),
             sphere_type: sphere.sphere_type,
             categories: vec![Category {
                 id: sphere_id, // Special case: category with sphere id is to be treated as uncategorised.
                 name: "uncategorised".to_string(),
                 position: 0,
-                channels: vec![SphereChannel::Text(TextChannel {
-                    id: channel_id,
-                    sphere_id,
-                    name: "general".to_string(),
-                    topic: None,
-                    position: 0,
-                    category_id: sphere_id,
-                })],
+                channels: vec![match channel_type {
+                    ChannelType::Forum => SphereChannel::Forum(ForumChannel {
+                        id: channel_id,
+                        sphere_id,
+                        name: "general".to_string(),
+                        topic: None,
+                        position: 0,
+                        category_id: sphere_id,
+                    }),
+                    _ => SphereChannel::Text(TextChannel {
+                        id: channel_id,
+                        sphere_id,
+                        name: "general".to_string(),
+                        topic: None,
+                        position: 0,
+                        category_id: sphere_id,
+                    }),
+                }],
             }],
             members: vec![],
             emojis: vec![],
+            roles: vec![],
         };
-        let member = sphere.add_member(owner_id, db).await?;
+        let member = sphere.add_member(owner_id, None, db).await?;
         sphere.members.push(member);
         Ok(sphere)
     }
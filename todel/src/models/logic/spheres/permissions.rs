@@ -0,0 +1,157 @@
+use sqlx::{pool::PoolConnection, Postgres};
+
+use crate::models::{ErrorResponse, Sphere, SpherePermission};
+
+impl Sphere {
+    /// Resolve the sphere-wide permissions of a user.
+    ///
+    /// The sphere's owner and members with the `ADMINISTRATOR` permission are granted every
+    /// permission, other members get the sphere's `default_permissions` combined with the
+    /// permissions of all of their roles.
+    pub async fn get_member_permissions(
+        &self,
+        user_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<u64, ErrorResponse> {
+        if self.owner_id == user_id {
+            return Ok(SpherePermission::ALL);
+        }
+        if !self.has_member(user_id, db).await? {
+            return Err(error!(FORBIDDEN));
+        }
+        let role_permissions = sqlx::query!(
+            "
+SELECT BIT_OR(roles.permissions) AS permissions
+FROM member_roles
+JOIN roles ON roles.id = member_roles.role_id
+WHERE member_roles.member_id = $1
+    AND member_roles.sphere_id = $2
+    AND roles.is_deleted = FALSE
+            ",
+            user_id as i64,
+            self.id as i64,
+        )
+        .fetch_one(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Couldn't resolve member {}'s permissions in sphere {}: {}",
+                user_id,
+                self.id,
+                err
+            );
+            error!(SERVER, "Failed to resolve member permissions")
+        })?
+        .permissions
+        .unwrap_or(0) as u64;
+
+        let permissions = self.default_permissions | role_permissions;
+        if SpherePermission::Administrator.is_set(permissions) {
+            Ok(SpherePermission::ALL)
+        } else {
+            Ok(permissions)
+        }
+    }
+
+    /// Make sure a user has a permission in this sphere, returning a forbidden error otherwise.
+    pub async fn require_permission(
+        &self,
+        user_id: u64,
+        permission: SpherePermission,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<(), ErrorResponse> {
+        if permission.is_set(self.get_member_permissions(user_id, db).await?) {
+            Ok(())
+        } else {
+            Err(error!(FORBIDDEN))
+        }
+    }
+
+    /// Make sure a user has every permission of a bitfield, used to stop members from granting
+    /// permissions they don't have themselves.
+    pub async fn require_permissions(
+        &self,
+        user_id: u64,
+        permissions: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<(), ErrorResponse> {
+        if permissions & !self.get_member_permissions(user_id, db).await? == 0 {
+            Ok(())
+        } else {
+            Err(error!(FORBIDDEN))
+        }
+    }
+
+    /// Get the position of a member's highest role, `None` if they have no roles.
+    pub async fn get_member_top_role_position(
+        &self,
+        user_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<Option<u32>, ErrorResponse> {
+        Ok(sqlx::query!(
+            "
+SELECT MIN(roles.position) AS position
+FROM member_roles
+JOIN roles ON roles.id = member_roles.role_id
+WHERE member_roles.member_id = $1
+    AND member_roles.sphere_id = $2
+    AND roles.is_deleted = FALSE
+            ",
+            user_id as i64,
+            self.id as i64,
+        )
+        .fetch_one(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Couldn't fetch member {}'s top role in sphere {}: {}",
+                user_id,
+                self.id,
+                err
+            );
+            error!(SERVER, "Failed to resolve member permissions")
+        })?
+        .position
+        .map(|p| p as u32))
+    }
+
+    /// Make sure a user is placed above a role position in the sphere's role hierarchy.
+    ///
+    /// The sphere's owner is above every role.
+    pub async fn require_above_role(
+        &self,
+        user_id: u64,
+        position: u32,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<(), ErrorResponse> {
+        if self.owner_id == user_id {
+            return Ok(());
+        }
+        match self.get_member_top_role_position(user_id, db).await? {
+            Some(top) if top < position => Ok(()),
+            _ => Err(error!(FORBIDDEN)),
+        }
+    }
+
+    /// Make sure a user is placed above another member in the sphere's role hierarchy.
+    ///
+    /// Nobody is above the sphere's owner and members without roles are below everyone who
+    /// has at least one.
+    pub async fn require_above_member(
+        &self,
+        user_id: u64,
+        member_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<(), ErrorResponse> {
+        if self.owner_id == user_id {
+            return Ok(());
+        }
+        if self.owner_id == member_id {
+            return Err(error!(FORBIDDEN));
+        }
+        match self.get_member_top_role_position(member_id, db).await? {
+            Some(position) => self.require_above_role(user_id, position, db).await,
+            None => Ok(()),
+        }
+    }
+}
@@ -1,6 +1,6 @@
-use sqlx::{pool::PoolConnection, Postgres};
+use sqlx::{pool::PoolConnection, PgConnection, Postgres};
 
-use crate::models::{ErrorResponse, Sphere};
+use crate::models::{AuditLogActor, AuditLogChanges, AuditLogEntry, ErrorResponse, Sphere};
 
 impl Sphere {
     pub async fn remove_member(
@@ -11,6 +11,17 @@ impl Sphere {
         if !self.has_member(user_id, db).await? {
             return Err(error!(VALIDATION, "sphere", "User isn't in this sphere"));
         }
+        self.delete_member(user_id, db).await
+    }
+
+    /// Delete a user's membership, roles and overwrites in this sphere.
+    ///
+    /// This takes a plain connection so that it can be done inside of a transaction.
+    pub(crate) async fn delete_member(
+        &self,
+        user_id: u64,
+        db: &mut PgConnection,
+    ) -> Result<(), ErrorResponse> {
         sqlx::query!(
             "
             DELETE FROM members
@@ -20,12 +31,68 @@ impl Sphere {
             user_id as i64,
             self.id as i64
         )
-        .execute(&mut **db)
+        .execute(&mut *db)
         .await
         .map_err(|err| {
             log::error!("Couldn't remove member from sphere {}: {}", self.id, err);
             error!(SERVER, "Failed to remove member from sphere")
         })?;
+        sqlx::query!(
+            "
+            DELETE FROM member_roles
+            WHERE member_id = $1
+            AND sphere_id = $2
+            ",
+            user_id as i64,
+            self.id as i64
+        )
+        .execute(&mut *db)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Couldn't remove member's roles in sphere {}: {}",
+                self.id,
+                err
+            );
+            error!(SERVER, "Failed to remove member from sphere")
+        })?;
+        sqlx::query!(
+            "
+            DELETE FROM channel_overwrites
+            USING channels
+            WHERE channel_overwrites.channel_id = channels.id
+            AND channels.sphere_id = $2
+            AND channel_overwrites.target_id = $1
+            AND channel_overwrites.overwrite_type = 'MEMBER'
+            ",
+            user_id as i64,
+            self.id as i64
+        )
+        .execute(&mut *db)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Couldn't remove member's overwrites in sphere {}: {}",
+                self.id,
+                err
+            );
+            error!(SERVER, "Failed to remove member from sphere")
+        })?;
+        Ok(())
+    }
+
+    /// Kick a member from this sphere, recording it in the sphere's audit log.
+    ///
+    /// Unlike bans, kicks don't stop the user from joining the sphere again.
+    pub async fn kick_member(
+        &self,
+        user_id: u64,
+        actor: AuditLogActor<'_>,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<(), ErrorResponse> {
+        actor.validate()?;
+        self.remove_member(user_id, db).await?;
+        AuditLogEntry::create(self.id, user_id, AuditLogChanges::MemberKick, actor, db).await?;
         Ok(())
     }
 }
@@ -365,10 +365,10 @@ WHERE id = $1
             error!(SERVER, "Couldn't check user verification status")
         })?;
 
-        if let Some(email) = &conf.email {
+        if let (Some(email), Some(address)) = (&conf.email, &user.email) {
             mailer
                 .send_email(
-                    &format!("{} <{}>", user.username, user.email),
+                    &format!("{} <{}>", user.username, address),
                     EmailPreset::Verify {
                         username: &user.username,
                         code: cache_code,
@@ -391,6 +391,7 @@ WHERE id = $1
     ) -> Result<Self, ErrorResponse> {
         edit.validate(&mut *db).await?;
         Self::validate_password(id, &edit.password, hasher, db).await?;
+        Self::validate_two_factor(id, edit.two_factor_code.as_deref(), hasher, db).await?;
         let mut query: QueryBuilder<Postgres> = QueryBuilder::new("UPDATE users SET ");
         let mut seperated = query.separated(", ");
         let username = Self::get_unfiltered(id, &mut *db).await?.username;
@@ -554,10 +555,10 @@ RETURNING username, email
                 );
                 error!(SERVER, "Couldn't reset the user's  password")
             })?;
-        if let Some(email) = &conf.email {
+        if let (Some(email), Some(address)) = (&conf.email, &user.email) {
             mailer
                 .send_email(
-                    &format!("{} <{}>", user.username, user.email),
+                    &format!("{} <{}>", user.username, address),
                     EmailPreset::UserUpdated {
                         username: &user.username,
                         old_username: None,
@@ -580,6 +581,7 @@ RETURNING username, email
         db: &mut PoolConnection<Postgres>,
     ) -> Result<(), ErrorResponse> {
         Self::validate_password(id, &delete.password, verifier, db).await?;
+        Self::validate_two_factor(id, delete.two_factor_code.as_deref(), verifier, db).await?;
         let user = sqlx::query!(
             "
 UPDATE users
@@ -595,10 +597,28 @@ RETURNING username, email
             log::error!("Couldn't mark user as deleted: {}", err);
             error!(SERVER, "Failed to delete user")
         })?;
-        if let Some(email) = &conf.email {
+        sqlx::query!(
+            "
+UPDATE users
+SET is_deleted = TRUE
+WHERE id IN (
+    SELECT id
+    FROM applications
+    WHERE owner_id = $1
+)
+            ",
+            id as i64
+        )
+        .execute(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't mark the bots of user {} as deleted: {}", id, err);
+            error!(SERVER, "Failed to delete user")
+        })?;
+        if let (Some(email), Some(address)) = (&conf.email, &user.email) {
             mailer
                 .send_email(
-                    &format!("{} <{}>", user.username, user.email),
+                    &format!("{} <{}>", user.username, address),
                     EmailPreset::Delete {
                         username: &user.username,
                     },
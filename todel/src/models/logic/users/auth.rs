@@ -25,7 +25,8 @@ AND is_deleted = FALSE
             log::error!("Could not fetch the user's password: {}", err);
             error!(SERVER, "Failed to fetch the user's password")
         })?
-        .password;
+        .password
+        .ok_or_else(|| error!(VALIDATION, "password", "Bots don't have a password"))?;
         verifier
             .verify_password(
                 password.as_bytes(),
@@ -2,8 +2,9 @@ mod account;
 mod auth;
 mod profile;
 mod social;
+mod two_factor;
 
-use crate::models::{Status, User};
+use crate::models::{MessageWebhook, Status, StatusType, User};
 use sqlx::{postgres::PgRow, Database, Decode, FromRow, Row};
 
 pub use account::*;
@@ -36,8 +37,52 @@ impl FromRow<'_, PgRow> for User {
             banner: row.get::<Option<i64>, _>("banner").map(|b| b as u64),
             badges: row.get::<i64, _>("badges") as u64,
             permissions: row.get::<i64, _>("permissions") as u64,
-            email: Some(row.get("email")),
+            email: row.get("email"),
             verified: Some(row.get("verified")),
         })
     }
 }
+
+impl User {
+    /// The placeholder user which stands in for the authors of content whose account was deleted.
+    pub(crate) fn deleted() -> Self {
+        Self {
+            id: 0,
+            username: "deleted-user".to_string(),
+            display_name: Some("Deleted User".to_string()),
+            social_credit: 0,
+            status: Status {
+                status_type: StatusType::Offline,
+                text: None,
+            },
+            bio: None,
+            avatar: None,
+            banner: None,
+            badges: 0,
+            permissions: 0,
+            email: None,
+            verified: None,
+        }
+    }
+
+    /// The user shown as the author of a message sent through a webhook.
+    pub(crate) fn webhook(webhook: &MessageWebhook) -> Self {
+        Self {
+            id: webhook.id,
+            username: webhook.name.clone(),
+            display_name: None,
+            social_credit: 0,
+            status: Status {
+                status_type: StatusType::Offline,
+                text: None,
+            },
+            bio: None,
+            avatar: webhook.avatar,
+            banner: None,
+            badges: 0,
+            permissions: 0,
+            email: None,
+            verified: None,
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use sqlx::{pool::PoolConnection, Postgres, QueryBuilder};
 
-use crate::models::{ErrorResponse, File, User, UserProfileEdit};
+use crate::models::{ErrorResponse, File, Status, User, UserProfileEdit};
 
 impl UserProfileEdit {
     pub async fn validate(&self, db: &mut PoolConnection<Postgres>) -> Result<(), ErrorResponse> {
@@ -61,7 +61,47 @@ impl UserProfileEdit {
     }
 }
 
+impl Status {
+    pub fn validate(&self) -> Result<(), ErrorResponse> {
+        if let Some(text) = &self.text {
+            if text.is_empty() || text.len() > 150 {
+                return Err(error!(
+                    VALIDATION,
+                    "text", "The user's status text must be between 1 and 150 characters in length"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
 impl User {
+    /// Update a user's status, used to update their presence from the gateway.
+    pub async fn update_status(
+        id: u64,
+        status: &Status,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<(), ErrorResponse> {
+        status.validate()?;
+        sqlx::query(
+            "
+UPDATE users
+SET status = $1, status_type = $2
+WHERE id = $3
+            ",
+        )
+        .bind(&status.text)
+        .bind(&status.status_type)
+        .bind(id as i64)
+        .execute(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't update user status: {}", err);
+            error!(SERVER, "Failed to update user status")
+        })?;
+        Ok(())
+    }
+
     pub async fn edit_profile(
         id: u64,
         profile: UserProfileEdit,
@@ -125,6 +125,7 @@ WHERE members.id = $1
             sphere.populate_channels(db).await?;
             sphere.populate_members(db, cache).await?;
             sphere.populate_emojis(db).await?;
+            sphere.populate_roles(db).await?;
             populated.push(sphere)
         }
         Ok(populated)
@@ -0,0 +1,413 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use argon2::{
+    password_hash::{rand_core::CryptoRngCore, SaltString},
+    PasswordHash, PasswordHasher, PasswordVerifier,
+};
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use rand::{distributions::Alphanumeric, Rng};
+use redis::AsyncCommands;
+use sha1::Sha1;
+use sqlx::{pool::PoolConnection, Acquire, Postgres};
+use url::Url;
+
+use crate::{
+    models::{
+        ErrorResponse, PasswordDeleteCredentials, TwoFactorConfirm, TwoFactorRecoveryCodes,
+        TwoFactorSetup, User,
+    },
+    Conf,
+};
+
+/// How many seconds each TOTP code is valid for.
+const TOTP_STEP: u64 = 30;
+/// How many steps before and after the current one are also accepted to account for clock drift.
+const TOTP_WINDOW: u64 = 1;
+/// How many recovery codes are generated when enabling two-factor authentication.
+const RECOVERY_CODE_COUNT: usize = 10;
+/// The length of a recovery code, including the dash between its two halves.
+const RECOVERY_CODE_LENGTH: usize = 9;
+
+/// Generate the 6 digit TOTP code of a secret for a specific step as described in RFC 6238.
+fn generate_totp(secret: &[u8], step: u64) -> u32 {
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret).expect("HMAC accepts keys of any size");
+    mac.update(&step.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+    let offset = (hash[hash.len() - 1] & 0xf) as usize;
+    let code = u32::from_be_bytes([
+        hash[offset] & 0x7f,
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ]);
+    code % 1_000_000
+}
+
+/// Check whether a code matches a base32 encoded TOTP secret, returning the step it belongs to.
+///
+/// Only steps after `last_step` are accepted so that a code can't be used twice.
+fn verify_totp(secret: &str, code: &str, last_step: Option<u64>) -> Option<u64> {
+    let code = code.trim();
+    if code.len() != 6 {
+        return None;
+    }
+    let (Ok(code), Ok(secret)) = (code.parse::<u32>(), BASE32_NOPAD.decode(secret.as_bytes()))
+    else {
+        return None;
+    };
+    let step = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Couldn't get current timestamp")
+        .as_secs()
+        / TOTP_STEP;
+    (step.saturating_sub(TOTP_WINDOW)..=step + TOTP_WINDOW)
+        .filter(|step| last_step.is_none_or(|last_step| *step > last_step))
+        .find(|step| generate_totp(&secret, *step) == code)
+}
+
+/// Hash a recovery code so that it can be stored.
+fn hash_recovery_code<H: PasswordHasher, R: CryptoRngCore>(
+    code: &str,
+    hasher: &H,
+    rng: &mut R,
+) -> Result<String, ErrorResponse> {
+    let salt = SaltString::generate(rng);
+    Ok(hasher
+        .hash_password(code.as_bytes(), &salt)
+        .map_err(|err| {
+            log::error!("Failed to hash recovery code: {}", err);
+            error!(SERVER, "Could not hash recovery code")
+        })?
+        .to_string())
+}
+
+impl User {
+    /// Get a user's TOTP secret, not present if they don't have two-factor authentication
+    /// enabled.
+    async fn get_two_factor_secret(
+        id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<Option<String>, ErrorResponse> {
+        Ok(sqlx::query!(
+            "
+SELECT two_factor_auth
+FROM users
+WHERE id = $1
+AND is_deleted = FALSE
+            ",
+            id as i64
+        )
+        .fetch_one(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch the user's two-factor secret: {}", err);
+            error!(SERVER, "Failed to validate the user's two-factor code")
+        })?
+        .two_factor_auth)
+    }
+
+    /// Make sure a user provided a valid two-factor code if they have two-factor
+    /// authentication enabled.
+    ///
+    /// Each TOTP code can only be used once and recovery codes get consumed once used.
+    pub async fn validate_two_factor<V: PasswordVerifier>(
+        id: u64,
+        code: Option<&str>,
+        verifier: &V,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<(), ErrorResponse> {
+        let user = sqlx::query!(
+            "
+SELECT two_factor_auth, two_factor_last_step
+FROM users
+WHERE id = $1
+AND is_deleted = FALSE
+            ",
+            id as i64
+        )
+        .fetch_one(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch the user's two-factor secret: {}", err);
+            error!(SERVER, "Failed to validate the user's two-factor code")
+        })?;
+        let secret = match user.two_factor_auth {
+            Some(secret) => secret,
+            None => return Ok(()),
+        };
+        let code = code.ok_or_else(|| error!(TWO_FACTOR_REQUIRED))?;
+        let last_step = user.two_factor_last_step.map(|s| s as u64);
+        if let Some(step) = verify_totp(&secret, code, last_step) {
+            // only the request that manages to move the last step forward gets to use the code
+            let claimed = sqlx::query!(
+                "
+UPDATE users
+SET two_factor_last_step = $1
+WHERE id = $2
+AND (two_factor_last_step IS NULL OR two_factor_last_step < $1)
+                ",
+                step as i64,
+                id as i64,
+            )
+            .execute(&mut **db)
+            .await
+            .map_err(|err| {
+                log::error!("Couldn't store last TOTP step of user {}: {}", id, err);
+                error!(SERVER, "Failed to validate the user's two-factor code")
+            })?
+            .rows_affected();
+            if claimed == 0 {
+                return Err(error!(UNAUTHORIZED));
+            }
+            return Ok(());
+        }
+        let code = code.trim().to_lowercase();
+        if code.len() != RECOVERY_CODE_LENGTH {
+            return Err(error!(UNAUTHORIZED));
+        }
+        let hashes = sqlx::query!(
+            "
+SELECT code_hash
+FROM two_factor_recovery_codes
+WHERE user_id = $1
+            ",
+            id as i64,
+        )
+        .fetch_all(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch recovery codes of user {}: {}", id, err);
+            error!(SERVER, "Failed to validate the user's two-factor code")
+        })?;
+        let hash = hashes
+            .into_iter()
+            .map(|r| r.code_hash)
+            .find(|hash| {
+                PasswordHash::new(hash)
+                    .is_ok_and(|hash| verifier.verify_password(code.as_bytes(), &hash).is_ok())
+            })
+            .ok_or_else(|| error!(UNAUTHORIZED))?;
+        let deleted = sqlx::query!(
+            "
+DELETE FROM two_factor_recovery_codes
+WHERE user_id = $1
+AND code_hash = $2
+            ",
+            id as i64,
+            hash,
+        )
+        .execute(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't consume recovery code of user {}: {}", id, err);
+            error!(SERVER, "Failed to validate the user's two-factor code")
+        })?
+        .rows_affected();
+        if deleted == 0 {
+            return Err(error!(UNAUTHORIZED));
+        }
+        Ok(())
+    }
+
+    pub async fn setup_two_factor<V: PasswordVerifier, R: CryptoRngCore, C: AsyncCommands>(
+        id: u64,
+        credentials: PasswordDeleteCredentials,
+        verifier: &V,
+        rng: &mut R,
+        conf: &Conf,
+        db: &mut PoolConnection<Postgres>,
+        cache: &mut C,
+    ) -> Result<TwoFactorSetup, ErrorResponse> {
+        Self::validate_password(id, &credentials.password, verifier, db).await?;
+        if Self::get_two_factor_secret(id, db).await?.is_some() {
+            return Err(error!(CONFLICT, "two-factor authentication"));
+        }
+        let username = sqlx::query!(
+            "
+SELECT username
+FROM users
+WHERE id = $1
+            ",
+            id as i64
+        )
+        .fetch_one(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Failed to fetch user data: {}", err);
+            error!(SERVER, "Couldn't fetch user data")
+        })?
+        .username;
+        let mut bytes = [0; 10];
+        rng.fill_bytes(&mut bytes);
+        let secret = BASE32_NOPAD.encode(&bytes);
+        cache
+            .set_ex::<_, _, ()>(format!("two-factor-setup:{}", id), &secret, 600)
+            .await
+            .map_err(|err| {
+                log::error!("Failed to set two-factor secret in cache: {}", err);
+                error!(SERVER, "Couldn't set up two-factor authentication")
+            })?;
+        let mut uri = Url::parse("otpauth://totp/").expect("Couldn't parse otpauth URI");
+        uri.set_path(&format!("{}:{}", conf.instance_name, username));
+        uri.query_pairs_mut()
+            .append_pair("secret", &secret)
+            .append_pair("issuer", &conf.instance_name);
+        Ok(TwoFactorSetup {
+            secret,
+            uri: uri.to_string(),
+        })
+    }
+
+    pub async fn confirm_two_factor<H: PasswordHasher, R: CryptoRngCore, C: AsyncCommands>(
+        id: u64,
+        confirm: TwoFactorConfirm,
+        hasher: &H,
+        rng: &mut R,
+        db: &mut PoolConnection<Postgres>,
+        cache: &mut C,
+    ) -> Result<TwoFactorRecoveryCodes, ErrorResponse> {
+        let secret: String = cache
+            .get::<_, Option<String>>(format!("two-factor-setup:{}", id))
+            .await
+            .map_err(|err| {
+                log::error!("Failed to get two-factor secret from cache: {}", err);
+                error!(SERVER, "Couldn't enable two-factor authentication")
+            })?
+            .ok_or_else(|| {
+                error!(
+                    VALIDATION,
+                    "code", "Two-factor authentication wasn't set up or the setup expired"
+                )
+            })?;
+        let step = verify_totp(&secret, &confirm.code, None)
+            .ok_or_else(|| error!(VALIDATION, "code", "Invalid two-factor code"))?;
+        let codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+            .map(|_| {
+                let code: String = (&mut *rng)
+                    .sample_iter(&Alphanumeric)
+                    .take(8)
+                    .map(|c| char::from(c).to_ascii_lowercase())
+                    .collect();
+                format!("{}-{}", &code[..4], &code[4..])
+            })
+            .collect();
+        let hashes = codes
+            .iter()
+            .map(|c| hash_recovery_code(c, hasher, &mut *rng))
+            .collect::<Result<Vec<String>, ErrorResponse>>()?;
+        let mut transaction = db.begin().await.map_err(|err| {
+            log::error!("Couldn't start two-factor transaction: {}", err);
+            error!(SERVER, "Couldn't enable two-factor authentication")
+        })?;
+        let updated = sqlx::query!(
+            "
+UPDATE users
+SET two_factor_auth = $1, two_factor_last_step = $2
+WHERE id = $3
+AND two_factor_auth IS NULL
+            ",
+            secret,
+            step as i64,
+            id as i64,
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't enable two-factor for user {}: {}", id, err);
+            error!(SERVER, "Couldn't enable two-factor authentication")
+        })?
+        .rows_affected();
+        if updated == 0 {
+            return Err(error!(CONFLICT, "two-factor authentication"));
+        }
+        sqlx::query!(
+            "
+INSERT INTO two_factor_recovery_codes(user_id, code_hash)
+SELECT $1, * FROM UNNEST($2::TEXT[])
+            ",
+            id as i64,
+            &hashes,
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't store recovery codes of user {}: {}", id, err);
+            error!(SERVER, "Couldn't enable two-factor authentication")
+        })?;
+        transaction.commit().await.map_err(|err| {
+            log::error!("Couldn't commit two-factor transaction: {}", err);
+            error!(SERVER, "Couldn't enable two-factor authentication")
+        })?;
+        if let Err(err) = cache.del::<_, ()>(format!("two-factor-setup:{}", id)).await {
+            log::error!("Failed to remove two-factor secret from cache: {}", err);
+        }
+        Ok(TwoFactorRecoveryCodes { codes })
+    }
+
+    pub async fn disable_two_factor<V: PasswordVerifier>(
+        id: u64,
+        credentials: PasswordDeleteCredentials,
+        verifier: &V,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<(), ErrorResponse> {
+        Self::validate_password(id, &credentials.password, verifier, db).await?;
+        if Self::get_two_factor_secret(id, db).await?.is_none() {
+            return Err(error!(
+                VALIDATION,
+                "two_factor_code", "The user doesn't have two-factor authentication enabled"
+            ));
+        }
+        Self::validate_two_factor(id, credentials.two_factor_code.as_deref(), verifier, db).await?;
+        let mut transaction = db.begin().await.map_err(|err| {
+            log::error!("Couldn't start two-factor transaction: {}", err);
+            error!(SERVER, "Couldn't disable two-factor authentication")
+        })?;
+        sqlx::query!(
+            "
+UPDATE users
+SET two_factor_auth = NULL, two_factor_last_step = NULL
+WHERE id = $1
+            ",
+            id as i64,
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't disable two-factor for user {}: {}", id, err);
+            error!(SERVER, "Couldn't disable two-factor authentication")
+        })?;
+        sqlx::query!(
+            "
+DELETE FROM two_factor_recovery_codes
+WHERE user_id = $1
+            ",
+            id as i64,
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't delete recovery codes of user {}: {}", id, err);
+            error!(SERVER, "Couldn't disable two-factor authentication")
+        })?;
+        transaction.commit().await.map_err(|err| {
+            log::error!("Couldn't commit two-factor transaction: {}", err);
+            error!(SERVER, "Couldn't disable two-factor authentication")
+        })?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::generate_totp;
+
+    #[test]
+    fn totp() {
+        // RFC 6238 SHA-1 test vectors truncated to 6 digits.
+        let secret = b"12345678901234567890";
+        assert_eq!(generate_totp(secret, 59 / 30), 287082);
+        assert_eq!(generate_totp(secret, 1111111109 / 30), 81804);
+        assert_eq!(generate_totp(secret, 1234567890 / 30), 5924);
+    }
+}
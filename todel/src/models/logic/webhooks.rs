@@ -0,0 +1,293 @@
+use rand::{distributions::Alphanumeric, Rng};
+use sqlx::{pool::PoolConnection, postgres::PgRow, FromRow, Postgres, Row};
+
+use crate::{
+    ids::IdGenerator,
+    models::{
+        ErrorResponse, File, MessageWebhook, SphereChannel, Webhook, WebhookCreate, WebhookCreated,
+        WebhookEdit,
+    },
+};
+
+/// The maximum amount of webhooks a channel can have.
+const MAX_WEBHOOKS: i64 = 15;
+/// The length of generated webhook tokens.
+const WEBHOOK_TOKEN_LENGTH: usize = 64;
+
+/// Validate a webhook's name.
+fn validate_name(name: &str) -> Result<(), ErrorResponse> {
+    if name.len() < 2 || name.len() > 32 {
+        return Err(error!(
+            VALIDATION,
+            "name", "The webhook's name must be between 2 and 32 characters in length"
+        ));
+    }
+    Ok(())
+}
+
+/// Make sure a webhook's avatar exists in the avatars bucket.
+async fn validate_avatar(
+    avatar: u64,
+    db: &mut PoolConnection<Postgres>,
+) -> Result<(), ErrorResponse> {
+    if File::get(avatar, "avatars", db).await.is_none() {
+        return Err(error!(
+            VALIDATION,
+            "avatar", "The webhook's avatar must be a valid file that exists in the avatars bucket"
+        ));
+    }
+    Ok(())
+}
+
+impl FromRow<'_, PgRow> for Webhook {
+    fn from_row(row: &PgRow) -> sqlx::Result<Self> {
+        Ok(Self {
+            id: row.get::<i64, _>("id") as u64,
+            channel_id: row.get::<i64, _>("channel_id") as u64,
+            creator_id: row.get::<Option<i64>, _>("creator_id").map(|c| c as u64),
+            name: row.get("name"),
+            avatar: row.get::<Option<i64>, _>("avatar").map(|a| a as u64),
+        })
+    }
+}
+
+impl WebhookCreate {
+    pub async fn validate(
+        &mut self,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<(), ErrorResponse> {
+        self.name = self.name.trim().to_string();
+        validate_name(&self.name)?;
+        if let Some(avatar) = self.avatar {
+            validate_avatar(avatar, db).await?;
+        }
+        Ok(())
+    }
+}
+
+impl WebhookEdit {
+    pub async fn validate(
+        &mut self,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<(), ErrorResponse> {
+        if self.name.is_none() && self.avatar.is_none() {
+            return Err(error!(VALIDATION, "body", "At least one field must exist"));
+        }
+        if let Some(name) = &mut self.name {
+            *name = name.trim().to_string();
+            validate_name(name)?;
+        }
+        if let Some(Some(avatar)) = self.avatar {
+            validate_avatar(avatar, db).await?;
+        }
+        Ok(())
+    }
+}
+
+impl Webhook {
+    pub async fn create<R: Rng>(
+        mut webhook: WebhookCreate,
+        channel_id: u64,
+        creator_id: u64,
+        rng: &mut R,
+        id_generator: &mut IdGenerator,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<WebhookCreated, ErrorResponse> {
+        webhook.validate(db).await?;
+        if let SphereChannel::Forum(_) = SphereChannel::get(channel_id, db).await? {
+            return Err(error!(
+                VALIDATION,
+                "channel", "Webhooks can't be created in forum channels"
+            ));
+        }
+        let count = sqlx::query!(
+            "
+SELECT COUNT(id)
+FROM webhooks
+WHERE channel_id = $1
+            ",
+            channel_id as i64
+        )
+        .fetch_one(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't count webhooks of channel {}: {}", channel_id, err);
+            error!(SERVER, "Failed to create webhook")
+        })?
+        .count
+        .unwrap_or(0);
+        if count >= MAX_WEBHOOKS {
+            return Err(error!(
+                VALIDATION,
+                "channel",
+                format!("Channels can't have more than {} webhooks", MAX_WEBHOOKS)
+            ));
+        }
+        let id = id_generator.generate();
+        let token: String = rng
+            .sample_iter(&Alphanumeric)
+            .take(WEBHOOK_TOKEN_LENGTH)
+            .map(char::from)
+            .collect();
+        sqlx::query!(
+            "
+INSERT INTO webhooks(id, channel_id, creator_id, name, avatar, token_hash)
+VALUES($1, $2, $3, $4, $5, $6)
+            ",
+            id as i64,
+            channel_id as i64,
+            creator_id as i64,
+            webhook.name,
+            webhook.avatar.map(|a| a as i64),
+            sha256::digest(token.as_str()),
+        )
+        .execute(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!(
+                "Couldn't create webhook for channel {} by {}: {}",
+                channel_id,
+                creator_id,
+                err
+            );
+            error!(SERVER, "Failed to create webhook")
+        })?;
+        Ok(WebhookCreated {
+            token,
+            webhook: Self {
+                id,
+                channel_id,
+                creator_id: Some(creator_id),
+                name: webhook.name,
+                avatar: webhook.avatar,
+            },
+        })
+    }
+
+    /// Get a webhook whose channel still exists.
+    pub async fn get(id: u64, db: &mut PoolConnection<Postgres>) -> Result<Self, ErrorResponse> {
+        sqlx::query_as(
+            "
+SELECT w.*
+FROM webhooks w
+JOIN channels c
+ON w.channel_id = c.id
+WHERE w.id = $1
+    AND c.is_deleted = FALSE
+            ",
+        )
+        .bind(id as i64)
+        .fetch_optional(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch webhook {}: {}", id, err);
+            error!(SERVER, "Failed to get webhook")
+        })?
+        .ok_or_else(|| error!(NOT_FOUND))
+    }
+
+    /// Get a webhook using its token, this is how webhooks authenticate.
+    pub async fn get_with_token(
+        id: u64,
+        token: &str,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<Self, ErrorResponse> {
+        sqlx::query_as(
+            "
+SELECT w.*
+FROM webhooks w
+JOIN channels c
+ON w.channel_id = c.id
+WHERE w.id = $1
+    AND w.token_hash = $2
+    AND c.is_deleted = FALSE
+            ",
+        )
+        .bind(id as i64)
+        .bind(sha256::digest(token))
+        .fetch_optional(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch webhook {}: {}", id, err);
+            error!(SERVER, "Failed to get webhook")
+        })?
+        .ok_or_else(|| error!(NOT_FOUND))
+    }
+
+    /// Get all of a channel's webhooks.
+    pub async fn get_all(
+        channel_id: u64,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<Vec<Self>, ErrorResponse> {
+        sqlx::query_as(
+            "
+SELECT *
+FROM webhooks
+WHERE channel_id = $1
+ORDER BY id
+            ",
+        )
+        .bind(channel_id as i64)
+        .fetch_all(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't fetch webhooks of channel {}: {}", channel_id, err);
+            error!(SERVER, "Failed to get webhooks")
+        })
+    }
+
+    pub async fn edit(
+        &mut self,
+        mut edit: WebhookEdit,
+        db: &mut PoolConnection<Postgres>,
+    ) -> Result<(), ErrorResponse> {
+        edit.validate(db).await?;
+        let name = edit.name.unwrap_or_else(|| self.name.clone());
+        let avatar = edit.avatar.unwrap_or(self.avatar);
+        sqlx::query!(
+            "
+UPDATE webhooks
+SET name = $1, avatar = $2
+WHERE id = $3
+            ",
+            name,
+            avatar.map(|a| a as i64),
+            self.id as i64,
+        )
+        .execute(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't edit webhook {}: {}", self.id, err);
+            error!(SERVER, "Failed to edit webhook")
+        })?;
+        self.name = name;
+        self.avatar = avatar;
+        Ok(())
+    }
+
+    pub async fn delete(&self, db: &mut PoolConnection<Postgres>) -> Result<(), ErrorResponse> {
+        sqlx::query!(
+            "
+DELETE FROM webhooks
+WHERE id = $1
+            ",
+            self.id as i64,
+        )
+        .execute(&mut **db)
+        .await
+        .map_err(|err| {
+            log::error!("Couldn't delete webhook {}: {}", self.id, err);
+            error!(SERVER, "Failed to delete webhook")
+        })?;
+        Ok(())
+    }
+
+    /// Get the webhook as it should be shown on the messages it sends.
+    pub fn to_message_webhook(&self) -> MessageWebhook {
+        MessageWebhook {
+            id: self.id,
+            name: self.name.clone(),
+            avatar: self.avatar,
+        }
+    }
+}
@@ -18,7 +18,8 @@ use super::User;
 ///     ...
 ///   },
 ///   "sphere_id": 4080402038786,
-///   "nickname": "Nicky"
+///   "nickname": "Nicky",
+///   "roles": [5490083823625]
 /// }
 /// ```
 #[autodoc(category = "Members")]
@@ -43,6 +44,13 @@ pub struct Member {
     /// The sphere-specific status of this member.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sphere_status: Option<String>,
+    /// The IDs of the roles this member has.
+    pub roles: Vec<u64>,
+    /// The time until which this member is timed out, in seconds since the Unix epoch.
+    ///
+    /// Timed out members can't send messages or add reactions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timed_out_until: Option<u64>,
 }
 
 /// The MemberEdit payload.
@@ -99,4 +107,82 @@ pub struct MemberEdit {
         with = "double_option"
     )]
     pub sphere_status: Option<Option<String>>,
+    /// The IDs of the roles this member should have, replacing their current ones.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roles: Option<Vec<u64>>,
+}
+
+/// The MemberTimeout payload.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "duration": 3600
+/// }
+/// ```
+#[autodoc(category = "Members", hidden = true)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemberTimeout {
+    /// How long the member should be timed out for in seconds, at most 28 days.
+    ///
+    /// A duration of 0 removes the member's current timeout.
+    pub duration: u64,
+}
+
+/// The SphereBan payload. This represents a user who's banned from a sphere.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "user_id": 48615849987333,
+///   "sphere_id": 4080402038786,
+///   "moderator_id": 48615849987334,
+///   "reason": "Posting gacha pulls in #general",
+///   "expires_at": 1750000000
+/// }
+/// ```
+#[autodoc(category = "Members")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SphereBan {
+    /// The ID of the banned user.
+    pub user_id: u64,
+    /// The ID of the sphere the user is banned from.
+    pub sphere_id: u64,
+    /// The ID of the member who banned the user.
+    pub moderator_id: u64,
+    /// The reason for the ban.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// When the ban expires in seconds since the Unix epoch, permanent if not present.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+}
+
+/// The SphereBanCreate payload.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "reason": "Posting gacha pulls in #general",
+///   "duration": 604800
+/// }
+/// ```
+#[autodoc(category = "Members", hidden = true)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SphereBanCreate {
+    /// The reason for the ban. This field has to be between 1 and 512 characters long.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// How long the ban should last in seconds, the ban is permanent if not present.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<u64>,
 }
@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 use serde_with::rust::double_option;
 
-use super::{Attachment, AttachmentCreate, CustomEmbed, Embed, Reaction, SphereChannel, User};
+use super::{
+    Attachment, AttachmentCreate, Channel, CustomEmbed, Embed, MessageWebhook, Reaction, User,
+};
 
 /// The MessageCreate payload. This is used when you want to create a message using the REST API.
 ///
@@ -32,13 +34,15 @@ pub struct MessageCreate {
     /// The ID of the message referenced by this message.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reference: Option<u64>,
+    /// Deprecated, use a [`Webhook`] instead.
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "_disguise")]
     pub disguise: Option<MessageDisguise>,
 }
 
-/// A temporary way to mask the message's author's name and avatar. This is mainly used for
-/// bridging and will be removed when webhooks are officially supported.
+/// A temporary way to mask the message's author's name and avatar.
+///
+/// This is deprecated in favour of [`Webhook`]s and will be removed in a future version.
 ///
 /// -----
 ///
@@ -130,13 +134,18 @@ pub struct Message {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reference: Option<Box<Message>>,
     /// The channel in which the message is sent.
-    pub channel: SphereChannel,
+    pub channel: Channel,
     /// The attachments of this message.
     pub attachments: Vec<Attachment>,
     /// The embeds of this message.
     pub embeds: Vec<Embed>,
     /// The reactions of this message.
     pub reactions: Vec<Reaction>,
+    /// The webhook this message was sent through. The message's author is made up from the
+    /// webhook's name and avatar in that case.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<MessageWebhook>,
+    /// Deprecated, see [`MessageCreate`]'s `_disguise`.
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "_disguise")]
     pub disguise: Option<MessageDisguise>,
@@ -1,6 +1,8 @@
 //! A collection of models and some related function implementations for eludris.
 
+mod applications;
 mod attachments;
+mod audit_log;
 mod categories;
 mod channels;
 mod embeds;
@@ -8,14 +10,23 @@ mod emojis;
 mod files;
 mod gateway;
 mod info;
+mod invites;
 mod members;
 mod messages;
+mod oauth;
+mod posts;
+mod read_states;
 mod response;
+mod roles;
 mod sessions;
 mod spheres;
 mod users;
+mod voice;
+mod webhooks;
 
+pub use applications::*;
 pub use attachments::*;
+pub use audit_log::*;
 pub use categories::*;
 pub use channels::*;
 pub use embeds::*;
@@ -23,12 +34,19 @@ pub use emojis::*;
 pub use files::*;
 pub use gateway::*;
 pub use info::*;
+pub use invites::*;
 pub use members::*;
 pub use messages::*;
+pub use oauth::*;
+pub use posts::*;
+pub use read_states::*;
 pub use response::*;
+pub use roles::*;
 pub use sessions::*;
 pub use spheres::*;
 pub use users::*;
+pub use voice::*;
+pub use webhooks::*;
 
 #[cfg(feature = "logic")]
 mod logic;
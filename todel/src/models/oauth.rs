@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+
+use super::Application;
+
+/// The scopes an OAuth2 application can request, limiting which parts of the API its tokens
+/// can access.
+///
+/// Scopes are stored as a bitfield, each variant being one bit of it. They are requested as a
+/// space separated list of their names, for example `identify spheres.read`.
+#[autodoc(category = "OAuth2")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u64)]
+pub enum OAuthScope {
+    /// Allows reading users, including the authorizing user (`1 << 0`).
+    #[serde(rename = "identify")]
+    Identify = 1 << 0,
+    /// Allows reading the spheres the user is in along with their channels (`1 << 1`).
+    #[serde(rename = "spheres.read")]
+    SpheresRead = 1 << 1,
+    /// Allows joining, leaving and managing spheres on behalf of the user (`1 << 2`).
+    #[serde(rename = "spheres.write")]
+    SpheresWrite = 1 << 2,
+    /// Allows reading messages and posts (`1 << 3`).
+    #[serde(rename = "messages.read")]
+    MessagesRead = 1 << 3,
+    /// Allows sending, editing and reacting to messages and posts on behalf of the user
+    /// (`1 << 4`).
+    #[serde(rename = "messages.write")]
+    MessagesWrite = 1 << 4,
+    /// Allows connecting to the gateway as the user (`1 << 5`).
+    #[serde(rename = "gateway")]
+    Gateway = 1 << 5,
+}
+
+/// The OAuthAuthorize payload. These are the query parameters of an OAuth2 authorization request
+/// as described in RFC 6749 and RFC 7636.
+///
+/// Only the authorization code flow with `S256` PKCE challenges is supported.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "response_type": "code",
+///   "client_id": 48615849987334,
+///   "redirect_uri": "https://pengin.example/callback",
+///   "scope": "identify spheres.read",
+///   "state": "xyz",
+///   "code_challenge": "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM",
+///   "code_challenge_method": "S256"
+/// }
+/// ```
+#[autodoc(category = "OAuth2", hidden = true)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "http", derive(rocket::FromForm))]
+pub struct OAuthAuthorize {
+    /// The type of response, this has to be `code`.
+    pub response_type: String,
+    /// The ID of the application requesting authorization.
+    pub client_id: u64,
+    /// The URI the user is redirected to, this has to be one of the application's redirect URIs.
+    pub redirect_uri: String,
+    /// The space separated [`OAuthScope`]s the application is requesting.
+    pub scope: String,
+    /// An opaque value which is passed back to the application in the redirect.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    /// The PKCE code challenge.
+    pub code_challenge: String,
+    /// The PKCE code challenge method, this has to be `S256`.
+    pub code_challenge_method: String,
+}
+
+/// The OAuthAuthorization payload. This describes an authorization request so that the user can
+/// be asked for their consent.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "application": {
+///     "id": 48615849987334,
+///     "owner_id": 48615849987333,
+///     "redirect_uris": ["https://pengin.example/callback"],
+///     "bot": {
+///       "id": 48615849987334,
+///       "username": "pengin-bot",
+///       "social_credit": 0,
+///       "status": {
+///         "type": "ONLINE"
+///       },
+///       "badges": 1,
+///       "permissions": 0
+///     }
+///   },
+///   "scopes": ["identify", "spheres.read"]
+/// }
+/// ```
+#[autodoc(category = "OAuth2")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OAuthAuthorization {
+    /// The application requesting authorization.
+    pub application: Application,
+    /// The scopes the application is requesting.
+    pub scopes: Vec<OAuthScope>,
+}
+
+/// The OAuthAuthorized payload. This is returned once the user approves an authorization
+/// request.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "redirect_uri": "https://pengin.example/callback?code=Q2x8pZkWm3rV9tYb&state=xyz"
+/// }
+/// ```
+#[autodoc(category = "OAuth2")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OAuthAuthorized {
+    /// The URI the user should be redirected to, containing the authorization code.
+    pub redirect_uri: String,
+}
+
+/// The OAuthTokenCreate payload. This is the form encoded body of an OAuth2 token request.
+///
+/// The `authorization_code` grant requires `code`, `redirect_uri` and `code_verifier` while the
+/// `refresh_token` grant requires `refresh_token`.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "grant_type": "authorization_code",
+///   "client_id": 48615849987334,
+///   "code": "Q2x8pZkWm3rV9tYb",
+///   "redirect_uri": "https://pengin.example/callback",
+///   "code_verifier": "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"
+/// }
+/// ```
+#[autodoc(category = "OAuth2", hidden = true)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "http", derive(rocket::FromForm))]
+pub struct OAuthTokenCreate {
+    /// The grant type, either `authorization_code` or `refresh_token`.
+    pub grant_type: String,
+    /// The ID of the application requesting the token.
+    pub client_id: u64,
+    /// The authorization code.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// The redirect URI used in the authorization request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect_uri: Option<String>,
+    /// The PKCE code verifier.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code_verifier: Option<String>,
+    /// The refresh token.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+}
+
+/// The OAuthToken payload. This is returned by the OAuth2 token endpoint.
+///
+/// The access token is used like any other token and shows up as a session of the user.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "access_token": "<token>",
+///   "token_type": "Bearer",
+///   "expires_in": 86400,
+///   "refresh_token": "<refresh token>",
+///   "scope": "identify spheres.read"
+/// }
+/// ```
+#[autodoc(category = "OAuth2")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OAuthToken {
+    /// The access token.
+    pub access_token: String,
+    /// The type of the token, this is always `Bearer`.
+    pub token_type: String,
+    /// How many seconds the access token is valid for.
+    pub expires_in: u64,
+    /// The refresh token, used to get a new access token once this one expires.
+    pub refresh_token: String,
+    /// The space separated [`OAuthScope`]s the token was granted.
+    pub scope: String,
+}
@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+use serde_with::rust::double_option;
+
+use super::{Attachment, Embed, MessageCreate, MessageEdit, User};
+
+/// The PostCreate payload. This is used when you want to create a post or a comment in a forum
+/// channel using the REST API.
+///
+/// Aside from its title, flair and parent, a post takes the same fields as a [`MessageCreate`]
+/// payload except for `reference` and `_disguise`, so at least either content, an attachment or
+/// an embed have to exist.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "title": "I made a thing!",
+///   "flair": "showcase",
+///   "content": "It's a rock, but it thinks."
+/// }
+/// ```
+#[autodoc(category = "Posts")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PostCreate {
+    /// The post's title, this has to be between 1 and 256 characters long.
+    ///
+    /// This is required for posts and can't be set for comments.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The post's flair, this has to be between 1 and 32 characters long.
+    ///
+    /// This can't be set for comments.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flair: Option<String>,
+    /// The ID of the post or comment this comment replies to.
+    ///
+    /// Leaving this out creates a new top level post.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<u64>,
+    #[serde(flatten)]
+    pub message: MessageCreate,
+}
+
+/// The PostEdit payload. This is used when you want to edit an existing post or comment using the
+/// REST API.
+///
+/// Aside from the title and flair, a post edit takes the same fields as a [`MessageEdit`] payload.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "flair": null,
+///   "content": "It's a rock, but it thinks. EDIT: It stopped thinking."
+/// }
+/// ```
+#[autodoc(category = "Posts")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PostEdit {
+    /// The post's new title, this has to be between 1 and 256 characters long.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The post's new flair, this has to be between 1 and 32 characters long.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    pub flair: Option<Option<String>>,
+    #[serde(flatten)]
+    pub message: MessageEdit,
+}
+
+/// The Post payload. This is returned when you're provided information about a pre-existing post
+/// or comment.
+///
+/// Comments are posts which have a parent.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "id": 5490083823641,
+///   "channel_id": 4080402038801,
+///   "author": {
+///     "id": 48615849987333,
+///     "username": "mlynar",
+///     "social_credit": 9999,
+///     "badges": 256,
+///     "permissions": 8
+///   },
+///   "title": "I made a thing!",
+///   "flair": "showcase",
+///   "content": "It's a rock, but it thinks.",
+///   "attachments": [],
+///   "embeds": [],
+///   "score": 42,
+///   "comment_count": 1,
+///   "comments": [
+///     {
+///       "id": 5490083823644,
+///       "channel_id": 4080402038801,
+///       "author": {
+///         "id": 48615849987334,
+///         "username": "barbaz",
+///         "social_credit": 3,
+///         "badges": 0,
+///         "permissions": 0
+///       },
+///       "parent_id": 5490083823641,
+///       "content": "What does it think about?",
+///       "attachments": [],
+///       "embeds": [],
+///       "score": 7,
+///       "comment_count": 0
+///     }
+///   ]
+/// }
+/// ```
+#[autodoc(category = "Posts")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Post {
+    /// The ID of the post.
+    pub id: u64,
+    /// The ID of the forum channel the post is in.
+    pub channel_id: u64,
+    /// The post's author.
+    pub author: User,
+    /// The post's title. This is only present on top level posts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The post's flair.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flair: Option<String>,
+    /// The ID of the post or comment this comment replies to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<u64>,
+    /// The post's content.
+    pub content: Option<String>,
+    /// The attachments of this post.
+    pub attachments: Vec<Attachment>,
+    /// The embeds of this post.
+    pub embeds: Vec<Embed>,
+    /// The post's score, its up-votes minus its down-votes.
+    pub score: i32,
+    /// The amount of direct replies this post has.
+    pub comment_count: u32,
+    /// The replies to this post, each with their own replies.
+    ///
+    /// This is only populated when fetching a single post.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub comments: Vec<Post>,
+}
+
+/// The PostVote payload. This is used when you want to up-vote or down-vote a post or a comment
+/// using the REST API.
+///
+/// Each user has one vote per post, voting again replaces their previous vote. Votes are added to
+/// the social credit of the post's author.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "vote": 1
+/// }
+/// ```
+#[autodoc(category = "Posts")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PostVote {
+    /// The vote, `1` for an up-vote and `-1` for a down-vote.
+    pub vote: i8,
+}
+
+/// The order in which a forum channel's posts are listed.
+#[autodoc(category = "Posts")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum PostSort {
+    /// Posts with a high score relative to their age first.
+    #[default]
+    Hot,
+    /// Posts with the highest score first.
+    Top,
+    /// The newest posts first.
+    New,
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+/// The ReadState payload. This tracks which messages of a channel a user has read.
+///
+/// A channel has unread messages if its `last_message_id` is greater than its `last_acked_id`.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "channel_id": 4080402038789,
+///   "last_acked_id": 5490083823641,
+///   "last_message_id": 5490083823690,
+///   "mention_count": 2
+/// }
+/// ```
+#[autodoc(category = "Messaging")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReadState {
+    /// The ID of the channel this read state belongs to.
+    pub channel_id: u64,
+    /// The ID of the last message the user acknowledged in this channel.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_acked_id: Option<u64>,
+    /// The ID of the latest message in this channel.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_message_id: Option<u64>,
+    /// How many times the user was mentioned in this channel since they last acknowledged a
+    /// message in it.
+    pub mention_count: u32,
+}
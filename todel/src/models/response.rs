@@ -37,6 +37,22 @@ pub enum ErrorResponse {
         #[serde(flatten)]
         shared: SharedErrorData,
     },
+    /// The error when a user with two-factor authentication enabled didn't provide a code from
+    /// their authenticator app or one of their recovery codes. Clients should prompt the user for
+    /// one and retry the request with it.
+    /// -----
+    /// ### Example
+    /// ```json
+    /// {
+    ///   "type": "TWO_FACTOR_REQUIRED",
+    ///   "status": 401,
+    ///   "message": "The user has two-factor authentication enabled and must provide a code"
+    /// }
+    /// ```
+    TwoFactorRequired {
+        #[serde(flatten)]
+        shared: SharedErrorData,
+    },
     /// The error when a client *has* been succesfully authorized but does not have the required
     /// permissions to execute an action.
     ///
@@ -200,6 +216,14 @@ macro_rules! error {
             }
         }
     };
+    (TWO_FACTOR_REQUIRED) => {
+        ErrorResponse::TwoFactorRequired {
+            shared: $crate::models::SharedErrorData {
+                status: 401,
+                message: "The user has two-factor authentication enabled and must provide a code".to_string(),
+            }
+        }
+    };
     (FORBIDDEN) => {
         ErrorResponse::Forbidden {
             shared: $crate::models::SharedErrorData {
@@ -268,6 +292,7 @@ impl Display for ErrorResponse {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ErrorResponse::Unauthorized { shared, .. } => write!(f, "{}", shared.message),
+            ErrorResponse::TwoFactorRequired { shared, .. } => write!(f, "{}", shared.message),
             ErrorResponse::Forbidden { shared, .. } => write!(f, "{}", shared.message),
             ErrorResponse::NotFound { shared, .. } => write!(f, "{}", shared.message),
             ErrorResponse::Conflict { shared, item } => write!(f, "{}: {}", shared.message, item),
@@ -288,6 +313,7 @@ impl ErrorResponse {
     pub fn shared(&self) -> &SharedErrorData {
         match self {
             ErrorResponse::Unauthorized { shared, .. } => shared,
+            ErrorResponse::TwoFactorRequired { shared, .. } => shared,
             ErrorResponse::Forbidden { shared, .. } => shared,
             ErrorResponse::NotFound { shared, .. } => shared,
             ErrorResponse::Conflict { shared, .. } => shared,
@@ -319,6 +345,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn two_factor_required_error() {
+        assert_eq!(
+            error!(TWO_FACTOR_REQUIRED),
+            ErrorResponse::TwoFactorRequired {
+                shared: SharedErrorData {
+                    status: 401,
+                    message:
+                        "The user has two-factor authentication enabled and must provide a code"
+                            .to_string(),
+                },
+            }
+        );
+    }
+
     #[test]
     fn forbidden_error() {
         assert_eq!(
@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+use serde_with::rust::double_option;
+
+/// The permissions that can be granted to sphere members, either through the sphere's
+/// `default_permissions` or through their roles.
+///
+/// Permissions are stored as a bitfield, each variant being one bit of it.
+#[autodoc(category = "Roles")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[repr(u64)]
+pub enum SpherePermission {
+    /// Grants every other permission and bypasses channel overwrites (`1 << 0`).
+    Administrator = 1 << 0,
+    /// Allows editing the sphere itself and managing all of its invites (`1 << 1`).
+    ManageSphere = 1 << 1,
+    /// Allows creating, editing, deleting and assigning roles below the member's highest role
+    /// (`1 << 2`).
+    ManageRoles = 1 << 2,
+    /// Allows creating, editing and deleting channels and categories (`1 << 3`).
+    ManageChannels = 1 << 3,
+    /// Allows creating, editing and deleting emojis (`1 << 4`).
+    ManageEmojis = 1 << 4,
+    /// Allows changing other members' nicknames and resetting their sphere profiles (`1 << 5`).
+    ManageNicknames = 1 << 5,
+    /// Allows removing other members from the sphere (`1 << 6`).
+    KickMembers = 1 << 6,
+    /// Allows managing other members' messages and reactions (`1 << 7`).
+    ManageMessages = 1 << 7,
+    /// Allows viewing channels and reading their messages (`1 << 8`).
+    ViewChannels = 1 << 8,
+    /// Allows sending messages (`1 << 9`).
+    SendMessages = 1 << 9,
+    /// Allows reacting to messages (`1 << 10`).
+    AddReactions = 1 << 10,
+    /// Allows banning and unbanning members (`1 << 11`).
+    BanMembers = 1 << 11,
+    /// Allows timing members out, stopping them from sending messages and reacting (`1 << 12`).
+    TimeoutMembers = 1 << 12,
+    /// Allows viewing the sphere's audit log (`1 << 13`).
+    ViewAuditLog = 1 << 13,
+    /// Allows creating invites and revoking the member's own ones (`1 << 14`).
+    CreateInvites = 1 << 14,
+    /// Allows creating, editing and deleting webhooks (`1 << 15`).
+    ManageWebhooks = 1 << 15,
+}
+
+impl SpherePermission {
+    /// Every permission bit that is currently defined.
+    pub const ALL: u64 = (1 << 16) - 1;
+    /// The permissions a sphere grants to all of its members by default.
+    pub const DEFAULT: u64 = Self::ViewChannels as u64
+        | Self::SendMessages as u64
+        | Self::AddReactions as u64
+        | Self::CreateInvites as u64;
+    /// The permissions that can be overwritten per channel.
+    pub const CHANNEL: u64 = Self::ManageMessages as u64
+        | Self::ViewChannels as u64
+        | Self::SendMessages as u64
+        | Self::AddReactions as u64;
+    /// The permissions every member of a direct message or group channel has.
+    pub const PRIVATE_CHANNEL: u64 =
+        Self::ViewChannels as u64 | Self::SendMessages as u64 | Self::AddReactions as u64;
+
+    /// Check whether this permission is set in a permission bitfield.
+    pub fn is_set(self, permissions: u64) -> bool {
+        permissions & self as u64 != 0
+    }
+}
+
+/// The Role payload. Roles are used to group sphere members and grant them permissions.
+///
+/// Roles with a lower position are higher in the sphere's role hierarchy.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "id": 5490083823625,
+///   "sphere_id": 5490083823619,
+///   "name": "moderators",
+///   "colour": 16750848,
+///   "position": 0,
+///   "permissions": 228
+/// }
+/// ```
+#[autodoc(category = "Roles")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Role {
+    /// The ID of this role.
+    pub id: u64,
+    /// The ID of the sphere this role belongs to.
+    pub sphere_id: u64,
+    /// The name of this role.
+    pub name: String,
+    /// The colour of this role as a `0xRRGGBB` integer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub colour: Option<u32>,
+    /// This role's position inside of its sphere's role hierarchy.
+    pub position: u32,
+    /// The permissions this role grants as a bitfield of [`SpherePermission`]s.
+    pub permissions: u64,
+}
+
+/// The RoleCreate payload.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "name": "moderators",
+///   "colour": 16750848,
+///   "permissions": 228
+/// }
+/// ```
+#[autodoc(category = "Roles", hidden = true)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoleCreate {
+    /// The name of the new role. This field has to be between 1 and 32 characters long.
+    pub name: String,
+    /// The colour of the new role as a `0xRRGGBB` integer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub colour: Option<u32>,
+    /// The permissions of the new role. Defaults to no permissions.
+    #[serde(default)]
+    pub permissions: u64,
+}
+
+/// The RoleEdit payload.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "name": "janitors",
+///   "colour": null,
+///   "position": 2
+/// }
+/// ```
+#[autodoc(category = "Roles", hidden = true)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoleEdit {
+    /// The new name of the role. This field has to be between 1 and 32 characters long.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The new colour of the role.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    pub colour: Option<Option<u32>>,
+    /// The new position of the role.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<u32>,
+    /// The new permissions of the role.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<u64>,
+}
@@ -16,7 +16,11 @@ use serde::{Deserialize, Serialize};
 ///   "user_id": 2312155693057,
 ///   "platform": "linux",
 ///   "client": "pilfer",
-///   "ip": "127.0.0.1"
+///   "ip": "127.0.0.1",
+///   "bot": false,
+///   "created_at": 1751300000,
+///   "last_used_at": 1751386400,
+///   "last_ip": "127.0.0.1"
 /// }
 /// ```
 #[autodoc(category = "Sessions")]
@@ -32,6 +36,24 @@ pub struct Session {
     pub client: String,
     /// The session's creation IP address.
     pub ip: IpAddr,
+    /// Whether this is a bot's session, created through its [`Application`].
+    #[serde(default)]
+    pub bot: bool,
+    /// The ID of the application this session was authorized for through OAuth2.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub application_id: Option<u64>,
+    /// The [`OAuthScope`]s this session was granted as a bitfield, sessions which aren't
+    /// scoped have full access to the user's account.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<u64>,
+    /// When the session was created in seconds since the Unix epoch.
+    pub created_at: u64,
+    /// When the session was last used in seconds since the Unix epoch.
+    ///
+    /// This is only updated about once a minute.
+    pub last_used_at: u64,
+    /// The IP address the session was last used from.
+    pub last_ip: IpAddr,
 }
 
 /// The SessionCreate payload.
@@ -61,6 +83,10 @@ pub struct SessionCreate {
     pub platform: String,
     /// The client the session was created by.
     pub client: String,
+    /// A code from the user's authenticator app or one of their recovery codes, required if
+    /// they have two-factor authentication enabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub two_factor_code: Option<String>,
 }
 
 /// The response to a [`SessionCreate`].
@@ -77,7 +103,11 @@ pub struct SessionCreate {
 ///     "user_id": 2312155693057,
 ///     "platform": "linux",
 ///     "client": "pilfer",
-///     "ip": "127.0.0.1"
+///     "ip": "127.0.0.1",
+///     "bot": false,
+///     "created_at": 1751300000,
+///     "last_used_at": 1751300000,
+///     "last_ip": "127.0.0.1"
 ///   }
 /// }
 /// ```
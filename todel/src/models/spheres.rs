@@ -3,7 +3,7 @@ use std::fmt::Display;
 use serde::{Deserialize, Serialize};
 use serde_with::rust::double_option;
 
-use super::{Category, Emoji, Member};
+use super::{Category, Emoji, Member, Role};
 
 /// The different types a sphere can be.
 #[autodoc(category = "Spheres")]
@@ -47,6 +47,9 @@ impl Display for SphereType {
 ///   "description": "Truly the sphere of all time",
 ///   "icon": 4080412852228,
 ///   "badges": 0,
+///   "default_permissions": 18176,
+///   "discoverable": true,
+///   "invite_only": false,
 ///   "categories": [
 ///     {
 ///       "id":5490083823619,
@@ -78,9 +81,11 @@ impl Display for SphereType {
 ///         "email": "john.mahjong@example.com",
 ///         "verified": false
 ///       },
-///       "sphere_id": 5490083823619
+///       "sphere_id": 5490083823619,
+///       "roles": []
 ///     }
-///   ]
+///   ],
+///   "roles": []
 /// }
 /// ```
 #[autodoc(category = "Spheres")]
@@ -109,12 +114,20 @@ pub struct Sphere {
     pub banner: Option<u64>,
     /// The sphere's badges as a bitfield.
     pub badges: u64,
+    /// The permissions every member of this sphere has as a bitfield of [`SpherePermission`]s.
+    pub default_permissions: u64,
+    /// Whether users who aren't members of the sphere can find it through its ID or slug.
+    pub discoverable: bool,
+    /// Whether users can only join the sphere through an [`Invite`].
+    pub invite_only: bool,
     /// The categories that this sphere contains.
     pub categories: Vec<Category>,
     /// The members that are inside this sphere.
     pub members: Vec<Member>,
     /// The emojis that this sphere has.
     pub emojis: Vec<Emoji>,
+    /// The roles that this sphere has.
+    pub roles: Vec<Role>,
 }
 
 /// The SphereCreate payload.
@@ -149,6 +162,12 @@ pub struct SphereCreate {
     /// The sphere's banner. This field has to be a valid file ID in the "sphere-banners" bucket.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub banner: Option<u64>,
+    /// Whether users who aren't members of the sphere can find it, defaults to `true`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discoverable: Option<bool>,
+    /// Whether users can only join the sphere through an invite, defaults to `false`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invite_only: Option<bool>,
 }
 
 /// The SphereEdit payload.
@@ -199,4 +218,13 @@ pub struct SphereEdit {
         with = "double_option"
     )]
     pub banner: Option<Option<u64>>,
+    /// The permissions every member of this sphere has as a bitfield of [`SpherePermission`]s.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_permissions: Option<u64>,
+    /// Whether users who aren't members of the sphere can find it through its ID or slug.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discoverable: Option<bool>,
+    /// Whether users can only join the sphere through an invite.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invite_only: Option<bool>,
 }
@@ -88,7 +88,8 @@ pub struct User {
     pub badges: u64,
     /// The user's instance-wide permissions as a bitfield.
     pub permissions: u64,
-    /// The user's email. This is only shown when the user queries their own data.
+    /// The user's email. This is only shown when the user queries their own data and is never
+    /// present for bots.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub email: Option<String>,
     /// The user's verification status. This is only shown when the user queries their own data.
@@ -106,6 +107,25 @@ impl fmt::Display for User {
     }
 }
 
+/// The badges a user can have.
+///
+/// Badges are stored as a bitfield in [`User`]'s `badges`, each variant being one bit of it.
+#[autodoc(category = "Users")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[repr(u64)]
+pub enum UserBadge {
+    /// The user is a bot owned by another user through an [`Application`] (`1 << 0`).
+    Bot = 1 << 0,
+}
+
+impl UserBadge {
+    /// Check whether this badge is set in a badge bitfield.
+    pub fn is_set(self, badges: u64) -> bool {
+        badges & self as u64 != 0
+    }
+}
+
 /// The UserCreate payload.
 ///
 /// This is used when a user is initially first created. For authentication payloads check
@@ -165,6 +185,10 @@ pub struct UserEdit {
     /// The user's new password.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub new_password: Option<String>,
+    /// A code from the user's authenticator app or one of their recovery codes, required if
+    /// they have two-factor authentication enabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub two_factor_code: Option<String>,
 }
 
 /// The UpdateUserProfile payload. This payload is used to update a user's profile. The abscence of a
@@ -277,11 +301,80 @@ pub struct PasswordReset {
 ///
 /// ```json
 /// {
-///   "password": "wowsuchpassword"
+///   "password": "wowsuchpassword",
+///   "two_factor_code": "123456"
 /// }
 /// ```
 #[autodoc(category = "Users")]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PasswordDeleteCredentials {
     pub password: String,
+    /// A code from the user's authenticator app or one of their recovery codes, required if
+    /// they have two-factor authentication enabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub two_factor_code: Option<String>,
+}
+
+/// The TwoFactorSetup payload. This is returned when a user starts enabling two-factor
+/// authentication.
+///
+/// The secret has to be added to an authenticator app, usually by scanning the `uri` as a QR
+/// code, then confirmed with a first code from it.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "secret": "JBSWY3DPEHPK3PXP",
+///   "uri": "otpauth://totp/eludris:yendri?secret=JBSWY3DPEHPK3PXP&issuer=eludris"
+/// }
+/// ```
+#[autodoc(category = "Users")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TwoFactorSetup {
+    /// The base32 encoded TOTP secret.
+    pub secret: String,
+    /// The `otpauth://` URI of the secret.
+    pub uri: String,
+}
+
+/// The TwoFactorConfirm payload. This is used to finish enabling two-factor authentication.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "code": "123456"
+/// }
+/// ```
+#[autodoc(category = "Users", hidden = true)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TwoFactorConfirm {
+    /// A code from the user's authenticator app.
+    pub code: String,
+}
+
+/// The TwoFactorRecoveryCodes payload. This is returned once two-factor authentication is
+/// enabled.
+///
+/// Each recovery code can be used once in place of a code from the user's authenticator app.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "codes": ["a8k2-x9q3", "m4p7-c2z8", "..."]
+/// }
+/// ```
+#[autodoc(category = "Users")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TwoFactorRecoveryCodes {
+    /// The user's recovery codes.
+    pub codes: Vec<String>,
 }
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+
+/// The VoiceState payload. This represents a user's connection to a sphere voice channel.
+///
+/// Each user can only be connected to one voice channel at a time, from one of their gateway
+/// sessions.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "user_id": 48615849987333,
+///   "session_id": 9323884838914,
+///   "channel_id": 4080402038790,
+///   "sphere_id": 4080402038786,
+///   "self_mute": false,
+///   "self_deaf": false
+/// }
+/// ```
+#[autodoc(category = "Voice")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VoiceState {
+    /// The ID of the connected user.
+    pub user_id: u64,
+    /// The ID of the gateway session the user is connected from.
+    pub session_id: u64,
+    /// The ID of the voice channel the user is connected to, `null` if they just disconnected.
+    pub channel_id: Option<u64>,
+    /// The ID of the sphere the voice channel is in.
+    pub sphere_id: u64,
+    /// Whether the user muted themselves.
+    pub self_mute: bool,
+    /// Whether the user deafened themselves.
+    pub self_deaf: bool,
+}
+
+/// A WebRTC signaling message relayed between two participants of a voice channel.
+///
+/// Voice is peer-to-peer, each participant is expected to open a WebRTC connection with
+/// every other participant using these signals.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "type": "ICE_CANDIDATE",
+///   "candidate": "candidate:842163049 1 udp 1677729535 192.0.2.3 46154 typ srflx raddr 0.0.0.0 rport 0 generation 0",
+///   "sdp_mid": "0",
+///   "sdp_m_line_index": 0
+/// }
+/// ```
+#[autodoc(category = "Voice")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum VoiceSignal {
+    /// A session description offer, sent by the participant initiating the connection.
+    Offer {
+        /// The offer's SDP.
+        sdp: String,
+    },
+    /// A session description answer to an offer.
+    Answer {
+        /// The answer's SDP.
+        sdp: String,
+    },
+    /// An ICE candidate for an ongoing connection.
+    IceCandidate {
+        /// The candidate's description.
+        candidate: String,
+        /// The media stream identification tag of the candidate.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sdp_mid: Option<String>,
+        /// The index of the media description the candidate is associated with.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sdp_m_line_index: Option<u16>,
+    },
+}
@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+use serde_with::rust::double_option;
+
+/// The Webhook payload. Webhooks let external services, like bridges, send messages to a sphere
+/// channel without a user account.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "id": 5490083823650,
+///   "channel_id": 4080402038789,
+///   "creator_id": 48615849987333,
+///   "name": "GitHub",
+///   "avatar": 2255112175647
+/// }
+/// ```
+#[autodoc(category = "Webhooks")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Webhook {
+    /// The webhook's ID.
+    pub id: u64,
+    /// The ID of the channel this webhook sends messages to.
+    pub channel_id: u64,
+    /// The ID of the user who created this webhook, not present if their account was deleted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub creator_id: Option<u64>,
+    /// The name the webhook's messages are shown with. This field has to be between 2 and 32
+    /// characters long.
+    pub name: String,
+    /// The avatar the webhook's messages are shown with. This field has to be a valid file ID in
+    /// the "avatars" bucket.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<u64>,
+}
+
+/// The WebhookCreate payload.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "name": "GitHub",
+///   "avatar": 2255112175647
+/// }
+/// ```
+#[autodoc(category = "Webhooks", hidden = true)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookCreate {
+    /// The webhook's name.
+    pub name: String,
+    /// The webhook's avatar.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<u64>,
+}
+
+/// The WebhookEdit payload.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "name": "GitLab",
+///   "avatar": null
+/// }
+/// ```
+#[autodoc(category = "Webhooks", hidden = true)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookEdit {
+    /// The webhook's new name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The webhook's new avatar. Setting this to null will remove the webhook's avatar.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    pub avatar: Option<Option<u64>>,
+}
+
+/// The response to a [`WebhookCreate`].
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "token": "dWV4Q2xZb3R6c1pMbkNRQ3RVWkhqZ0x5TW1aWFlqRkE",
+///   "webhook": {
+///     "id": 5490083823650,
+///     "channel_id": 4080402038789,
+///     "creator_id": 48615849987333,
+///     "name": "GitHub",
+///     "avatar": 2255112175647
+///   }
+/// }
+/// ```
+#[autodoc(category = "Webhooks")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookCreated {
+    /// The webhook's token. This will only be shown once, the webhook has to be recreated if it
+    /// gets lost.
+    pub token: String,
+    /// The created webhook.
+    pub webhook: Webhook,
+}
+
+/// The webhook a [`Message`] was sent through, with the name and avatar it had at the time.
+///
+/// -----
+///
+/// ### Example
+///
+/// ```json
+/// {
+///   "id": 5490083823650,
+///   "name": "GitHub",
+///   "avatar": 2255112175647
+/// }
+/// ```
+#[autodoc(category = "Webhooks")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageWebhook {
+    /// The webhook's ID.
+    pub id: u64,
+    /// The webhook's name.
+    pub name: String,
+    /// The webhook's avatar.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<u64>,
+}
@@ -0,0 +1,137 @@
+use std::{
+    io::{self, SeekFrom},
+    path::PathBuf,
+};
+
+use rocket::async_trait;
+use tokio::{
+    fs,
+    io::{AsyncReadExt, AsyncSeekExt},
+};
+
+use super::{resolve_range, StorageBackend, StorageObject};
+
+/// Stores objects as files in a directory on the local disk.
+#[derive(Debug, Clone)]
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, path: &str) -> PathBuf {
+        self.root.join(path)
+    }
+}
+
+#[async_trait]
+impl StorageBackend for LocalStorage {
+    async fn put(&self, path: &str, data: Vec<u8>) -> io::Result<()> {
+        let path = self.path(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::write(path, data).await
+    }
+
+    async fn get(&self, path: &str) -> io::Result<StorageObject> {
+        let file = fs::File::open(self.path(path)).await?;
+        let size = file.metadata().await?.len();
+        Ok(StorageObject {
+            reader: Box::pin(file),
+            offset: 0,
+            size,
+            total_size: size,
+        })
+    }
+
+    async fn get_range(
+        &self,
+        path: &str,
+        start: u64,
+        end: Option<u64>,
+    ) -> io::Result<StorageObject> {
+        let mut file = fs::File::open(self.path(path)).await?;
+        let total_size = file.metadata().await?.len();
+        let (start, end) = resolve_range(start, end, total_size)?;
+        file.seek(SeekFrom::Start(start)).await?;
+        let size = end - start + 1;
+        Ok(StorageObject {
+            reader: Box::pin(file.take(size)),
+            offset: start,
+            size,
+            total_size,
+        })
+    }
+
+    async fn delete(&self, path: &str) -> io::Result<()> {
+        match fs::remove_file(self.path(path)).await {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            res => res,
+        }
+    }
+
+    async fn exists(&self, path: &str) -> io::Result<bool> {
+        fs::try_exists(self.path(path)).await
+    }
+
+    async fn size(&self, path: &str) -> io::Result<u64> {
+        Ok(fs::metadata(self.path(path)).await?.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn round_trip() {
+        let root = std::env::temp_dir().join(format!("todel-local-storage-{}", std::process::id()));
+        let storage = LocalStorage::new(&root);
+
+        assert!(!storage.exists("attachments/1").await.unwrap());
+        storage
+            .put("attachments/1", b"hello world".to_vec())
+            .await
+            .unwrap();
+        assert!(storage.exists("attachments/1").await.unwrap());
+        assert_eq!(storage.size("attachments/1").await.unwrap(), 11);
+
+        let object = storage.get("attachments/1").await.unwrap();
+        assert_eq!(object.size, 11);
+        assert_eq!(object.into_bytes().await.unwrap(), b"hello world");
+
+        let object = storage
+            .get_range("attachments/1", 6, Some(7))
+            .await
+            .unwrap();
+        assert_eq!((object.offset, object.size, object.total_size), (6, 2, 11));
+        assert_eq!(object.into_bytes().await.unwrap(), b"wo");
+
+        let object = storage.get_range("attachments/1", 6, None).await.unwrap();
+        assert_eq!(object.into_bytes().await.unwrap(), b"world");
+
+        assert_eq!(
+            storage
+                .get_range("attachments/1", 11, None)
+                .await
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::InvalidInput
+        );
+
+        storage.delete("attachments/1").await.unwrap();
+        storage.delete("attachments/1").await.unwrap();
+        assert_eq!(
+            storage.get("attachments/1").await.unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+
+        fs::remove_dir_all(root).await.unwrap();
+    }
+}
@@ -0,0 +1,147 @@
+//! Pluggable backends for where Effis keeps its files.
+//!
+//! Objects are addressed by slash separated paths relative to the root of the storage, like
+//! `attachments/4080402038789` or `static/logo.png`.
+mod local;
+mod s3;
+
+use std::{io, ops::Deref, pin::Pin, time::Duration};
+
+use rocket::{
+    async_trait,
+    response::{self, Responder},
+    Request, Response,
+};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::conf::StorageConf;
+
+pub use local::LocalStorage;
+pub use s3::S3Storage;
+
+/// An object, or a part of an object, being read from a [`StorageBackend`].
+pub struct StorageObject {
+    /// The object's content.
+    pub reader: Pin<Box<dyn AsyncRead + Send>>,
+    /// Where in the object `reader` starts.
+    pub offset: u64,
+    /// The amount of bytes `reader` yields.
+    pub size: u64,
+    /// The size of the whole object, this is only different from `size` for ranged reads.
+    pub total_size: u64,
+}
+
+impl std::fmt::Debug for StorageObject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StorageObject")
+            .field("offset", &self.offset)
+            .field("size", &self.size)
+            .field("total_size", &self.total_size)
+            .finish_non_exhaustive()
+    }
+}
+
+impl StorageObject {
+    /// Read the entire object into memory.
+    pub async fn into_bytes(mut self) -> io::Result<Vec<u8>> {
+        let mut data = Vec::with_capacity(self.size as usize);
+        self.reader.read_to_end(&mut data).await?;
+        Ok(data)
+    }
+}
+
+impl<'r> Responder<'r, 'static> for StorageObject {
+    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'static> {
+        // Rocket only knows the length of seekable bodies, setting it ourselves lets streamed
+        // objects still be sent with a `Content-Length`.
+        Response::build()
+            .raw_header("Content-Length", self.size.to_string())
+            .streamed_body(self.reader)
+            .ok()
+    }
+}
+
+/// A place Effis can store files in.
+///
+/// Missing objects are reported as [`io::ErrorKind::NotFound`] errors and ranges that start
+/// past the end of an object as [`io::ErrorKind::InvalidInput`] errors.
+#[async_trait]
+pub trait StorageBackend: Send + Sync {
+    /// Store an object, replacing any object which already exists at `path`.
+    async fn put(&self, path: &str, data: Vec<u8>) -> io::Result<()>;
+    /// Read a whole object.
+    async fn get(&self, path: &str) -> io::Result<StorageObject>;
+    /// Read an object from `start` up to and including `end`, or the end of the object if `end`
+    /// is `None` or past it.
+    async fn get_range(
+        &self,
+        path: &str,
+        start: u64,
+        end: Option<u64>,
+    ) -> io::Result<StorageObject>;
+    /// Delete an object, deleting an object that doesn't exist is not an error.
+    async fn delete(&self, path: &str) -> io::Result<()>;
+    /// Check whether an object exists.
+    async fn exists(&self, path: &str) -> io::Result<bool>;
+    /// Get the size of an object in bytes.
+    async fn size(&self, path: &str) -> io::Result<u64>;
+}
+
+/// The [`StorageBackend`] an instance is configured to use.
+pub struct Storage(Box<dyn StorageBackend>);
+
+impl Storage {
+    /// Create the [`Storage`] described by the instance's [`StorageConf`].
+    pub fn new(conf: &StorageConf) -> anyhow::Result<Self> {
+        Ok(match conf {
+            StorageConf::Local { path } => Self(Box::new(LocalStorage::new(path))),
+            StorageConf::S3 {
+                endpoint,
+                bucket,
+                region,
+                access_key,
+                secret_key,
+                path_style,
+                connect_timeout,
+                timeout,
+            } => Self(Box::new(S3Storage::new(
+                endpoint,
+                bucket,
+                region,
+                access_key,
+                secret_key,
+                *path_style,
+                Duration::from_secs(*connect_timeout),
+                Duration::from_secs(*timeout),
+            )?)),
+        })
+    }
+}
+
+impl Deref for Storage {
+    type Target = dyn StorageBackend;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_ref()
+    }
+}
+
+/// Get the first and last byte a ranged read covers in an object of `total_size` bytes.
+pub(crate) fn resolve_range(
+    start: u64,
+    end: Option<u64>,
+    total_size: u64,
+) -> io::Result<(u64, u64)> {
+    if start >= total_size || end.map(|end| end < start).unwrap_or(false) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Range {}-{} is not satisfiable for an object of {} bytes",
+                start,
+                end.map(|end| end.to_string()).unwrap_or_default(),
+                total_size
+            ),
+        ));
+    }
+    Ok((start, end.unwrap_or(u64::MAX).min(total_size - 1)))
+}